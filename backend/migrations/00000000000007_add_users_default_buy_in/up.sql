@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN default_buy_in DECIMAL(10, 2);
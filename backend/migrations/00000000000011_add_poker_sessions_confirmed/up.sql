@@ -0,0 +1 @@
+ALTER TABLE poker_sessions ADD COLUMN confirmed BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN profit_convention VARCHAR(20);
@@ -0,0 +1 @@
+ALTER TABLE poker_sessions DROP COLUMN game_type;
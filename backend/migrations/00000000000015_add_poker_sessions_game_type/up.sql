@@ -0,0 +1 @@
+ALTER TABLE poker_sessions ADD COLUMN game_type VARCHAR(20) NOT NULL DEFAULT 'cash';
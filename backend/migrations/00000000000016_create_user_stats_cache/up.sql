@@ -0,0 +1,10 @@
+-- One row per user. session_count and sessions_updated_at record what the
+-- sessions looked like at refresh time, to tell whether the stats went stale.
+CREATE TABLE user_stats_cache (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    stats TEXT NOT NULL,
+    session_count BIGINT NOT NULL,
+    sessions_updated_at TIMESTAMPTZ,
+    profit_convention VARCHAR(20) NOT NULL,
+    refreshed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
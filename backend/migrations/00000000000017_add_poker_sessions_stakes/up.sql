@@ -0,0 +1 @@
+ALTER TABLE poker_sessions ADD COLUMN stakes VARCHAR(20);
@@ -0,0 +1 @@
+ALTER TABLE poker_sessions ADD COLUMN location VARCHAR(100);
@@ -0,0 +1 @@
+ALTER TABLE poker_sessions ADD COLUMN latitude DOUBLE PRECISION, ADD COLUMN longitude DOUBLE PRECISION;
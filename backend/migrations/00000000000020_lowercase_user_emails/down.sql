@@ -0,0 +1,2 @@
+-- The original casing of backfilled emails is not kept
+DROP INDEX users_email_lower_key;
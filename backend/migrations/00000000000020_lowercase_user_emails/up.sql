@@ -0,0 +1,30 @@
+-- Emails are matched case-insensitively from now on, so every stored address
+-- is lowercased. Two accounts whose emails differ only in case (e.g.
+-- `Bob@example.com` and `bob@example.com`) would collide on users_email_key,
+-- so they are detected up front and the migration aborts with the list of
+-- offending addresses. To resolve, keep one account per address and either
+-- move the others' poker_sessions and templates over to it and
+-- delete them (their remaining rows cascade), or give them a different
+-- email, then run the migration again.
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(lower_email, ', ' ORDER BY lower_email)
+    INTO duplicates
+    FROM (
+        SELECT LOWER(email) AS lower_email
+        FROM users
+        GROUP BY LOWER(email)
+        HAVING COUNT(*) > 1
+    ) AS dupes;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE EXCEPTION 'Cannot lowercase user emails: these addresses belong to more than one account when compared case-insensitively: %', duplicates
+            USING HINT = 'Merge each set of accounts into one (reassign poker_sessions and templates, then delete the rest) or change the extra accounts'' emails, then rerun the migration.';
+    END IF;
+END
+$$;
+
+UPDATE users SET email = LOWER(email) WHERE email <> LOWER(email);
+CREATE UNIQUE INDEX users_email_lower_key ON users (LOWER(email));
@@ -0,0 +1,9 @@
+-- Tokens logged out before they expire. A row only matters until expires_at,
+-- after which the token is rejected anyway and the row can be purged.
+CREATE TABLE revoked_tokens (
+    jti UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_revoked_tokens_expires_at ON revoked_tokens(expires_at);
@@ -0,0 +1,2 @@
+ALTER TABLE users DROP COLUMN default_game_type;
+ALTER TABLE users DROP COLUMN default_location;
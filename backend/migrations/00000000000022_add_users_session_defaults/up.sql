@@ -0,0 +1,2 @@
+ALTER TABLE users ADD COLUMN default_location VARCHAR(100);
+ALTER TABLE users ADD COLUMN default_game_type VARCHAR(20);
@@ -0,0 +1,3 @@
+-- The user an action was done to, e.g. the one an admin impersonated. Not a
+-- foreign key either, so entries outlive the account.
+ALTER TABLE audit_log ADD COLUMN target_user_id UUID;
@@ -0,0 +1,2 @@
+DROP INDEX idx_users_username_lower;
+CREATE INDEX idx_users_username_lower ON users (LOWER(username));
@@ -0,0 +1,27 @@
+-- Usernames are unique ignoring case, enforced by the database so concurrent
+-- registrations can't both pass the application check. Accounts whose
+-- usernames differ only in case (e.g. `Dave` and `dave`) are detected up
+-- front and the migration aborts with the list of offending names. Rename all
+-- but one of each set, then run the migration again.
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(lower_username, ', ' ORDER BY lower_username)
+    INTO duplicates
+    FROM (
+        SELECT LOWER(username) AS lower_username
+        FROM users
+        GROUP BY LOWER(username)
+        HAVING COUNT(*) > 1
+    ) AS dupes;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE EXCEPTION 'Cannot make usernames unique ignoring case: these names belong to more than one account when compared case-insensitively: %', duplicates
+            USING HINT = 'Rename all but one account in each set (UPDATE users SET username = ... WHERE id = ...), then rerun the migration.';
+    END IF;
+END
+$$;
+
+DROP INDEX idx_users_username_lower;
+CREATE UNIQUE INDEX idx_users_username_lower ON users (LOWER(username));
@@ -1,19 +1,25 @@
 use axum::{
     Json, Router,
     extract::State,
-    http::StatusCode,
+    http::{HeaderName, StatusCode, header},
     response::{IntoResponse, Response},
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
 };
+use diesel::migration::MigrationSource;
+use diesel::pg::Pg;
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
-use handlers::{auth, poker_session};
-use middleware::AuthLayer;
-use utils::establish_connection_pool;
+use handlers::{admin, api_key, auth, poker_session, stats, template};
+use middleware::{
+    AuthLayer, api_key_auth, camel_case_json, https_guard, log_request_bodies, log_request_latency,
+    maintenance_guard, pretty_json, record_db_timing, require_password_change, stats_cache_headers,
+};
+use utils::{QueryTimingDbProvider, establish_connection_pool};
 
 use diesel::RunQueryDsl;
 use diesel::sql_types::Integer;
@@ -21,27 +27,124 @@ use diesel::sql_types::Integer;
 use crate::utils::PokerTrackerConfig;
 use crate::{handlers, middleware, utils};
 
+/// Tables the app cannot work without; probed by the health check to catch a
+/// database migrated for a different version
+const EXPECTED_TABLES: [&str; 2] = ["users", "poker_sessions"];
+
 // this method is called from the /api/health route, via Axum
 async fn health(State(state): State<Arc<AppState>>) -> Response {
-    if let Ok(mut conn) = state.db_provider.get_connection()
-        && let Ok(_) = diesel::select(diesel::dsl::sql::<Integer>("1")).execute(&mut conn)
+    let Ok(mut conn) = state.db_provider.get_connection() else {
+        return health_db_failure();
+    };
+    if diesel::select(diesel::dsl::sql::<Integer>("1"))
+        .execute(&mut conn)
+        .is_err()
     {
-        (
-            StatusCode::OK,
-            Json(serde_json::json!({
-                "status": "Ok"
-            })),
-        )
-            .into_response()
-    } else {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
+        return health_db_failure();
+    }
+
+    let missing_tables: Vec<&str> = EXPECTED_TABLES
+        .into_iter()
+        .filter(|table| {
+            diesel::sql_query(format!("SELECT 1 FROM {} LIMIT 0", table))
+                .execute(&mut conn)
+                .is_err()
+        })
+        .collect();
+
+    if !missing_tables.is_empty() {
+        tracing::error!(
+            ?missing_tables,
+            "database schema is missing expected tables"
+        );
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
             Json(serde_json::json!({
-                "error": "Database connection failed"
+                "error": "Database schema is missing expected tables",
+                "missing_tables": missing_tables
             })),
         )
-            .into_response()
+            .into_response();
     }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": "Ok"
+        })),
+    )
+        .into_response()
+}
+
+fn health_db_failure() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({
+            "error": "Database connection failed"
+        })),
+    )
+        .into_response()
+}
+
+// this method is called from the /api/health/live route, via Axum; it only
+// reports that the process is up, without touching the database
+async fn liveness() -> Response {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": "Ok"
+        })),
+    )
+        .into_response()
+}
+
+// this method is called from the /api/version route, via Axum
+async fn version(State(state): State<Arc<AppState>>) -> Response {
+    let build_timestamp = env!("BUILD_TIMESTAMP")
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map(|ts| ts.to_rfc3339());
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "git_commit": env!("GIT_COMMIT"),
+            "build_timestamp": build_timestamp,
+            "schema": schema_status(&state),
+        })),
+    )
+        .into_response()
+}
+
+/// Applied migration count and the newest applied migration's name, or
+/// `null` when the database can't be asked (the version is still reported)
+fn schema_status(state: &AppState) -> serde_json::Value {
+    let Ok(mut conn) = state.db_provider.get_connection() else {
+        return serde_json::Value::Null;
+    };
+    let Ok(applied) = conn.applied_migrations() else {
+        return serde_json::Value::Null;
+    };
+
+    let latest_migration = applied.iter().max().map(|latest| {
+        MigrationSource::<Pg>::migrations(&MIGRATIONS)
+            .ok()
+            .and_then(|migrations| {
+                migrations
+                    .iter()
+                    .find(|migration| migration.name().version() == *latest)
+                    .map(|migration| migration.name().to_string())
+            })
+            // Applied by a newer binary, so only the version is known here
+            .unwrap_or_else(|| latest.to_string())
+    });
+
+    serde_json::json!({
+        "applied_migrations": applied.len(),
+        "latest_migration": latest_migration,
+    })
 }
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
@@ -50,8 +153,45 @@ pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 pub struct AppState {
     pub db_provider: Arc<dyn utils::DbProvider>,
     pub config: PokerTrackerConfig,
+    maintenance_mode: AtomicBool,
+}
+
+impl AppState {
+    pub fn new(db_provider: Arc<dyn utils::DbProvider>, config: PokerTrackerConfig) -> Self {
+        let maintenance_mode = AtomicBool::new(config.maintenance_mode);
+        let db_provider: Arc<dyn utils::DbProvider> = if config.debug_timing {
+            Arc::new(QueryTimingDbProvider::new(db_provider))
+        } else {
+            db_provider
+        };
+        AppState {
+            db_provider,
+            config,
+            maintenance_mode,
+        }
+    }
+
+    pub fn is_maintenance_mode(&self) -> bool {
+        self.maintenance_mode.load(Ordering::Relaxed)
+    }
+
+    pub fn set_maintenance_mode(&self, enabled: bool) {
+        self.maintenance_mode.store(enabled, Ordering::Relaxed);
+    }
 }
 
+/// Response headers browser clients may read from a cross-origin `fetch`,
+/// e.g. the export filename and the byte range of a resumed download
+const EXPOSED_HEADERS: [HeaderName; 7] = [
+    header::CONTENT_DISPOSITION,
+    header::CONTENT_RANGE,
+    header::ACCEPT_RANGES,
+    header::RETRY_AFTER,
+    HeaderName::from_static("x-db-queries"),
+    HeaderName::from_static("x-db-time-ms"),
+    HeaderName::from_static(poker_session::EXPORT_NEXT_CURSOR_HEADER),
+];
+
 /// Create the application router with the given state.
 pub fn create_app_router(state: Arc<AppState>) -> Router {
     // Configure CORS
@@ -59,36 +199,178 @@ pub fn create_app_router(state: Arc<AppState>) -> Router {
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any)
+        .expose_headers(EXPOSED_HEADERS)
         .max_age(std::time::Duration::from_secs(3600));
 
     let jwt_secret = state.config.jwt_secret.clone();
+    let jwt_leeway_secs = state.config.jwt_leeway_secs;
+    let slow_request_threshold =
+        std::time::Duration::from_millis(state.config.slow_request_threshold_ms);
 
-    Router::new()
+    let router = Router::new()
         .route("/api/health", get(health))
+        .route("/api/health/live", get(liveness))
+        .route("/api/version", get(version))
         // Public auth routes
         .route("/api/auth/register", post(auth::register))
         .route("/api/auth/login", post(auth::login))
         // Protected auth routes
-        .route("/api/auth/me", get(auth::get_me))
+        .route("/api/auth/me", get(auth::get_me).put(auth::update_profile))
         .route("/api/auth/cookie-consent", put(auth::update_cookie_consent))
         .route("/api/auth/change-password", post(auth::change_password))
+        .route("/api/auth/refresh", post(auth::refresh_token))
+        .route("/api/auth/logout", post(auth::logout))
+        .route(
+            "/api/auth/api-keys",
+            post(api_key::create_api_key).get(api_key::list_api_keys),
+        )
+        .route("/api/auth/api-keys/{id}", delete(api_key::revoke_api_key))
+        // Admin routes
+        .route("/api/admin/users", post(admin::create_user))
+        .route("/api/admin/export", get(admin::export_all_sessions))
+        .route("/api/admin/audit", get(admin::get_audit_log))
+        .route("/api/admin/maintenance", put(admin::set_maintenance_mode))
+        .route(
+            "/api/admin/impersonate/{user_id}",
+            post(admin::impersonate_user),
+        )
+        // Notes templates
+        .route(
+            "/api/templates",
+            post(template::create_template).get(template::list_templates),
+        )
         // Protected session routes
         .route(
             "/api/sessions",
-            post(poker_session::create_session).get(poker_session::get_sessions),
+            post(poker_session::create_session)
+                .get(poker_session::get_sessions)
+                .delete(poker_session::delete_sessions_in_range),
         )
         .route("/api/sessions/export", get(poker_session::export_sessions))
+        .route(
+            "/api/sessions/recent",
+            get(poker_session::get_recent_sessions),
+        )
+        .route("/api/sessions/stats", get(stats::get_stats))
+        .route(
+            "/api/sessions/stats/refresh",
+            post(stats::refresh_stats_cache),
+        )
+        .route(
+            "/api/sessions/stats/by-category",
+            get(stats::get_stats_by_category),
+        )
+        .route("/api/sessions/stats/by-hour", get(stats::get_stats_by_hour))
+        .route("/api/sessions/stats/rolling", get(stats::get_rolling))
+        .route("/api/sessions/stats/dashboard", get(stats::get_dashboard))
+        .route(
+            "/api/sessions/stats/weekday-vs-weekend",
+            get(stats::get_weekday_vs_weekend),
+        )
+        .route(
+            "/api/sessions/stats/by-buyin-bucket",
+            get(stats::get_buy_in_buckets),
+        )
+        .route("/api/sessions/stats/rankings", get(stats::get_rankings))
+        .route("/api/sessions/stats/risk", get(stats::get_risk))
+        .route("/api/sessions/stats/kelly", get(stats::get_kelly))
+        .route("/api/sessions/stats/cumulative", get(stats::get_cumulative))
+        .route(
+            "/api/sessions/stats/bankroll-series",
+            get(stats::get_bankroll_series),
+        )
+        .route(
+            "/api/sessions/stats/duration-correlation",
+            get(stats::get_duration_correlation),
+        )
         .route(
             "/api/sessions/{id}",
             get(poker_session::get_session)
                 .put(poker_session::update_session)
                 .delete(poker_session::delete_session),
         )
-        // Apply middleware
-        .layer(AuthLayer::new(jwt_secret))
+        .route(
+            "/api/sessions/{id}/notes/append",
+            post(poker_session::append_notes),
+        )
+        .route(
+            "/api/sessions/bulk-update",
+            post(poker_session::bulk_update_sessions),
+        )
+        .route(
+            "/api/sessions/preview-profit",
+            post(poker_session::preview_profit),
+        )
+        .route(
+            "/api/sessions/{id}/preview-update",
+            post(poker_session::preview_update),
+        )
+        .route(
+            "/api/sessions/{id}/confirm",
+            post(poker_session::confirm_session),
+        )
+        // Apply middleware (the last layer added runs first)
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            require_password_change,
+        ))
+        .layer(AuthLayer::new(
+            jwt_secret,
+            jwt_leeway_secs,
+            state.db_provider.clone(),
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            api_key_auth,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            maintenance_guard,
+        ))
+        // Inside pretty_json, so renamed keys are still pretty-printed
+        .layer(axum::middleware::from_fn_with_state(
+            state.config.camel_case_json,
+            camel_case_json,
+        ))
+        .layer(axum::middleware::from_fn(pretty_json))
         .layer(cors)
-        .layer(TraceLayer::new_for_http())
-        .with_state(state)
+        .layer(axum::middleware::from_fn_with_state(
+            slow_request_threshold,
+            log_request_latency,
+        ))
+        .layer(TraceLayer::new_for_http());
+
+    let router = if state.config.stats_cache_max_age_secs > 0 {
+        router.layer(axum::middleware::from_fn_with_state(
+            state.config.stats_cache_max_age_secs,
+            stats_cache_headers,
+        ))
+    } else {
+        router
+    };
+
+    let router = if state.config.debug_log_bodies {
+        router.layer(axum::middleware::from_fn(log_request_bodies))
+    } else {
+        router
+    };
+
+    // Outside everything but the HTTPS guard, so queries made by the
+    // middleware above are counted too
+    let router = if state.config.debug_timing {
+        router.layer(axum::middleware::from_fn(record_db_timing))
+    } else {
+        router
+    };
+
+    // Outermost, so a plaintext request is refused before anything else runs
+    let router = if state.config.require_https && state.config.trust_proxy {
+        router.layer(axum::middleware::from_fn(https_guard))
+    } else {
+        router
+    };
+
+    router.with_state(state)
 }
 
 pub struct PokerTrackerApp {
@@ -112,11 +394,25 @@ impl PokerTrackerApp {
 
         tracing::info!("Starting server at http://{}", bind_address);
 
+        if self.config.debug_timing {
+            tracing::warn!("debug_timing is enabled; this is intended for development only");
+        }
+
+        if self.config.debug_log_bodies {
+            tracing::warn!("debug_log_bodies is enabled; this is intended for development only");
+        }
+
         // Create shared application state
-        let state = Arc::new(AppState {
-            db_provider: Arc::new(pool),
-            config: self.config.clone(),
-        });
+        let state = Arc::new(AppState::new(Arc::new(pool), self.config.clone()));
+
+        match utils::purge_expired_revocations(
+            state.db_provider.as_ref(),
+            self.config.jwt_leeway_secs,
+            self.config.purge_batch_size,
+        ) {
+            Ok(purged) => tracing::info!(purged, "Purged expired token revocations"),
+            Err(e) => tracing::warn!(error = %e, "Failed to purge expired token revocations"),
+        }
 
         // Build the router using the extracted function
         let app = create_app_router(state);
@@ -130,8 +426,12 @@ impl PokerTrackerApp {
         let listener = tokio::net::TcpListener::bind(addr).await?;
 
         // Run server
-        axum::serve(listener, app)
-            .await
-            .map_err(std::io::Error::other)
+        // Peer addresses are recorded in the audit log
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .map_err(std::io::Error::other)
     }
 }
@@ -0,0 +1,493 @@
+use axum::{
+    Extension,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use serde::Deserialize;
+use std::io;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::app::AppState;
+use crate::handlers::audit::{ClientInfo, record_targeted_audit_event};
+use crate::handlers::auth::{RegisterError, do_register_temporary_account, weak_password_response};
+use crate::handlers::json_body::JsonBody;
+use crate::models::{
+    AdminCreateUserRequest, AdminCreateUserResponse, AdminExportRow, AuditAction, AuditEntry,
+    ImpersonationResponse, PokerSession, ProfitConvention, SessionWithProfit, User,
+};
+use crate::schema::{audit_log, poker_sessions, users};
+use crate::utils::{
+    DbProvider, IMPERSONATION_TOKEN_MINUTES, check_password_strength, create_impersonation_jwt,
+};
+
+#[derive(Debug, Error)]
+pub enum AdminError {
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Admin privileges required")]
+    Forbidden,
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+/// Verify that the given user has the admin flag set
+pub fn check_admin(db_provider: &dyn DbProvider, user_id: Uuid) -> Result<(), AdminError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| AdminError::DatabaseConnection)?;
+
+    let is_admin = users::table
+        .find(user_id)
+        .select(users::is_admin)
+        .first::<bool>(&mut conn)
+        .unwrap_or(false);
+
+    if is_admin {
+        Ok(())
+    } else {
+        Err(AdminError::Forbidden)
+    }
+}
+
+fn admin_error_response(error: AdminError) -> Response {
+    match error {
+        AdminError::DatabaseConnection => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database connection failed"
+            })),
+        )
+            .into_response(),
+        AdminError::Forbidden => (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Admin privileges required"
+            })),
+        )
+            .into_response(),
+        AdminError::Database(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database operation failed"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Generate a random temporary password for admin-created accounts
+fn generate_temporary_password() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+pub async fn create_user(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    JsonBody(req): JsonBody<AdminCreateUserRequest>,
+) -> Response {
+    if let Err(e) = check_admin(state.db_provider.as_ref(), user_id) {
+        return admin_error_response(e);
+    }
+
+    if let Err(errors) = req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    let (password, temporary_password) = match req.password {
+        Some(password) => {
+            if let Err(weak) = check_password_strength(
+                &password,
+                state.config.min_password_score,
+                &[&req.email, &req.username],
+            ) {
+                return weak_password_response(weak);
+            }
+            (password, None)
+        }
+        None => {
+            let generated = generate_temporary_password();
+            (generated.clone(), Some(generated))
+        }
+    };
+
+    // Admin-issued passwords are temporary
+    let user = match do_register_temporary_account(
+        state.db_provider.as_ref(),
+        &state.config,
+        req.email,
+        req.username,
+        password,
+    ) {
+        Ok(user) => user,
+        Err(RegisterError::DuplicateEmail) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "error": "An account with this email already exists"
+                })),
+            )
+                .into_response();
+        }
+        Err(RegisterError::DuplicateUsername) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "error": "This username is already taken"
+                })),
+            )
+                .into_response();
+        }
+        Err(RegisterError::BlockedEmailDomain) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": "Registration with this email domain is not allowed"
+                })),
+            )
+                .into_response();
+        }
+        Err(RegisterError::DuplicateAccount) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "error": "An account with these details already exists"
+                })),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to create account. Please try again."
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        StatusCode::CREATED,
+        Json(AdminCreateUserResponse {
+            user,
+            temporary_password,
+        }),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MaintenanceModeRequest {
+    pub enabled: bool,
+}
+
+/// Switch maintenance mode on or off at runtime
+pub async fn set_maintenance_mode(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    JsonBody(req): JsonBody<MaintenanceModeRequest>,
+) -> Response {
+    if let Err(e) = check_admin(state.db_provider.as_ref(), user_id) {
+        return admin_error_response(e);
+    }
+
+    state.set_maintenance_mode(req.enabled);
+    tracing::warn!(enabled = req.enabled, %user_id, "maintenance mode changed");
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "maintenance_mode": state.is_maintenance_mode()
+        })),
+    )
+        .into_response()
+}
+
+pub async fn impersonate_user(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(target_user_id): Path<Uuid>,
+    client: ClientInfo,
+) -> Response {
+    if let Err(e) = check_admin(state.db_provider.as_ref(), admin_id) {
+        return admin_error_response(e);
+    }
+
+    let mut conn = match state.db_provider.get_connection() {
+        Ok(conn) => conn,
+        Err(_) => return admin_error_response(AdminError::DatabaseConnection),
+    };
+
+    let user = match users::table
+        .find(target_user_id)
+        .first::<User>(&mut conn)
+        .optional()
+    {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "User not found"
+                })),
+            )
+                .into_response();
+        }
+        Err(e) => return admin_error_response(AdminError::Database(e)),
+    };
+
+    let expires_at = Utc::now() + Duration::minutes(IMPERSONATION_TOKEN_MINUTES);
+    let token = match create_impersonation_jwt(user.id, admin_id, &state.config.jwt_secret) {
+        Ok(token) => token,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to create token"
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    tracing::warn!(%admin_id, target_user_id = %user.id, "admin impersonation token issued");
+    record_targeted_audit_event(
+        state.db_provider.clone(),
+        admin_id,
+        AuditAction::Impersonate,
+        Some(user.id),
+        client,
+    );
+
+    (
+        StatusCode::OK,
+        Json(ImpersonationResponse {
+            token,
+            user,
+            impersonated_by: admin_id,
+            expires_at,
+        }),
+    )
+        .into_response()
+}
+
+pub const DEFAULT_AUDIT_LIMIT: i64 = 100;
+pub const MAX_AUDIT_LIMIT: i64 = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    pub user_id: Option<Uuid>,
+    pub action: Option<String>,
+    /// Only entries at or after this time (RFC 3339)
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries before this time (RFC 3339)
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+/// Audit entries matching the query, newest first
+pub fn load_audit_entries(
+    db_provider: &dyn DbProvider,
+    query: &AuditQuery,
+    limit: i64,
+) -> Result<Vec<AuditEntry>, AdminError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| AdminError::DatabaseConnection)?;
+
+    let mut entries = audit_log::table.into_boxed();
+    if let Some(user_id) = query.user_id {
+        entries = entries.filter(audit_log::user_id.eq(user_id));
+    }
+    if let Some(action) = &query.action {
+        entries = entries.filter(audit_log::action.eq(action));
+    }
+    if let Some(since) = query.since {
+        entries = entries.filter(audit_log::created_at.ge(since));
+    }
+    if let Some(until) = query.until {
+        entries = entries.filter(audit_log::created_at.lt(until));
+    }
+
+    Ok(entries
+        .order(audit_log::created_at.desc())
+        .limit(limit)
+        .load::<AuditEntry>(&mut conn)?)
+}
+
+pub async fn get_audit_log(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<AuditQuery>,
+) -> Response {
+    if let Err(e) = check_admin(state.db_provider.as_ref(), user_id) {
+        return admin_error_response(e);
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT);
+    if !(1..=MAX_AUDIT_LIMIT).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("limit must be between 1 and {}", MAX_AUDIT_LIMIT)
+            })),
+        )
+            .into_response();
+    }
+
+    match load_audit_entries(state.db_provider.as_ref(), &query, limit) {
+        Ok(entries) => (StatusCode::OK, Json(entries)).into_response(),
+        Err(e) => admin_error_response(e),
+    }
+}
+
+/// Sessions are read this many at a time while exporting, which bounds the
+/// memory an export needs regardless of how many sessions exist
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+/// Load the next page of sessions across all users, ordered by owner and id,
+/// each with its owner's username and profit convention name. `after` is the
+/// `(user_id, id)` of the last session of the previous page.
+pub fn load_export_batch(
+    db_provider: &dyn DbProvider,
+    after: Option<(Uuid, Uuid)>,
+) -> Result<Vec<(PokerSession, String, Option<String>)>, AdminError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| AdminError::DatabaseConnection)?;
+
+    let mut query = poker_sessions::table
+        .inner_join(users::table)
+        .select((
+            poker_sessions::all_columns,
+            users::username,
+            users::profit_convention,
+        ))
+        .into_boxed();
+
+    if let Some((user_id, id)) = after {
+        query = query.filter(
+            poker_sessions::user_id
+                .gt(user_id)
+                .or(poker_sessions::user_id
+                    .eq(user_id)
+                    .and(poker_sessions::id.gt(id))),
+        );
+    }
+
+    Ok(query
+        .order((poker_sessions::user_id.asc(), poker_sessions::id.asc()))
+        .limit(EXPORT_BATCH_SIZE)
+        .load::<(PokerSession, String, Option<String>)>(&mut conn)?)
+}
+
+/// Send every session as NDJSON, one batch per chunk, until the sessions run
+/// out or the client goes away
+fn write_export(
+    db_provider: &dyn DbProvider,
+    break_even_epsilon: f64,
+    tx: &mpsc::Sender<Result<String, io::Error>>,
+) {
+    let mut after = None;
+    loop {
+        let batch = match load_export_batch(db_provider, after) {
+            Ok(batch) => batch,
+            Err(e) => {
+                tracing::error!(error = %e, "admin export failed");
+                let _ = tx.blocking_send(Err(io::Error::other(e.to_string())));
+                return;
+            }
+        };
+        let is_last_batch = (batch.len() as i64) < EXPORT_BATCH_SIZE;
+        after = batch
+            .last()
+            .map(|(session, _, _)| (session.user_id, session.id));
+
+        let mut chunk = String::new();
+        for (session, username, convention) in batch {
+            let convention = ProfitConvention::from_setting(convention.as_deref());
+            let row = AdminExportRow {
+                username,
+                session: SessionWithProfit::with_convention(session, convention)
+                    .with_break_even_epsilon(break_even_epsilon),
+            };
+            // Serializing plain data to a string cannot fail
+            chunk.push_str(&serde_json::to_string(&row).unwrap_or_default());
+            chunk.push('\n');
+        }
+
+        if !chunk.is_empty() && tx.blocking_send(Ok(chunk)).is_err() {
+            return;
+        }
+        if is_last_batch {
+            return;
+        }
+    }
+}
+
+/// Stream every user's sessions as NDJSON for backups
+pub async fn export_all_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Response {
+    if let Err(e) = check_admin(state.db_provider.as_ref(), user_id) {
+        return admin_error_response(e);
+    }
+
+    tracing::info!(%user_id, "admin export started");
+
+    let (tx, rx) = mpsc::channel(4);
+    let db_provider = state.db_provider.clone();
+    let break_even_epsilon = state.config.break_even_epsilon;
+    tokio::task::spawn_blocking(move || {
+        write_export(db_provider.as_ref(), break_even_epsilon, &tx)
+    });
+
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    });
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"poker-sessions-all-users.ndjson\"",
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_temporary_password_meets_minimum_length() {
+        let password = generate_temporary_password();
+        assert!(password.len() >= 8);
+    }
+
+    #[test]
+    fn test_generate_temporary_password_is_unique() {
+        assert_ne!(generate_temporary_password(), generate_temporary_password());
+    }
+}
@@ -0,0 +1,197 @@
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::app::AppState;
+use crate::handlers::audit::{ClientInfo, record_audit_event};
+use crate::handlers::json_body::JsonBody;
+use crate::models::{ApiKey, AuditAction, CreateApiKeyRequest, CreatedApiKey, NewApiKey};
+use crate::schema::api_keys;
+use crate::utils::{DbProvider, generate_api_key, hash_api_key};
+
+#[derive(Debug, Error)]
+pub enum ApiKeyError {
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("API key not found")]
+    NotFound,
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+/// Business logic for issuing an API key; the plain key is only returned here
+pub fn do_create_api_key(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    req: CreateApiKeyRequest,
+) -> Result<CreatedApiKey, ApiKeyError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| ApiKeyError::DatabaseConnection)?;
+
+    let key = generate_api_key();
+    let new_api_key = NewApiKey {
+        user_id,
+        key_hash: hash_api_key(&key),
+        label: req.label,
+        read_only: req.read_only,
+    };
+
+    let api_key = diesel::insert_into(api_keys::table)
+        .values(&new_api_key)
+        .returning(ApiKey::as_returning())
+        .get_result(&mut conn)?;
+
+    Ok(CreatedApiKey { key, api_key })
+}
+
+/// Business logic for listing the user's API keys, oldest first
+pub fn do_list_api_keys(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+) -> Result<Vec<ApiKey>, ApiKeyError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| ApiKeyError::DatabaseConnection)?;
+
+    Ok(api_keys::table
+        .filter(api_keys::user_id.eq(user_id))
+        .order(api_keys::created_at.asc())
+        .select(ApiKey::as_select())
+        .load(&mut conn)?)
+}
+
+/// Business logic for revoking one of the user's API keys
+pub fn do_revoke_api_key(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    api_key_id: Uuid,
+) -> Result<(), ApiKeyError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| ApiKeyError::DatabaseConnection)?;
+
+    let deleted = diesel::delete(
+        api_keys::table
+            .filter(api_keys::id.eq(api_key_id))
+            .filter(api_keys::user_id.eq(user_id)),
+    )
+    .execute(&mut conn)?;
+
+    if deleted == 0 {
+        return Err(ApiKeyError::NotFound);
+    }
+    Ok(())
+}
+
+/// Look up the key's owner and whether it is read-only, recording the use.
+/// Returns `None` for an unknown or revoked key.
+pub fn do_authenticate_api_key(
+    db_provider: &dyn DbProvider,
+    key: &str,
+) -> Result<Option<(Uuid, bool)>, ApiKeyError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| ApiKeyError::DatabaseConnection)?;
+
+    Ok(
+        diesel::update(api_keys::table.filter(api_keys::key_hash.eq(hash_api_key(key))))
+            .set(api_keys::last_used_at.eq(Utc::now()))
+            .returning((api_keys::user_id, api_keys::read_only))
+            .get_result::<(Uuid, bool)>(&mut conn)
+            .optional()?,
+    )
+}
+
+fn api_key_error_response(error: ApiKeyError) -> Response {
+    match error {
+        ApiKeyError::DatabaseConnection => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database connection failed"
+            })),
+        )
+            .into_response(),
+        ApiKeyError::NotFound => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "API key not found"
+            })),
+        )
+            .into_response(),
+        ApiKeyError::Database(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database operation failed"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    JsonBody(req): JsonBody<CreateApiKeyRequest>,
+) -> Response {
+    if let Err(errors) = req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    match do_create_api_key(state.db_provider.as_ref(), user_id, req) {
+        Ok(created) => (StatusCode::CREATED, Json(created)).into_response(),
+        Err(e) => api_key_error_response(e),
+    }
+}
+
+pub async fn list_api_keys(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Response {
+    match do_list_api_keys(state.db_provider.as_ref(), user_id) {
+        Ok(api_keys) => (StatusCode::OK, Json(api_keys)).into_response(),
+        Err(e) => api_key_error_response(e),
+    }
+}
+
+pub async fn revoke_api_key(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    client: ClientInfo,
+    Path(api_key_id): Path<Uuid>,
+) -> Response {
+    match do_revoke_api_key(state.db_provider.as_ref(), user_id, api_key_id) {
+        Ok(()) => {
+            record_audit_event(
+                state.db_provider.clone(),
+                user_id,
+                AuditAction::ApiKeyRevoke,
+                client,
+            );
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "message": "API key revoked"
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => api_key_error_response(e),
+    }
+}
@@ -0,0 +1,194 @@
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts},
+};
+use diesel::prelude::*;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::app::AppState;
+use crate::models::{AuditAction, NewAuditEntry};
+use crate::schema::audit_log;
+use crate::utils::DbProvider;
+
+/// Longest stored IP address (IPv6 text form) and user agent, in characters
+const MAX_IP_LENGTH: usize = 45;
+const MAX_USER_AGENT_LENGTH: usize = 500;
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+/// Where a request came from, as recorded in the audit log
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    /// Read the client from a request. `X-Forwarded-For` is only honoured
+    /// with `trust_proxy`, since without a proxy any client can set it.
+    pub fn from_parts(parts: &Parts, trust_proxy: bool) -> Self {
+        let header_value = |name| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+
+        // Behind a reverse proxy the peer is the proxy, so prefer the client
+        // it reports
+        let forwarded_for = if trust_proxy {
+            header_value(header::HeaderName::from_static("x-forwarded-for"))
+                .and_then(|value| value.split(',').next())
+                .map(str::trim)
+                .filter(|ip| !ip.is_empty())
+                .map(str::to_string)
+        } else {
+            None
+        };
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+        ClientInfo {
+            ip: forwarded_for
+                .or(peer)
+                .map(|ip| ip.chars().take(MAX_IP_LENGTH).collect()),
+            user_agent: header_value(header::USER_AGENT)
+                .map(|agent| agent.chars().take(MAX_USER_AGENT_LENGTH).collect()),
+        }
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(ClientInfo::from_parts(parts, state.config.trust_proxy))
+    }
+}
+
+pub fn insert_audit_entry(
+    db_provider: &dyn DbProvider,
+    entry: NewAuditEntry,
+) -> Result<(), AuditError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| AuditError::DatabaseConnection)?;
+
+    diesel::insert_into(audit_log::table)
+        .values(&entry)
+        .execute(&mut conn)?;
+    Ok(())
+}
+
+/// Record an action in the audit log without holding up the response. This
+/// is best-effort: a failed write is logged and never fails the request.
+pub fn record_audit_event(
+    db_provider: Arc<dyn DbProvider>,
+    user_id: Uuid,
+    action: AuditAction,
+    client: ClientInfo,
+) {
+    record_targeted_audit_event(db_provider, user_id, action, None, client);
+}
+
+/// Like [`record_audit_event`], for an action `user_id` took on another user
+pub fn record_targeted_audit_event(
+    db_provider: Arc<dyn DbProvider>,
+    user_id: Uuid,
+    action: AuditAction,
+    target_user_id: Option<Uuid>,
+    client: ClientInfo,
+) {
+    tokio::task::spawn_blocking(move || {
+        let entry = NewAuditEntry {
+            user_id,
+            action: action.name().to_string(),
+            ip: client.ip,
+            user_agent: client.user_agent,
+            target_user_id,
+        };
+        if let Err(e) = insert_audit_entry(db_provider.as_ref(), entry) {
+            tracing::warn!(error = %e, %user_id, action = action.name(), "failed to write audit entry");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    fn client_info(request: Request<()>, trust_proxy: bool) -> ClientInfo {
+        let (parts, _) = request.into_parts();
+        ClientInfo::from_parts(&parts, trust_proxy)
+    }
+
+    #[test]
+    fn test_client_info_prefers_first_forwarded_address_behind_trusted_proxy() {
+        let mut request = Request::builder()
+            .header("x-forwarded-for", "203.0.113.7, 10.0.0.1")
+            .header("user-agent", "curl/8.0")
+            .body(())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+
+        let info = client_info(request, true);
+        assert_eq!(info.ip.as_deref(), Some("203.0.113.7"));
+        assert_eq!(info.user_agent.as_deref(), Some("curl/8.0"));
+    }
+
+    #[test]
+    fn test_client_info_ignores_forwarded_address_without_trusted_proxy() {
+        let mut request = Request::builder()
+            .header("x-forwarded-for", "203.0.113.7")
+            .body(())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([192, 0, 2, 1], 4000))));
+
+        let info = client_info(request, false);
+        assert_eq!(info.ip.as_deref(), Some("192.0.2.1"));
+    }
+
+    #[test]
+    fn test_client_info_falls_back_to_peer_address() {
+        let mut request = Request::builder().body(()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([192, 0, 2, 1], 4000))));
+
+        let info = client_info(request, true);
+        assert_eq!(info.ip.as_deref(), Some("192.0.2.1"));
+        assert_eq!(info.user_agent, None);
+    }
+
+    #[test]
+    fn test_client_info_truncates_long_user_agent() {
+        let request = Request::builder()
+            .header("user-agent", "x".repeat(MAX_USER_AGENT_LENGTH + 10))
+            .body(())
+            .unwrap();
+
+        let info = client_info(request, false);
+        assert_eq!(info.ip, None);
+        assert_eq!(info.user_agent.unwrap().len(), MAX_USER_AGENT_LENGTH);
+    }
+}
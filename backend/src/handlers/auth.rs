@@ -1,24 +1,33 @@
 use axum::{
     Extension,
     extract::State,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Json, Response},
 };
 use bcrypt::{hash, verify};
+use bigdecimal::{BigDecimal, FromPrimitive};
 use chrono::Utc;
 use diesel::prelude::*;
+use diesel::sql_types::{Bool, Text};
 use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::app::AppState;
+use crate::handlers::audit::{ClientInfo, record_audit_event};
+use crate::handlers::json_body::JsonBody;
+use crate::middleware::ApiKeyUser;
 use crate::models::{
-    AuthResponse, ChangePasswordRequest, LoginRequest, NewUser, RegisterRequest,
-    UpdateCookieConsent, User,
+    AuditAction, AuthResponse, ChangePasswordRequest, GameType, LoginRequest, NewUser,
+    ProfitConvention, RegisterRequest, UpdateCookieConsent, UpdateProfileRequest, User,
 };
 use crate::schema::users;
-use crate::utils::{DbProvider, create_jwt};
+use crate::utils::{
+    DbProvider, PokerTrackerConfig, RefreshError, RevocationError, WeakPassword,
+    check_password_strength, create_jwt, decode_jwt, purge_expired_revocations, redact_email,
+    refresh_jwt, revoke_jwt,
+};
 
 #[derive(Debug, Error)]
 pub enum RegisterError {
@@ -32,6 +41,8 @@ pub enum RegisterError {
     DuplicateUsername,
     #[error("Account already exists")]
     DuplicateAccount,
+    #[error("Email domain is not allowed")]
+    BlockedEmailDomain,
     #[error("Database error: {0}")]
     Database(#[from] diesel::result::Error),
 }
@@ -44,18 +55,20 @@ pub enum LoginError {
     InvalidCredentials,
 }
 
-/// Business logic for user registration
-pub fn do_register(
+/// Insert the account in one statement, so a forced password change can't be
+/// lost between creating it and flagging it
+fn insert_user(
     db_provider: &dyn DbProvider,
     bcrypt_cost: u32,
     email: String,
     username: String,
     password: String,
+    must_change_password: bool,
 ) -> Result<User, RegisterError> {
     let password_hash = hash(&password, bcrypt_cost).map_err(|_| RegisterError::PasswordHash)?;
 
     let new_user = NewUser {
-        email,
+        email: email.to_lowercase(),
         username,
         password_hash,
     };
@@ -65,27 +78,119 @@ pub fn do_register(
         .map_err(|_| RegisterError::DatabaseConnection)?;
 
     diesel::insert_into(users::table)
-        .values(&new_user)
+        .values((
+            &new_user,
+            users::must_change_password.eq(must_change_password),
+        ))
         .get_result::<User>(&mut conn)
-        .map_err(|e| match e {
-            diesel::result::Error::DatabaseError(
-                diesel::result::DatabaseErrorKind::UniqueViolation,
-                info,
-            ) => {
-                let message = info.message();
-                if message.contains("email") {
-                    RegisterError::DuplicateEmail
-                } else if message.contains("username") {
-                    RegisterError::DuplicateUsername
-                } else {
-                    RegisterError::DuplicateAccount
-                }
-            }
-            other => RegisterError::Database(other),
-        })
+        .map_err(register_error_from_diesel)
+}
+
+/// Classify a failed user insert. Duplicates are recognized by SQLSTATE
+/// (diesel's `UniqueViolation`) and constraint name only, never by the error
+/// message, which Postgres translates according to `lc_messages`.
+pub fn register_error_from_diesel(error: diesel::result::Error) -> RegisterError {
+    match error {
+        diesel::result::Error::DatabaseError(
+            diesel::result::DatabaseErrorKind::UniqueViolation,
+            info,
+        ) => duplicate_error_for_constraint(info.constraint_name()),
+        other => RegisterError::Database(other),
+    }
+}
+
+/// Business logic for user registration, honoring `blocked_email_domains` and
+/// `lowercase_usernames`. The email is stored lowercased, as login matches it
+/// case-insensitively. The username must not match an existing one in any
+/// case, and with `lowercase_usernames` on it is stored lowercased.
+pub fn do_register_with_config(
+    db_provider: &dyn DbProvider,
+    config: &PokerTrackerConfig,
+    email: String,
+    username: String,
+    password: String,
+) -> Result<User, RegisterError> {
+    register_with_config(db_provider, config, email, username, password, false)
+}
+
+/// [`do_register_with_config`] for an account created by an admin, whose
+/// password is temporary: the user is created already flagged with
+/// `must_change_password`
+pub fn do_register_temporary_account(
+    db_provider: &dyn DbProvider,
+    config: &PokerTrackerConfig,
+    email: String,
+    username: String,
+    password: String,
+) -> Result<User, RegisterError> {
+    register_with_config(db_provider, config, email, username, password, true)
+}
+
+fn register_with_config(
+    db_provider: &dyn DbProvider,
+    config: &PokerTrackerConfig,
+    email: String,
+    username: String,
+    password: String,
+    must_change_password: bool,
+) -> Result<User, RegisterError> {
+    if is_blocked_email_domain(&email, &config.blocked_email_domains) {
+        return Err(RegisterError::BlockedEmailDomain);
+    }
+
+    // A name differing from an existing one only in case is rejected by the
+    // idx_users_username_lower unique index either way
+    let username = if config.lowercase_usernames {
+        username.to_lowercase()
+    } else {
+        username
+    };
+
+    insert_user(
+        db_provider,
+        config.bcrypt_cost,
+        email,
+        username,
+        password,
+        must_change_password,
+    )
+}
+
+/// Whether the part of `email` after the last `@` is one of `blocked_domains`
+fn is_blocked_email_domain(email: &str, blocked_domains: &[String]) -> bool {
+    let Some((_, domain)) = email.trim().rsplit_once('@') else {
+        return false;
+    };
+    blocked_domains
+        .iter()
+        .any(|blocked| blocked.trim().eq_ignore_ascii_case(domain))
+}
+
+/// Unique constraints on `users`, as named by Postgres for the column-level
+/// UNIQUE declarations in the create_users migration
+const USERS_EMAIL_CONSTRAINT: &str = "users_email_key";
+const USERS_USERNAME_CONSTRAINT: &str = "users_username_key";
+/// Unique index on `LOWER(email)` from the lowercase_user_emails migration
+const USERS_EMAIL_LOWER_CONSTRAINT: &str = "users_email_lower_key";
+/// Unique index on `LOWER(username)` from the make_users_username_lower_unique
+/// migration
+const USERS_USERNAME_LOWER_CONSTRAINT: &str = "idx_users_username_lower";
+
+/// Map the constraint behind a unique violation to the conflicting field.
+/// Any other unique constraint is still a client conflict, not a server error.
+pub fn duplicate_error_for_constraint(constraint_name: Option<&str>) -> RegisterError {
+    match constraint_name {
+        Some(USERS_EMAIL_CONSTRAINT | USERS_EMAIL_LOWER_CONSTRAINT) => {
+            RegisterError::DuplicateEmail
+        }
+        Some(USERS_USERNAME_CONSTRAINT | USERS_USERNAME_LOWER_CONSTRAINT) => {
+            RegisterError::DuplicateUsername
+        }
+        _ => RegisterError::DuplicateAccount,
+    }
 }
 
-/// Business logic for user login
+/// Business logic for user login; the email matches in any case
 pub fn do_login(
     db_provider: &dyn DbProvider,
     email: String,
@@ -96,7 +201,8 @@ pub fn do_login(
         .map_err(|_| LoginError::DatabaseConnection)?;
 
     let user = users::table
-        .filter(users::email.eq(&email))
+        // Matches the users_email_lower_key functional index
+        .filter(diesel::dsl::sql::<Bool>("LOWER(email) = ").bind::<Text, _>(email.to_lowercase()))
         .first::<User>(&mut conn)
         .map_err(|_| LoginError::InvalidCredentials)?;
 
@@ -107,10 +213,34 @@ pub fn do_login(
     Ok(user)
 }
 
+/// 400 for a password below `min_password_score`, with zxcvbn's feedback
+pub fn weak_password_response(weak: WeakPassword) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": weak.to_string(),
+            "score": weak.score,
+            "warning": weak.warning,
+            "suggestions": weak.suggestions
+        })),
+    )
+        .into_response()
+}
+
 pub async fn register(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<RegisterRequest>,
+    JsonBody(req): JsonBody<RegisterRequest>,
 ) -> Response {
+    if !state.config.registration_enabled {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Registration disabled"
+            })),
+        )
+            .into_response();
+    }
+
     if let Err(errors) = req.validate() {
         return (
             StatusCode::BAD_REQUEST,
@@ -122,9 +252,19 @@ pub async fn register(
             .into_response();
     }
 
-    let user = match do_register(
+    if let Err(weak) = check_password_strength(
+        &req.password,
+        state.config.min_password_score,
+        &[&req.email, &req.username],
+    ) {
+        return weak_password_response(weak);
+    }
+
+    let redacted_email = redact_email(&req.email);
+
+    let user = match do_register_with_config(
         state.db_provider.as_ref(),
-        state.config.bcrypt_cost,
+        &state.config,
         req.email,
         req.username,
         req.password,
@@ -149,6 +289,7 @@ pub async fn register(
                 .into_response();
         }
         Err(RegisterError::DuplicateEmail) => {
+            tracing::info!(email = %redacted_email, "registration rejected: duplicate email");
             return (
                 StatusCode::CONFLICT,
                 Json(serde_json::json!({
@@ -158,6 +299,7 @@ pub async fn register(
                 .into_response();
         }
         Err(RegisterError::DuplicateUsername) => {
+            tracing::info!(email = %redacted_email, "registration rejected: duplicate username");
             return (
                 StatusCode::CONFLICT,
                 Json(serde_json::json!({
@@ -166,7 +308,28 @@ pub async fn register(
             )
                 .into_response();
         }
-        Err(RegisterError::DuplicateAccount) | Err(RegisterError::Database(_)) => {
+        Err(RegisterError::BlockedEmailDomain) => {
+            tracing::info!(email = %redacted_email, "registration rejected: blocked email domain");
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": "Registration with this email domain is not allowed"
+                })),
+            )
+                .into_response();
+        }
+        Err(RegisterError::DuplicateAccount) => {
+            tracing::info!(email = %redacted_email, "registration rejected: duplicate account");
+            return (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "error": "An account with these details already exists"
+                })),
+            )
+                .into_response();
+        }
+        Err(RegisterError::Database(e)) => {
+            tracing::error!(email = %redacted_email, error = %e, "registration failed");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({
@@ -177,7 +340,11 @@ pub async fn register(
         }
     };
 
-    let token = match create_jwt(user.id, &state.config.jwt_secret) {
+    let token = match create_jwt(
+        user.id,
+        &state.config.jwt_secret,
+        state.config.jwt_expiry_hours,
+    ) {
         Ok(t) => t,
         Err(_) => {
             return (
@@ -193,7 +360,152 @@ pub async fn register(
     (StatusCode::CREATED, Json(AuthResponse { token, user })).into_response()
 }
 
-pub async fn login(State(state): State<Arc<AppState>>, Json(req): Json<LoginRequest>) -> Response {
+pub async fn refresh_token(
+    State(state): State<Arc<AppState>>,
+    api_key_user: Option<Extension<ApiKeyUser>>,
+    headers: HeaderMap,
+) -> Response {
+    // The key, not the bearer token, authenticated the request, so the token
+    // was never checked and must not be renewed
+    if api_key_user.is_some() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "API keys cannot refresh login tokens"
+            })),
+        )
+            .into_response();
+    }
+
+    let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({
+                "error": "Invalid or missing token"
+            })),
+        )
+            .into_response();
+    };
+
+    match refresh_jwt(
+        state.db_provider.as_ref(),
+        token,
+        &state.config.jwt_secret,
+        state.config.jwt_leeway_secs,
+        state.config.jwt_expiry_hours,
+    ) {
+        Ok(token) => (StatusCode::OK, Json(serde_json::json!({ "token": token }))).into_response(),
+        Err(
+            e @ (RefreshError::InvalidToken
+            | RefreshError::UnknownUser
+            | RefreshError::Revoked
+            | RefreshError::MissingJti),
+        ) => (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        Err(e @ RefreshError::Impersonation) => (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "token refresh failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Token refresh failed"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Revoke the presented token so it can't be used again, even before it
+/// expires. Expired revocations are purged along the way.
+pub async fn logout(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    client: ClientInfo,
+    headers: HeaderMap,
+) -> Response {
+    let claims = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| {
+            decode_jwt(
+                token,
+                &state.config.jwt_secret,
+                state.config.jwt_leeway_secs,
+            )
+            .ok()
+        });
+    // API key requests pass the auth layer without a token
+    let Some(claims) = claims else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Logout requires a bearer token"
+            })),
+        )
+            .into_response();
+    };
+
+    match revoke_jwt(state.db_provider.as_ref(), &claims) {
+        Ok(_) => record_audit_event(
+            state.db_provider.clone(),
+            user_id,
+            AuditAction::Logout,
+            client,
+        ),
+        Err(e @ (RevocationError::MissingJti | RevocationError::InvalidClaims)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "logout failed");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Logout failed"
+                })),
+            )
+                .into_response();
+        }
+    }
+
+    if let Err(e) = purge_expired_revocations(
+        state.db_provider.as_ref(),
+        state.config.jwt_leeway_secs,
+        state.config.purge_batch_size,
+    ) {
+        tracing::warn!(error = %e, "failed to purge expired token revocations");
+    }
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+pub async fn login(
+    State(state): State<Arc<AppState>>,
+    client: ClientInfo,
+    JsonBody(req): JsonBody<LoginRequest>,
+) -> Response {
     if let Err(errors) = req.validate() {
         return (
             StatusCode::BAD_REQUEST,
@@ -205,6 +517,8 @@ pub async fn login(State(state): State<Arc<AppState>>, Json(req): Json<LoginRequ
             .into_response();
     }
 
+    let redacted_email = redact_email(&req.email);
+
     let user = match do_login(state.db_provider.as_ref(), req.email, req.password) {
         Ok(u) => u,
         Err(LoginError::DatabaseConnection) => {
@@ -217,6 +531,7 @@ pub async fn login(State(state): State<Arc<AppState>>, Json(req): Json<LoginRequ
                 .into_response();
         }
         Err(LoginError::InvalidCredentials) => {
+            tracing::info!(email = %redacted_email, "login failed: invalid credentials");
             return (
                 StatusCode::UNAUTHORIZED,
                 Json(serde_json::json!({
@@ -227,7 +542,11 @@ pub async fn login(State(state): State<Arc<AppState>>, Json(req): Json<LoginRequ
         }
     };
 
-    let token = match create_jwt(user.id, &state.config.jwt_secret) {
+    let token = match create_jwt(
+        user.id,
+        &state.config.jwt_secret,
+        state.config.jwt_expiry_hours,
+    ) {
         Ok(t) => t,
         Err(_) => {
             return (
@@ -240,6 +559,13 @@ pub async fn login(State(state): State<Arc<AppState>>, Json(req): Json<LoginRequ
         }
     };
 
+    record_audit_event(
+        state.db_provider.clone(),
+        user.id,
+        AuditAction::Login,
+        client,
+    );
+
     (StatusCode::OK, Json(AuthResponse { token, user })).into_response()
 }
 
@@ -272,11 +598,51 @@ pub async fn get_me(
     }
 }
 
-pub async fn update_cookie_consent(
+/// Only the fields a profile update names; `None` leaves a column alone
+#[derive(AsChangeset)]
+#[diesel(table_name = users)]
+struct ProfileChanges {
+    default_buy_in: Option<Option<BigDecimal>>,
+    profit_convention: Option<Option<&'static str>>,
+    default_location: Option<Option<String>>,
+    default_game_type: Option<Option<&'static str>>,
+    updated_at: chrono::DateTime<Utc>,
+}
+
+pub async fn update_profile(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
-    Json(consent): Json<UpdateCookieConsent>,
+    JsonBody(req): JsonBody<UpdateProfileRequest>,
 ) -> Response {
+    if let Err(errors) = req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    let Ok(default_buy_in) = req
+        .default_buy_in
+        .map(|amount| {
+            amount
+                .map(|amount| BigDecimal::from_f64(amount).ok_or(()))
+                .transpose()
+        })
+        .transpose()
+    else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Default buy-in is not a valid amount"
+            })),
+        )
+            .into_response();
+    };
+
     let mut conn = match state.db_provider.get_connection() {
         Ok(conn) => conn,
         Err(_) => {
@@ -290,21 +656,90 @@ pub async fn update_cookie_consent(
         }
     };
 
-    let consent_date = if consent.cookie_consent {
-        Some(Utc::now().naive_utc())
-    } else {
-        None
+    let changes = ProfileChanges {
+        default_buy_in,
+        profit_convention: req
+            .profit_convention
+            .map(|convention| convention.map(ProfitConvention::name)),
+        default_location: req.default_location,
+        default_game_type: req
+            .default_game_type
+            .map(|game_type| game_type.map(GameType::name)),
+        updated_at: Utc::now(),
     };
 
     match diesel::update(users::table.find(user_id))
-        .set((
-            users::cookie_consent.eq(consent.cookie_consent),
-            users::cookie_consent_date.eq(consent_date),
-            users::updated_at.eq(Utc::now().naive_utc()),
-        ))
+        .set(&changes)
         .get_result::<User>(&mut conn)
     {
         Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(diesel::result::Error::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "User not found"
+            })),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Failed to update profile"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn update_cookie_consent(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    JsonBody(consent): JsonBody<UpdateCookieConsent>,
+) -> Response {
+    let mut conn = match state.db_provider.get_connection() {
+        Ok(conn) => conn,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Database connection failed"
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    // cookie_consent_date records when consent was last granted *or* withdrawn,
+    // so a withdrawal keeps its timestamp. Repeating the current choice is a
+    // no-op and leaves the original date intact.
+    let result = conn.transaction(|conn| {
+        let user = users::table
+            .find(user_id)
+            .for_update()
+            .first::<User>(conn)?;
+
+        if user.cookie_consent == consent.cookie_consent && user.cookie_consent_date.is_some() {
+            return Ok(user);
+        }
+
+        let now = Utc::now();
+        diesel::update(users::table.find(user_id))
+            .set((
+                users::cookie_consent.eq(consent.cookie_consent),
+                users::cookie_consent_date.eq(Some(now)),
+                users::updated_at.eq(now),
+            ))
+            .get_result::<User>(conn)
+    });
+
+    match result {
+        Ok(user) => {
+            tracing::info!(
+                %user_id,
+                cookie_consent = user.cookie_consent,
+                "cookie consent updated"
+            );
+            (StatusCode::OK, Json(user)).into_response()
+        }
         Err(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({
@@ -318,7 +753,8 @@ pub async fn update_cookie_consent(
 pub async fn change_password(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
-    Json(passwords): Json<ChangePasswordRequest>,
+    client: ClientInfo,
+    JsonBody(passwords): JsonBody<ChangePasswordRequest>,
 ) -> Response {
     if let Err(errors) = passwords.validate() {
         return (
@@ -367,6 +803,14 @@ pub async fn change_password(
             .into_response();
     }
 
+    if let Err(weak) = check_password_strength(
+        &passwords.new_password,
+        state.config.min_password_score,
+        &[&user.email, &user.username],
+    ) {
+        return weak_password_response(weak);
+    }
+
     let new_password_hash = match hash(&passwords.new_password, state.config.bcrypt_cost) {
         Ok(h) => h,
         Err(_) => {
@@ -383,17 +827,26 @@ pub async fn change_password(
     match diesel::update(users::table.find(user_id))
         .set((
             users::password_hash.eq(new_password_hash),
-            users::updated_at.eq(Utc::now().naive_utc()),
+            users::must_change_password.eq(false),
+            users::updated_at.eq(Utc::now()),
         ))
         .execute(&mut conn)
     {
-        Ok(_) => (
-            StatusCode::OK,
-            Json(serde_json::json!({
-                "message": "Password changed successfully"
-            })),
-        )
-            .into_response(),
+        Ok(_) => {
+            record_audit_event(
+                state.db_provider.clone(),
+                user_id,
+                AuditAction::PasswordChange,
+                client,
+            );
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "message": "Password changed successfully"
+                })),
+            )
+                .into_response()
+        }
         Err(_) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({
@@ -403,3 +856,115 @@ pub async fn change_password(
             .into_response(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_email_constraint_maps_to_duplicate_email() {
+        assert!(matches!(
+            duplicate_error_for_constraint(Some("users_email_key")),
+            RegisterError::DuplicateEmail
+        ));
+        assert!(matches!(
+            duplicate_error_for_constraint(Some("users_email_lower_key")),
+            RegisterError::DuplicateEmail
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_username_constraint_maps_to_duplicate_username() {
+        assert!(matches!(
+            duplicate_error_for_constraint(Some("users_username_key")),
+            RegisterError::DuplicateUsername
+        ));
+        assert!(matches!(
+            duplicate_error_for_constraint(Some("idx_users_username_lower")),
+            RegisterError::DuplicateUsername
+        ));
+    }
+
+    #[test]
+    fn test_blocked_email_domain_matches_case_insensitively() {
+        let blocked = vec!["mailinator.com".to_string()];
+        assert!(is_blocked_email_domain(
+            "throwaway@Mailinator.COM",
+            &blocked
+        ));
+        assert!(!is_blocked_email_domain("player@example.com", &blocked));
+        // Only the whole domain counts, not a subdomain or a lookalike
+        assert!(!is_blocked_email_domain("a@notmailinator.com", &blocked));
+        assert!(!is_blocked_email_domain("a@mail.mailinator.com", &blocked));
+        assert!(!is_blocked_email_domain("mailinator.com", &blocked));
+    }
+
+    /// A unique violation as reported by a server with non-English messages
+    struct LocalizedUniqueViolation {
+        constraint_name: &'static str,
+    }
+
+    impl diesel::result::DatabaseErrorInformation for LocalizedUniqueViolation {
+        fn message(&self) -> &str {
+            "doppelter Schlüsselwert verletzt Unique-Constraint"
+        }
+        fn details(&self) -> Option<&str> {
+            Some("Schlüssel existiert bereits.")
+        }
+        fn hint(&self) -> Option<&str> {
+            None
+        }
+        fn table_name(&self) -> Option<&str> {
+            Some("users")
+        }
+        fn column_name(&self) -> Option<&str> {
+            None
+        }
+        fn constraint_name(&self) -> Option<&str> {
+            Some(self.constraint_name)
+        }
+        fn statement_position(&self) -> Option<i32> {
+            None
+        }
+    }
+
+    fn localized_unique_violation(constraint_name: &'static str) -> diesel::result::Error {
+        diesel::result::Error::DatabaseError(
+            diesel::result::DatabaseErrorKind::UniqueViolation,
+            Box::new(LocalizedUniqueViolation { constraint_name }),
+        )
+    }
+
+    #[test]
+    fn test_register_error_ignores_localized_message() {
+        assert!(matches!(
+            register_error_from_diesel(localized_unique_violation("users_email_key")),
+            RegisterError::DuplicateEmail
+        ));
+        assert!(matches!(
+            register_error_from_diesel(localized_unique_violation("users_username_key")),
+            RegisterError::DuplicateUsername
+        ));
+    }
+
+    #[test]
+    fn test_register_error_other_database_errors_are_not_duplicates() {
+        assert!(matches!(
+            register_error_from_diesel(diesel::result::Error::NotFound),
+            RegisterError::Database(_)
+        ));
+    }
+
+    #[test]
+    fn test_unknown_constraint_maps_to_duplicate_account() {
+        // A name that merely mentions a column must not be mistaken for it
+        assert!(matches!(
+            duplicate_error_for_constraint(Some("users_email_username_idx")),
+            RegisterError::DuplicateAccount
+        ));
+        assert!(matches!(
+            duplicate_error_for_constraint(None),
+            RegisterError::DuplicateAccount
+        ));
+    }
+}
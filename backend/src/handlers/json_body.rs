@@ -0,0 +1,160 @@
+use axum::{
+    Json,
+    extract::{FromRequest, Request, rejection::JsonRejection},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::de::{self, DeserializeOwned, Deserializer, Visitor};
+use std::sync::Arc;
+
+use crate::app::AppState;
+use crate::models::DURATION_OUT_OF_RANGE;
+
+/// A JSON request body. Behaves like axum's `Json`, except that with
+/// `strict_json` enabled, top-level fields the request type doesn't know are
+/// rejected with 400 instead of silently ignored. A `duration_minutes` too
+/// large for the database is a 400 rather than axum's generic 422.
+pub struct JsonBody<T>(pub T);
+
+impl<T: DeserializeOwned> FromRequest<Arc<AppState>> for JsonBody<T> {
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        if !state.config.strict_json {
+            let Json(value) = Json::<T>::from_request(req, state)
+                .await
+                .map_err(|rejection| match rejection {
+                    JsonRejection::JsonDataError(e)
+                        if e.body_text().contains(DURATION_OUT_OF_RANGE) =>
+                    {
+                        duration_out_of_range()
+                    }
+                    other => other.into_response(),
+                })?;
+            return Ok(JsonBody(value));
+        }
+
+        let Json(value) = Json::<serde_json::Value>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let unknown = unknown_fields(&value, struct_fields::<T>());
+        if !unknown.is_empty() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Unknown fields: {}", unknown.join(", ")),
+                    "unknown_fields": unknown
+                })),
+            )
+                .into_response());
+        }
+
+        // Same status and message as axum's `Json` for a body of the wrong shape
+        serde_json::from_value(value).map(JsonBody).map_err(|e| {
+            if e.to_string().contains(DURATION_OUT_OF_RANGE) {
+                return duration_out_of_range();
+            }
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!(
+                    "Failed to deserialize the JSON body into the target type: {}",
+                    e
+                ),
+            )
+                .into_response()
+        })
+    }
+}
+
+fn duration_out_of_range() -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": DURATION_OUT_OF_RANGE
+        })),
+    )
+        .into_response()
+}
+
+/// Keys of a JSON object that aren't among `fields`, sorted.
+/// Anything but an object, or a type whose fields are unknown, passes.
+fn unknown_fields(value: &serde_json::Value, fields: &[&str]) -> Vec<String> {
+    match value.as_object() {
+        Some(object) if !fields.is_empty() => object
+            .keys()
+            .filter(|key| !fields.contains(&key.as_str()))
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The field names `T`'s derived `Deserialize` accepts, as it passes them to
+/// `deserialize_struct`. Empty for anything that isn't a plain struct, e.g.
+/// one using `#[serde(flatten)]`.
+fn struct_fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut fields = None;
+    let _ = T::deserialize(FieldCapture(&mut fields));
+    fields.unwrap_or_default()
+}
+
+/// A deserializer that only records the field list it is asked for
+struct FieldCapture<'a>(&'a mut Option<&'static [&'static str]>);
+
+impl<'de> Deserializer<'de> for FieldCapture<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = Some(fields);
+        Err(de::Error::custom("field names captured"))
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreatePokerSessionRequest, LoginRequest};
+    use serde_json::json;
+
+    #[test]
+    fn test_struct_fields_lists_request_fields() {
+        let fields = struct_fields::<LoginRequest>();
+        assert_eq!(fields, ["email", "password"]);
+    }
+
+    #[test]
+    fn test_unknown_fields_reports_misspelled_keys() {
+        let body = json!({
+            "session_date": "2024-01-15",
+            "byu_in_amount": 100.0,
+            "duration_minutes": 60,
+            "cashout": 50.0
+        });
+        assert_eq!(
+            unknown_fields(&body, struct_fields::<CreatePokerSessionRequest>()),
+            ["byu_in_amount", "cashout"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_ignores_non_objects_and_unknown_shapes() {
+        assert!(unknown_fields(&json!([1, 2]), &["a"]).is_empty());
+        assert!(unknown_fields(&json!({"b": 1}), &[]).is_empty());
+    }
+}
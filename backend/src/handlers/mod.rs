@@ -1,2 +1,8 @@
+pub mod admin;
+pub mod api_key;
+pub mod audit;
 pub mod auth;
+pub mod json_body;
 pub mod poker_session;
+pub mod stats;
+pub mod template;
@@ -1,11 +1,12 @@
 use axum::{
     Extension,
-    extract::{Path, Query, State},
-    http::{StatusCode, header},
+    extract::{FromRequestParts, Path, Query, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header, request::Parts},
     response::{IntoResponse, Json, Response},
 };
-use bigdecimal::{BigDecimal, FromPrimitive};
+use bigdecimal::{BigDecimal, Zero};
 use chrono::{NaiveDate, Utc};
+use diesel::pg::Pg;
 use diesel::prelude::*;
 use serde::Deserialize;
 use std::sync::Arc;
@@ -14,17 +15,33 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::app::AppState;
+use crate::handlers::json_body::JsonBody;
 use crate::models::{
-    CreatePokerSessionRequest, NewPokerSession, PokerSession, SessionWithProfit,
-    UpdatePokerSessionRequest, calculate_profit,
+    AmountError, AppendNotesRequest, BulkUpdateFields, BulkUpdateRequest,
+    CreatePokerSessionRequest, GameType, NewPokerSession, PokerSession, ProfitConvention,
+    ProfitPreview, SessionWithProfit, UpdatePokerSessionRequest, append_note,
+    calculate_profit_decimal, is_rebuy_without_buy_in, normalize_category, parse_start_time,
+    resolve_amount, unconfirmed_large_loss,
 };
-use crate::schema::poker_sessions;
-use crate::utils::DbProvider;
+use crate::schema::{poker_sessions, templates, users};
+use crate::utils::{DbProvider, ExportFormat, PokerTrackerConfig, SortOrder};
 
 #[derive(Debug, Error)]
 pub enum CreateSessionError {
     #[error("Invalid date format: {0}")]
     InvalidDateFormat(String),
+    #[error("Invalid start time. Expected HH:MM")]
+    InvalidStartTime,
+    #[error("Template not found")]
+    TemplateNotFound,
+    #[error("A rebuy requires a non-zero buy-in")]
+    RebuyWithoutBuyIn,
+    #[error("A loss of {0} requires confirm_large_loss: true")]
+    UnconfirmedLargeLoss(BigDecimal),
+    #[error(transparent)]
+    InvalidAmount(#[from] AmountError),
+    #[error(transparent)]
+    NotesQuotaExceeded(#[from] NotesQuotaExceeded),
     #[error("Database connection error: {0}")]
     DatabaseConnection(String),
     #[error("Database error: {0}")]
@@ -47,6 +64,50 @@ pub enum UpdateSessionError {
     NotFound,
     #[error("Invalid date format")]
     InvalidDateFormat,
+    #[error("Invalid start time. Expected HH:MM")]
+    InvalidStartTime,
+    #[error("A rebuy requires a non-zero buy-in")]
+    RebuyWithoutBuyIn,
+    #[error("A loss of {0} requires confirm_large_loss: true")]
+    UnconfirmedLargeLoss(BigDecimal),
+    #[error(transparent)]
+    InvalidAmount(#[from] AmountError),
+    #[error(transparent)]
+    NotesQuotaExceeded(#[from] NotesQuotaExceeded),
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum AppendNotesError {
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Session not found")]
+    NotFound,
+    #[error(transparent)]
+    NotesQuotaExceeded(#[from] NotesQuotaExceeded),
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum BulkUpdateError {
+    #[error("No fields to update")]
+    NothingToUpdate,
+    #[error("At most {0} sessions can be updated at once")]
+    TooManyIds(u32),
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ConfirmSessionError {
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Session not found")]
+    NotFound,
     #[error("Database error: {0}")]
     Database(#[from] diesel::result::Error),
 }
@@ -59,31 +120,301 @@ pub enum DeleteSessionError {
     NotFound,
 }
 
+#[derive(Debug, Error)]
+pub enum DeleteRangeError {
+    #[error("Invalid date format. Use YYYY-MM-DD")]
+    InvalidDateFormat,
+    #[error("start_date must not be after end_date")]
+    InvalidRange,
+    #[error("Deleting sessions by date range requires confirm=delete")]
+    NotConfirmed,
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+#[derive(Debug, Error)]
+#[error("Notes storage limit of {max_bytes} bytes exceeded")]
+pub struct NotesQuotaExceeded {
+    /// Bytes of notes the user has stored now
+    pub current_bytes: u64,
+    pub max_bytes: u64,
+}
+
+/// Refuse notes that would take the user's stored notes past
+/// `max_total_notes_bytes`. `replaced` is the notes being overwritten, if
+/// any; changes that don't grow the total are always allowed, so users over
+/// a lowered limit can still trim their notes.
+///
+/// Locks the user's row before summing, so run it in the transaction that
+/// writes the notes: concurrent writes for the same user then can't both
+/// pass the check against the same total.
+fn check_notes_quota<E>(
+    conn: &mut PgConnection,
+    config: &PokerTrackerConfig,
+    user_id: Uuid,
+    replaced: Option<&str>,
+    notes: Option<&str>,
+) -> Result<(), E>
+where
+    E: From<NotesQuotaExceeded> + From<diesel::result::Error>,
+{
+    let Some(max_bytes) = config.max_total_notes_bytes else {
+        return Ok(());
+    };
+    let replaced_bytes = replaced.map_or(0, str::len) as u64;
+    let new_bytes = notes.map_or(0, str::len) as u64;
+    if new_bytes <= replaced_bytes {
+        return Ok(());
+    }
+
+    users::table
+        .find(user_id)
+        .select(users::id)
+        .for_update()
+        .first::<Uuid>(conn)?;
+    let current_bytes = poker_sessions::table
+        .filter(poker_sessions::user_id.eq(user_id))
+        .select(diesel::dsl::sql::<diesel::sql_types::BigInt>(
+            "COALESCE(SUM(OCTET_LENGTH(notes)), 0)",
+        ))
+        .first::<i64>(conn)? as u64;
+
+    if current_bytes.saturating_sub(replaced_bytes) + new_bytes > max_bytes {
+        return Err(NotesQuotaExceeded {
+            current_bytes,
+            max_bytes,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// 413 carrying the user's current usage, so the client can show how much
+/// to trim
+fn notes_quota_exceeded_response(exceeded: NotesQuotaExceeded) -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(serde_json::json!({
+            "error": exceeded.to_string(),
+            "current_bytes": exceeded.current_bytes,
+            "max_bytes": exceeded.max_bytes
+        })),
+    )
+        .into_response()
+}
+
+/// The `{id}` segment of the session routes. A malformed UUID is answered
+/// with a JSON 400 instead of axum's plain-text path rejection.
+pub struct SessionId(pub Uuid);
+
+impl<S: Send + Sync> FromRequestParts<S> for SessionId {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Path::<Uuid>::from_request_parts(parts, state).await {
+            Ok(Path(session_id)) => Ok(SessionId(session_id)),
+            Err(_) => Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": "invalid session id"
+                })),
+            )
+                .into_response()),
+        }
+    }
+}
+
 pub async fn do_create_session(
     db_provider: &dyn DbProvider,
+    config: &PokerTrackerConfig,
     user_id: Uuid,
     session_req: CreatePokerSessionRequest,
 ) -> Result<PokerSession, CreateSessionError> {
     let session_date = NaiveDate::parse_from_str(&session_req.session_date, "%Y-%m-%d")
         .map_err(|e| CreateSessionError::InvalidDateFormat(e.to_string()))?;
 
+    let start_time = session_req
+        .start_time
+        .as_deref()
+        .map(|value| parse_start_time(value).ok_or(CreateSessionError::InvalidStartTime))
+        .transpose()?;
+
+    let mut conn = db_provider.get_connection().map_err(|_| {
+        CreateSessionError::DatabaseConnection("Failed to get connection".to_string())
+    })?;
+
+    let buy_in_amount = resolve_amount(
+        "buy_in",
+        session_req.buy_in_amount,
+        session_req.buy_in_cents,
+    )?;
+
+    // Explicit values always win over the user's defaults
+    let (default_buy_in, default_location, default_game_type) = if buy_in_amount.is_none()
+        || session_req.location.is_none()
+        || session_req.game_type.is_none()
+    {
+        load_session_defaults(&mut conn, user_id)?
+    } else {
+        (None, None, None)
+    };
+    let buy_in_amount = buy_in_amount
+        .or(default_buy_in)
+        .ok_or(AmountError::Missing("buy_in"))?;
+    let game_type = session_req
+        .game_type
+        .or_else(|| default_game_type.as_deref().and_then(GameType::from_name))
+        .unwrap_or_default();
+    let rebuy_amount = resolve_amount("rebuy", session_req.rebuy_amount, session_req.rebuy_cents)?
+        .unwrap_or_else(BigDecimal::zero);
+    let cash_out_amount = resolve_amount(
+        "cash_out",
+        session_req.cash_out_amount,
+        session_req.cash_out_cents,
+    )?
+    .ok_or(AmountError::Missing("cash_out"))?;
+
+    // Explicit notes always win over a template
+    let notes = match (&session_req.notes, session_req.template_id) {
+        (Some(notes), _) => Some(notes.clone()),
+        (None, Some(template_id)) => Some(
+            templates::table
+                .filter(templates::id.eq(template_id))
+                .filter(templates::user_id.eq(user_id))
+                .select(templates::body)
+                .first::<String>(&mut conn)
+                .optional()?
+                .ok_or(CreateSessionError::TemplateNotFound)?,
+        ),
+        (None, None) => None,
+    };
+
     let new_session = NewPokerSession {
         user_id,
         session_date,
         duration_minutes: session_req.duration_minutes,
-        buy_in_amount: BigDecimal::from_f64(session_req.buy_in_amount).unwrap(),
-        rebuy_amount: BigDecimal::from_f64(session_req.rebuy_amount.unwrap_or(0.0)).unwrap(),
-        cash_out_amount: BigDecimal::from_f64(session_req.cash_out_amount).unwrap(),
-        notes: session_req.notes.clone(),
+        buy_in_amount,
+        rebuy_amount,
+        cash_out_amount,
+        notes,
+        category: session_req.category.as_deref().and_then(normalize_category),
+        start_time,
+        confirmed: session_req.confirmed.unwrap_or(true),
+        game_type: game_type.name().to_string(),
+        stakes: session_req.stakes.clone(),
+        location: session_req.location.clone().or(default_location),
+        latitude: session_req.latitude,
+        longitude: session_req.longitude,
     };
 
+    if config.require_buy_in_for_rebuy
+        && is_rebuy_without_buy_in(&new_session.buy_in_amount, &new_session.rebuy_amount)
+    {
+        return Err(CreateSessionError::RebuyWithoutBuyIn);
+    }
+
+    // The guard sees the loss the user will see, under their convention
+    let convention = load_profit_convention(&mut conn, user_id)?;
+    let profit = calculate_profit_decimal(
+        &new_session.buy_in_amount,
+        &convention.counted_rebuy(&new_session.rebuy_amount),
+        &new_session.cash_out_amount,
+    );
+    if let Some(loss) = unconfirmed_large_loss(
+        &profit,
+        config.large_loss_confirm_threshold,
+        session_req.confirm_large_loss,
+    ) {
+        return Err(CreateSessionError::UnconfirmedLargeLoss(loss));
+    }
+
+    conn.transaction(|conn| {
+        check_notes_quota::<CreateSessionError>(
+            conn,
+            config,
+            user_id,
+            None,
+            new_session.notes.as_deref(),
+        )?;
+
+        Ok(diesel::insert_into(poker_sessions::table)
+            .values(&new_session)
+            .get_result::<PokerSession>(conn)?)
+    })
+}
+
+/// The user's `default_buy_in`, `default_location` and `default_game_type`,
+/// filled in for whichever a new session leaves out. A missing user row has
+/// no defaults rather than failing the lookup.
+fn load_session_defaults(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> QueryResult<(Option<BigDecimal>, Option<String>, Option<String>)> {
+    users::table
+        .find(user_id)
+        .select((
+            users::default_buy_in,
+            users::default_location,
+            users::default_game_type,
+        ))
+        .first(conn)
+        .optional()
+        .map(Option::unwrap_or_default)
+}
+
+/// Business logic for previewing a create request's results without saving.
+/// Validates like [`do_create_session`], including filling a missing buy-in
+/// from the user's default, except the large-loss guard doesn't apply.
+/// Profit follows the user's convention, as the created session's does.
+pub fn do_preview_profit(
+    db_provider: &dyn DbProvider,
+    config: &PokerTrackerConfig,
+    user_id: Uuid,
+    session_req: &CreatePokerSessionRequest,
+) -> Result<ProfitPreview, CreateSessionError> {
+    NaiveDate::parse_from_str(&session_req.session_date, "%Y-%m-%d")
+        .map_err(|e| CreateSessionError::InvalidDateFormat(e.to_string()))?;
+
+    if let Some(value) = session_req.start_time.as_deref() {
+        parse_start_time(value).ok_or(CreateSessionError::InvalidStartTime)?;
+    }
+
     let mut conn = db_provider.get_connection().map_err(|_| {
         CreateSessionError::DatabaseConnection("Failed to get connection".to_string())
     })?;
 
-    Ok(diesel::insert_into(poker_sessions::table)
-        .values(&new_session)
-        .get_result::<PokerSession>(&mut conn)?)
+    let buy_in_amount = match resolve_amount(
+        "buy_in",
+        session_req.buy_in_amount,
+        session_req.buy_in_cents,
+    )? {
+        Some(buy_in_amount) => Some(buy_in_amount),
+        None => load_session_defaults(&mut conn, user_id)?.0,
+    }
+    .ok_or(AmountError::Missing("buy_in"))?;
+    let rebuy_amount = resolve_amount("rebuy", session_req.rebuy_amount, session_req.rebuy_cents)?
+        .unwrap_or_else(BigDecimal::zero);
+    let cash_out_amount = resolve_amount(
+        "cash_out",
+        session_req.cash_out_amount,
+        session_req.cash_out_cents,
+    )?
+    .ok_or(AmountError::Missing("cash_out"))?;
+
+    if config.require_buy_in_for_rebuy && is_rebuy_without_buy_in(&buy_in_amount, &rebuy_amount) {
+        return Err(CreateSessionError::RebuyWithoutBuyIn);
+    }
+
+    let convention = load_profit_convention(&mut conn, user_id)?;
+
+    Ok(ProfitPreview::new(
+        &buy_in_amount,
+        &convention.counted_rebuy(&rebuy_amount),
+        &cash_out_amount,
+        session_req.duration_minutes,
+    ))
 }
 
 /// Business logic for getting a single session
@@ -103,24 +434,14 @@ pub fn do_get_session(
         .map_err(|_| GetSessionError::NotFound)
 }
 
-/// Business logic for updating a session
-pub fn do_update_session(
-    db_provider: &dyn DbProvider,
-    session_id: Uuid,
-    user_id: Uuid,
-    update_req: UpdatePokerSessionRequest,
+/// Merge an update request into an existing session without saving it.
+/// Validates the request except for the large-loss guard, which only
+/// applies when the change is written.
+fn merge_session_update(
+    existing_session: PokerSession,
+    update_req: &UpdatePokerSessionRequest,
+    config: &PokerTrackerConfig,
 ) -> Result<PokerSession, UpdateSessionError> {
-    let mut conn = db_provider
-        .get_connection()
-        .map_err(|_| UpdateSessionError::DatabaseConnection)?;
-
-    // First verify ownership and get existing session
-    let existing_session = poker_sessions::table
-        .filter(poker_sessions::id.eq(session_id))
-        .filter(poker_sessions::user_id.eq(user_id))
-        .first::<PokerSession>(&mut conn)
-        .map_err(|_| UpdateSessionError::NotFound)?;
-
     // Parse date if provided
     let session_date = if let Some(date_str) = &update_req.session_date {
         NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
@@ -129,39 +450,286 @@ pub fn do_update_session(
         existing_session.session_date
     };
 
+    let start_time = match update_req.start_time.as_deref() {
+        Some("") => None,
+        Some(value) => Some(parse_start_time(value).ok_or(UpdateSessionError::InvalidStartTime)?),
+        None => existing_session.start_time,
+    };
+
     let duration_minutes = update_req
         .duration_minutes
         .unwrap_or(existing_session.duration_minutes);
 
-    let buy_in_amount = update_req
-        .buy_in_amount
-        .map(|v| BigDecimal::from_f64(v).unwrap())
-        .unwrap_or(existing_session.buy_in_amount);
+    let buy_in_amount =
+        resolve_amount("buy_in", update_req.buy_in_amount, update_req.buy_in_cents)?
+            .unwrap_or(existing_session.buy_in_amount);
 
-    let rebuy_amount = update_req
-        .rebuy_amount
-        .map(|v| BigDecimal::from_f64(v).unwrap())
+    let rebuy_amount = resolve_amount("rebuy", update_req.rebuy_amount, update_req.rebuy_cents)?
         .unwrap_or(existing_session.rebuy_amount);
 
-    let cash_out_amount = update_req
-        .cash_out_amount
-        .map(|v| BigDecimal::from_f64(v).unwrap())
-        .unwrap_or(existing_session.cash_out_amount);
+    let cash_out_amount = resolve_amount(
+        "cash_out",
+        update_req.cash_out_amount,
+        update_req.cash_out_cents,
+    )?
+    .unwrap_or(existing_session.cash_out_amount);
+
+    if config.require_buy_in_for_rebuy && is_rebuy_without_buy_in(&buy_in_amount, &rebuy_amount) {
+        return Err(UpdateSessionError::RebuyWithoutBuyIn);
+    }
 
     let notes = update_req.notes.clone().or(existing_session.notes);
+    let stakes = update_req.stakes.clone().or(existing_session.stakes);
+    let location = update_req.location.clone().or(existing_session.location);
+    let latitude = update_req.latitude.or(existing_session.latitude);
+    let longitude = update_req.longitude.or(existing_session.longitude);
+
+    let category = match update_req.category.as_deref() {
+        Some(category) => normalize_category(category),
+        None => existing_session.category,
+    };
 
-    diesel::update(poker_sessions::table.find(existing_session.id))
-        .set((
-            poker_sessions::session_date.eq(session_date),
-            poker_sessions::duration_minutes.eq(duration_minutes),
-            poker_sessions::buy_in_amount.eq(buy_in_amount),
-            poker_sessions::rebuy_amount.eq(rebuy_amount),
-            poker_sessions::cash_out_amount.eq(cash_out_amount),
-            poker_sessions::notes.eq(notes),
-            poker_sessions::updated_at.eq(Utc::now().naive_utc()),
-        ))
-        .get_result::<PokerSession>(&mut conn)
-        .map_err(UpdateSessionError::Database)
+    let game_type = match update_req.game_type {
+        Some(game_type) => game_type.name().to_string(),
+        None => existing_session.game_type,
+    };
+
+    Ok(PokerSession {
+        session_date,
+        duration_minutes,
+        buy_in_amount,
+        rebuy_amount,
+        cash_out_amount,
+        notes,
+        category,
+        start_time,
+        game_type,
+        stakes,
+        location,
+        latitude,
+        longitude,
+        ..existing_session
+    })
+}
+
+/// Load one of the user's sessions for an update
+fn load_owned_session(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<PokerSession, UpdateSessionError> {
+    poker_sessions::table
+        .filter(poker_sessions::id.eq(session_id))
+        .filter(poker_sessions::user_id.eq(user_id))
+        .first::<PokerSession>(conn)
+        .map_err(|_| UpdateSessionError::NotFound)
+}
+
+/// Business logic for updating a session
+pub fn do_update_session(
+    db_provider: &dyn DbProvider,
+    config: &PokerTrackerConfig,
+    session_id: Uuid,
+    user_id: Uuid,
+    update_req: UpdatePokerSessionRequest,
+) -> Result<PokerSession, UpdateSessionError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| UpdateSessionError::DatabaseConnection)?;
+
+    // Hold the user's row lock from the notes quota check through the write
+    conn.transaction(|conn| {
+        // First verify ownership and get existing session
+        let existing_session = load_owned_session(conn, session_id, user_id)?;
+        let replaced_notes = existing_session.notes.clone();
+        let session = merge_session_update(existing_session, &update_req, config)?;
+        check_notes_quota::<UpdateSessionError>(
+            conn,
+            config,
+            user_id,
+            replaced_notes.as_deref(),
+            session.notes.as_deref(),
+        )?;
+
+        // Only re-check the loss when the amounts are being edited
+        let amounts_changed = [
+            update_req.buy_in_amount.is_some() || update_req.buy_in_cents.is_some(),
+            update_req.rebuy_amount.is_some() || update_req.rebuy_cents.is_some(),
+            update_req.cash_out_amount.is_some() || update_req.cash_out_cents.is_some(),
+        ]
+        .contains(&true);
+        if amounts_changed {
+            let profit = load_profit_convention(conn, user_id)?.profit(&session);
+            if let Some(loss) = unconfirmed_large_loss(
+                &profit,
+                config.large_loss_confirm_threshold,
+                update_req.confirm_large_loss,
+            ) {
+                return Err(UpdateSessionError::UnconfirmedLargeLoss(loss));
+            }
+        }
+
+        diesel::update(poker_sessions::table.find(session.id))
+            .set((
+                poker_sessions::session_date.eq(session.session_date),
+                poker_sessions::duration_minutes.eq(session.duration_minutes),
+                poker_sessions::buy_in_amount.eq(session.buy_in_amount),
+                poker_sessions::rebuy_amount.eq(session.rebuy_amount),
+                poker_sessions::cash_out_amount.eq(session.cash_out_amount),
+                poker_sessions::notes.eq(session.notes),
+                poker_sessions::category.eq(session.category),
+                poker_sessions::start_time.eq(session.start_time),
+                poker_sessions::game_type.eq(session.game_type),
+                poker_sessions::stakes.eq(session.stakes),
+                poker_sessions::location.eq(session.location),
+                poker_sessions::latitude.eq(session.latitude),
+                poker_sessions::longitude.eq(session.longitude),
+                poker_sessions::updated_at.eq(Utc::now()),
+            ))
+            .get_result::<PokerSession>(conn)
+            .map_err(UpdateSessionError::Database)
+    })
+}
+
+/// Business logic for previewing an update's results without saving. The
+/// request is validated like [`do_update_session`], but the large-loss guard
+/// doesn't apply. Profit follows the user's convention, as the update's
+/// response does.
+pub fn do_preview_update(
+    db_provider: &dyn DbProvider,
+    config: &PokerTrackerConfig,
+    session_id: Uuid,
+    user_id: Uuid,
+    update_req: &UpdatePokerSessionRequest,
+) -> Result<ProfitPreview, UpdateSessionError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| UpdateSessionError::DatabaseConnection)?;
+
+    let existing_session = load_owned_session(&mut conn, session_id, user_id)?;
+    let session = merge_session_update(existing_session, update_req, config)?;
+    let convention = load_profit_convention(&mut conn, user_id)?;
+    let session = convention.apply(session);
+
+    Ok(ProfitPreview::new(
+        &session.buy_in_amount,
+        &session.rebuy_amount,
+        &session.cash_out_amount,
+        session.duration_minutes,
+    ))
+}
+
+/// Business logic for appending to a session's notes
+pub fn do_append_notes(
+    db_provider: &dyn DbProvider,
+    config: &PokerTrackerConfig,
+    session_id: Uuid,
+    user_id: Uuid,
+    req: AppendNotesRequest,
+) -> Result<PokerSession, AppendNotesError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| AppendNotesError::DatabaseConnection)?;
+
+    // Lock the row so concurrent appends can't overwrite each other
+    conn.transaction(|conn| {
+        let existing_session = poker_sessions::table
+            .filter(poker_sessions::id.eq(session_id))
+            .filter(poker_sessions::user_id.eq(user_id))
+            .for_update()
+            .first::<PokerSession>(conn)
+            .optional()?
+            .ok_or(AppendNotesError::NotFound)?;
+
+        let now = Utc::now();
+        let notes = append_note(
+            existing_session.notes.as_deref(),
+            &req.text,
+            req.timestamp.then_some(now),
+        );
+        check_notes_quota::<AppendNotesError>(
+            conn,
+            config,
+            user_id,
+            existing_session.notes.as_deref(),
+            Some(&notes),
+        )?;
+
+        Ok(
+            diesel::update(poker_sessions::table.find(existing_session.id))
+                .set((
+                    poker_sessions::notes.eq(notes),
+                    poker_sessions::updated_at.eq(now),
+                ))
+                .get_result::<PokerSession>(conn)?,
+        )
+    })
+}
+
+/// Business logic for setting the same fields on several of the user's
+/// sessions; ids the user doesn't own are skipped. At most `max_ids` ids
+/// are accepted, like a page of the session list. Returns how many sessions
+/// were updated.
+pub fn do_bulk_update_sessions(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    req: BulkUpdateRequest,
+    max_ids: u32,
+) -> Result<usize, BulkUpdateError> {
+    if req.ids.len() > max_ids as usize {
+        return Err(BulkUpdateError::TooManyIds(max_ids));
+    }
+    let BulkUpdateFields {
+        category,
+        location,
+        game_type,
+    } = req.set;
+    if category.is_none() && location.is_none() && game_type.is_none() {
+        return Err(BulkUpdateError::NothingToUpdate);
+    }
+
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| BulkUpdateError::DatabaseConnection)?;
+
+    // A single statement, so either every listed session changes or none do
+    Ok(diesel::update(
+        poker_sessions::table
+            .filter(poker_sessions::user_id.eq(user_id))
+            .filter(poker_sessions::id.eq_any(&req.ids)),
+    )
+    .set((
+        category.map(|category| poker_sessions::category.eq(normalize_category(&category))),
+        location
+            .map(|location| poker_sessions::location.eq(Some(location).filter(|l| !l.is_empty()))),
+        game_type.map(|game_type| poker_sessions::game_type.eq(game_type.name())),
+        poker_sessions::updated_at.eq(Utc::now()),
+    ))
+    .execute(&mut conn)?)
+}
+
+/// Business logic for marking a session's result as double-checked
+pub fn do_confirm_session(
+    db_provider: &dyn DbProvider,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<PokerSession, ConfirmSessionError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| ConfirmSessionError::DatabaseConnection)?;
+
+    diesel::update(
+        poker_sessions::table
+            .filter(poker_sessions::id.eq(session_id))
+            .filter(poker_sessions::user_id.eq(user_id)),
+    )
+    .set((
+        poker_sessions::confirmed.eq(true),
+        poker_sessions::updated_at.eq(Utc::now()),
+    ))
+    .get_result::<PokerSession>(&mut conn)
+    .optional()?
+    .ok_or(ConfirmSessionError::NotFound)
 }
 
 /// Business logic for deleting a session
@@ -189,10 +757,51 @@ pub fn do_delete_session(
     }
 }
 
+/// Value `confirm` must have for a date-range delete to go ahead
+pub const DELETE_RANGE_CONFIRM: &str = "delete";
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteRangeQuery {
+    pub start_date: String,
+    pub end_date: String,
+    pub confirm: Option<String>,
+}
+
+/// Business logic for deleting every session of the user's between two dates
+/// (inclusive); returns how many were deleted
+pub fn do_delete_sessions_in_range(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    query: &DeleteRangeQuery,
+) -> Result<usize, DeleteRangeError> {
+    let parse = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| DeleteRangeError::InvalidDateFormat)
+    };
+    let start_date = parse(&query.start_date)?;
+    let end_date = parse(&query.end_date)?;
+    if start_date > end_date {
+        return Err(DeleteRangeError::InvalidRange);
+    }
+    if query.confirm.as_deref() != Some(DELETE_RANGE_CONFIRM) {
+        return Err(DeleteRangeError::NotConfirmed);
+    }
+
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| DeleteRangeError::DatabaseConnection)?;
+
+    Ok(diesel::delete(
+        poker_sessions::table
+            .filter(poker_sessions::user_id.eq(user_id))
+            .filter(poker_sessions::session_date.between(start_date, end_date)),
+    )
+    .execute(&mut conn)?)
+}
+
 pub async fn create_session(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
-    Json(session_req): Json<CreatePokerSessionRequest>,
+    JsonBody(session_req): JsonBody<CreatePokerSessionRequest>,
 ) -> Response {
     if let Err(errors) = session_req.validate() {
         return (
@@ -205,27 +814,90 @@ pub async fn create_session(
             .into_response();
     }
 
-    match do_create_session(state.db_provider.as_ref(), user_id, session_req).await {
-        Ok(session) => {
-            let profit = calculate_profit(
-                &session.buy_in_amount,
-                &session.rebuy_amount,
-                &session.cash_out_amount,
-            );
-            (
-                StatusCode::CREATED,
-                Json(SessionWithProfit { session, profit }),
-            )
-                .into_response()
-        }
-        Err(CreateSessionError::InvalidDateFormat(msg)) => (
+    let Some(convention) = user_profit_convention(state.db_provider.as_ref(), user_id) else {
+        return profit_convention_error();
+    };
+
+    match do_create_session(
+        state.db_provider.as_ref(),
+        &state.config,
+        user_id,
+        session_req,
+    )
+    .await
+    {
+        Ok(session) => (
+            StatusCode::CREATED,
+            Json(
+                SessionWithProfit::with_convention(session, convention)
+                    .with_break_even_epsilon(state.config.break_even_epsilon),
+            ),
+        )
+            .into_response(),
+        Err(e) => create_session_error_response(e),
+    }
+}
+
+pub async fn preview_profit(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    JsonBody(session_req): JsonBody<CreatePokerSessionRequest>,
+) -> Response {
+    if let Err(errors) = session_req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    match do_preview_profit(
+        state.db_provider.as_ref(),
+        &state.config,
+        user_id,
+        &session_req,
+    ) {
+        Ok(preview) => (StatusCode::OK, Json(preview)).into_response(),
+        Err(e) => create_session_error_response(e),
+    }
+}
+
+fn create_session_error_response(error: CreateSessionError) -> Response {
+    match error {
+        CreateSessionError::InvalidDateFormat(msg) => (
             StatusCode::BAD_REQUEST,
             Json(serde_json::json!({
                 "error": format!("Invalid date format: {}", msg)
             })),
         )
             .into_response(),
-        Err(e) => (
+        e @ (CreateSessionError::InvalidStartTime | CreateSessionError::TemplateNotFound) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        CreateSessionError::RebuyWithoutBuyIn => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "A rebuy requires a non-zero buy-in"
+            })),
+        )
+            .into_response(),
+        CreateSessionError::UnconfirmedLargeLoss(loss) => unconfirmed_large_loss_response(loss),
+        CreateSessionError::NotesQuotaExceeded(exceeded) => notes_quota_exceeded_response(exceeded),
+        CreateSessionError::InvalidAmount(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        e => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({
                 "error": format!("Failed to create session: {}", e)
@@ -235,10 +907,128 @@ pub async fn create_session(
     }
 }
 
-pub async fn get_sessions(
-    State(state): State<Arc<AppState>>,
+/// 400 carrying the computed loss, so the client can ask the user to confirm
+fn unconfirmed_large_loss_response(loss: BigDecimal) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": format!("A loss of {} requires confirm_large_loss: true", loss),
+            "loss": loss
+        })),
+    )
+        .into_response()
+}
+
+/// Order a boxed session query by date, breaking ties on creation time
+fn order_by_session_date(
+    query: poker_sessions::BoxedQuery<'_, Pg>,
+    order: SortOrder,
+) -> poker_sessions::BoxedQuery<'_, Pg> {
+    match order {
+        SortOrder::Asc => query.order((
+            poker_sessions::session_date.asc(),
+            poker_sessions::created_at.asc(),
+        )),
+        SortOrder::Desc => query.order((
+            poker_sessions::session_date.desc(),
+            poker_sessions::created_at.desc(),
+        )),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionListQuery {
+    pub order: Option<SortOrder>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Only sessions with this `confirmed` state, e.g. `false` to review quick entries
+    pub confirmed: Option<bool>,
+    /// Only recent sessions; `default_time_range` when omitted
+    pub time_range: Option<String>,
+    /// Earliest session date (`YYYY-MM-DD`); with `end_date`, overrides `time_range`
+    pub start_date: Option<String>,
+    /// Latest session date (`YYYY-MM-DD`), inclusive
+    pub end_date: Option<String>,
+    /// Only sessions whose location contains this text, ignoring case
+    pub location: Option<String>,
+    /// Only cash games or only tournaments
+    pub game_type: Option<GameType>,
+    /// Only sessions played at exactly these stakes, e.g. "2/5"
+    pub stakes: Option<String>,
+}
+
+/// Escape `%`, `_` and `\` so user input matches literally in a LIKE pattern
+fn escape_like_pattern(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub async fn get_sessions(
+    State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
+    Query(query): Query<SessionListQuery>,
 ) -> Response {
+    // Never serve more than one page, however many sessions the user has
+    let max_page_size = i64::from(state.config.max_list_page_size);
+    let limit = query.limit.unwrap_or(max_page_size);
+    if !(1..=max_page_size).contains(&limit) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("limit must be between 1 and {}", max_page_size)
+            })),
+        )
+            .into_response();
+    }
+
+    let offset = query.offset.unwrap_or(0);
+    if offset < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "offset must not be negative"
+            })),
+        )
+            .into_response();
+    }
+
+    let date_range = match parse_date_range(query.start_date.as_deref(), query.end_date.as_deref())
+    {
+        Ok(date_range) => date_range,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    let time_range = query
+        .time_range
+        .as_deref()
+        .unwrap_or(&state.config.default_time_range);
+    let cutoff_date = match time_range_cutoff(Some(time_range)) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
     let mut conn = match state.db_provider.get_connection() {
         Ok(conn) => conn,
         Err(_) => {
@@ -252,19 +1042,144 @@ pub async fn get_sessions(
         }
     };
 
-    match poker_sessions::table
+    let order = query.order.unwrap_or(state.config.default_list_order);
+
+    let mut sessions_query = poker_sessions::table
         .filter(poker_sessions::user_id.eq(user_id))
-        .order(poker_sessions::session_date.desc())
-        .limit(100)
+        .into_boxed();
+    // Only filters the caller asked for count; the configured default doesn't
+    let filtered = query.confirmed.is_some()
+        || query.time_range.is_some()
+        || query.location.is_some()
+        || query.game_type.is_some()
+        || query.stakes.is_some()
+        || !date_range.is_unbounded();
+
+    if date_range.is_unbounded() {
+        if let Some(date) = cutoff_date {
+            sessions_query = sessions_query.filter(poker_sessions::session_date.ge(date));
+        }
+    } else {
+        if let Some(start) = date_range.start {
+            sessions_query = sessions_query.filter(poker_sessions::session_date.ge(start));
+        }
+        if let Some(end) = date_range.end {
+            sessions_query = sessions_query.filter(poker_sessions::session_date.le(end));
+        }
+    }
+
+    if let Some(confirmed) = query.confirmed {
+        sessions_query = sessions_query.filter(poker_sessions::confirmed.eq(confirmed));
+    }
+
+    if let Some(location) = query.location.as_deref() {
+        let pattern = format!("%{}%", escape_like_pattern(location));
+        sessions_query = sessions_query.filter(poker_sessions::location.ilike(pattern));
+    }
+
+    if let Some(game_type) = query.game_type {
+        sessions_query = sessions_query.filter(poker_sessions::game_type.eq(game_type.name()));
+    }
+
+    if let Some(stakes) = query.stakes.as_deref() {
+        sessions_query = sessions_query.filter(poker_sessions::stakes.eq(stakes));
+    }
+
+    let sessions_query = order_by_session_date(sessions_query, order);
+
+    let convention = match load_profit_convention(&mut conn, user_id) {
+        Ok(convention) => convention,
+        Err(_) => return profit_convention_error(),
+    };
+
+    match sessions_query
+        .limit(limit)
+        .offset(offset)
         .load::<PokerSession>(&mut conn)
     {
+        Ok(sessions) if sessions.is_empty() && filtered && state.config.empty_filter_not_found => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "No sessions match the filter"
+            })),
+        )
+            .into_response(),
         Ok(sessions) => {
             let sessions_with_profit: Vec<SessionWithProfit> = sessions
                 .into_iter()
-                .map(|s| {
-                    let profit =
-                        calculate_profit(&s.buy_in_amount, &s.rebuy_amount, &s.cash_out_amount);
-                    SessionWithProfit { session: s, profit }
+                .map(|session| {
+                    SessionWithProfit::with_convention(session, convention)
+                        .with_break_even_epsilon(state.config.break_even_epsilon)
+                })
+                .collect();
+            (StatusCode::OK, Json(sessions_with_profit)).into_response()
+        }
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Failed to fetch sessions"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+pub const DEFAULT_RECENT_SESSIONS: i64 = 5;
+pub const MAX_RECENT_SESSIONS: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct RecentSessionsQuery {
+    pub n: Option<i64>,
+}
+
+/// The most recent sessions, newest first, for home-screen style summaries
+pub async fn get_recent_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<RecentSessionsQuery>,
+) -> Response {
+    let n = query.n.unwrap_or(DEFAULT_RECENT_SESSIONS);
+    if !(1..=MAX_RECENT_SESSIONS).contains(&n) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("n must be between 1 and {}", MAX_RECENT_SESSIONS)
+            })),
+        )
+            .into_response();
+    }
+
+    let mut conn = match state.db_provider.get_connection() {
+        Ok(conn) => conn,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Database connection failed"
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    let sessions_query = poker_sessions::table
+        .filter(poker_sessions::user_id.eq(user_id))
+        .into_boxed();
+
+    let sessions_query = order_by_session_date(sessions_query, SortOrder::Desc);
+
+    let convention = match load_profit_convention(&mut conn, user_id) {
+        Ok(convention) => convention,
+        Err(_) => return profit_convention_error(),
+    };
+
+    match sessions_query.limit(n).load::<PokerSession>(&mut conn) {
+        Ok(sessions) => {
+            let sessions_with_profit: Vec<SessionWithProfit> = sessions
+                .into_iter()
+                .map(|session| {
+                    SessionWithProfit::with_convention(session, convention)
+                        .with_break_even_epsilon(state.config.break_even_epsilon)
                 })
                 .collect();
             (StatusCode::OK, Json(sessions_with_profit)).into_response()
@@ -282,17 +1197,21 @@ pub async fn get_sessions(
 pub async fn get_session(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
-    Path(session_id): Path<Uuid>,
+    SessionId(session_id): SessionId,
 ) -> Response {
+    let Some(convention) = user_profit_convention(state.db_provider.as_ref(), user_id) else {
+        return profit_convention_error();
+    };
+
     match do_get_session(state.db_provider.as_ref(), session_id, user_id) {
-        Ok(session) => {
-            let profit = calculate_profit(
-                &session.buy_in_amount,
-                &session.rebuy_amount,
-                &session.cash_out_amount,
-            );
-            (StatusCode::OK, Json(SessionWithProfit { session, profit })).into_response()
-        }
+        Ok(session) => (
+            StatusCode::OK,
+            Json(
+                SessionWithProfit::with_convention(session, convention)
+                    .with_break_even_epsilon(state.config.break_even_epsilon),
+            ),
+        )
+            .into_response(),
         Err(GetSessionError::DatabaseConnection) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({
@@ -313,40 +1232,284 @@ pub async fn get_session(
 pub async fn update_session(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
-    Path(session_id): Path<Uuid>,
-    Json(update_req): Json<UpdatePokerSessionRequest>,
+    SessionId(session_id): SessionId,
+    JsonBody(update_req): JsonBody<UpdatePokerSessionRequest>,
 ) -> Response {
-    match do_update_session(state.db_provider.as_ref(), session_id, user_id, update_req) {
-        Ok(session) => {
-            let profit = calculate_profit(
-                &session.buy_in_amount,
-                &session.rebuy_amount,
-                &session.cash_out_amount,
-            );
-            (StatusCode::OK, Json(SessionWithProfit { session, profit })).into_response()
-        }
-        Err(UpdateSessionError::DatabaseConnection) => (
+    if let Err(errors) = update_req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    let Some(convention) = user_profit_convention(state.db_provider.as_ref(), user_id) else {
+        return profit_convention_error();
+    };
+
+    match do_update_session(
+        state.db_provider.as_ref(),
+        &state.config,
+        session_id,
+        user_id,
+        update_req,
+    ) {
+        Ok(session) => (
+            StatusCode::OK,
+            Json(
+                SessionWithProfit::with_convention(session, convention)
+                    .with_break_even_epsilon(state.config.break_even_epsilon),
+            ),
+        )
+            .into_response(),
+        Err(e) => update_session_error_response(e),
+    }
+}
+
+pub async fn preview_update(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    SessionId(session_id): SessionId,
+    JsonBody(update_req): JsonBody<UpdatePokerSessionRequest>,
+) -> Response {
+    if let Err(errors) = update_req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    match do_preview_update(
+        state.db_provider.as_ref(),
+        &state.config,
+        session_id,
+        user_id,
+        &update_req,
+    ) {
+        Ok(preview) => (StatusCode::OK, Json(preview)).into_response(),
+        Err(e) => update_session_error_response(e),
+    }
+}
+
+fn update_session_error_response(error: UpdateSessionError) -> Response {
+    match error {
+        UpdateSessionError::DatabaseConnection => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({
                 "error": "Database connection failed"
             })),
         )
             .into_response(),
-        Err(UpdateSessionError::NotFound) => (
+        UpdateSessionError::NotFound => (
             StatusCode::NOT_FOUND,
             Json(serde_json::json!({
                 "error": "Session not found"
             })),
         )
             .into_response(),
-        Err(UpdateSessionError::InvalidDateFormat) => (
+        UpdateSessionError::InvalidDateFormat => (
             StatusCode::BAD_REQUEST,
             Json(serde_json::json!({
                 "error": "Invalid date format. Expected YYYY-MM-DD"
             })),
         )
             .into_response(),
-        Err(UpdateSessionError::Database(_)) => (
+        e @ UpdateSessionError::InvalidStartTime => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        UpdateSessionError::RebuyWithoutBuyIn => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "A rebuy requires a non-zero buy-in"
+            })),
+        )
+            .into_response(),
+        UpdateSessionError::UnconfirmedLargeLoss(loss) => unconfirmed_large_loss_response(loss),
+        UpdateSessionError::NotesQuotaExceeded(exceeded) => notes_quota_exceeded_response(exceeded),
+        UpdateSessionError::InvalidAmount(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        UpdateSessionError::Database(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Failed to update session"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn append_notes(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    SessionId(session_id): SessionId,
+    JsonBody(req): JsonBody<AppendNotesRequest>,
+) -> Response {
+    if let Err(errors) = req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    let Some(convention) = user_profit_convention(state.db_provider.as_ref(), user_id) else {
+        return profit_convention_error();
+    };
+
+    match do_append_notes(
+        state.db_provider.as_ref(),
+        &state.config,
+        session_id,
+        user_id,
+        req,
+    ) {
+        Ok(session) => (
+            StatusCode::OK,
+            Json(
+                SessionWithProfit::with_convention(session, convention)
+                    .with_break_even_epsilon(state.config.break_even_epsilon),
+            ),
+        )
+            .into_response(),
+        Err(AppendNotesError::DatabaseConnection) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database connection failed"
+            })),
+        )
+            .into_response(),
+        Err(AppendNotesError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Session not found"
+            })),
+        )
+            .into_response(),
+        Err(AppendNotesError::NotesQuotaExceeded(exceeded)) => {
+            notes_quota_exceeded_response(exceeded)
+        }
+        Err(AppendNotesError::Database(_)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Failed to update session"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn bulk_update_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    JsonBody(req): JsonBody<BulkUpdateRequest>,
+) -> Response {
+    if let Err(errors) = req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    match do_bulk_update_sessions(
+        state.db_provider.as_ref(),
+        user_id,
+        req,
+        state.config.max_list_page_size,
+    ) {
+        Ok(updated) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "updated": updated
+            })),
+        )
+            .into_response(),
+        Err(BulkUpdateError::NothingToUpdate) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "No fields to update"
+            })),
+        )
+            .into_response(),
+        Err(e @ BulkUpdateError::TooManyIds(_)) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        Err(BulkUpdateError::DatabaseConnection) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database connection failed"
+            })),
+        )
+            .into_response(),
+        Err(BulkUpdateError::Database(_)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Failed to update sessions"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn confirm_session(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    SessionId(session_id): SessionId,
+) -> Response {
+    let Some(convention) = user_profit_convention(state.db_provider.as_ref(), user_id) else {
+        return profit_convention_error();
+    };
+
+    match do_confirm_session(state.db_provider.as_ref(), session_id, user_id) {
+        Ok(session) => (
+            StatusCode::OK,
+            Json(
+                SessionWithProfit::with_convention(session, convention)
+                    .with_break_even_epsilon(state.config.break_even_epsilon),
+            ),
+        )
+            .into_response(),
+        Err(ConfirmSessionError::DatabaseConnection) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database connection failed"
+            })),
+        )
+            .into_response(),
+        Err(ConfirmSessionError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Session not found"
+            })),
+        )
+            .into_response(),
+        Err(ConfirmSessionError::Database(_)) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({
                 "error": "Failed to update session"
@@ -359,7 +1522,7 @@ pub async fn update_session(
 pub async fn delete_session(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
-    Path(session_id): Path<Uuid>,
+    SessionId(session_id): SessionId,
 ) -> Response {
     match do_delete_session(state.db_provider.as_ref(), session_id, user_id) {
         Ok(()) => (
@@ -386,16 +1549,239 @@ pub async fn delete_session(
     }
 }
 
+pub async fn delete_sessions_in_range(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<DeleteRangeQuery>,
+) -> Response {
+    match do_delete_sessions_in_range(state.db_provider.as_ref(), user_id, &query) {
+        Ok(deleted) => {
+            tracing::info!(%user_id, deleted, "sessions deleted by date range");
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "deleted": deleted
+                })),
+            )
+                .into_response()
+        }
+        Err(
+            e @ (DeleteRangeError::InvalidDateFormat
+            | DeleteRangeError::InvalidRange
+            | DeleteRangeError::NotConfirmed),
+        ) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        Err(DeleteRangeError::DatabaseConnection) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database connection failed"
+            })),
+        )
+            .into_response(),
+        Err(DeleteRangeError::Database(_)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Failed to delete sessions"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Look up the user's profit convention
+pub fn load_profit_convention(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> QueryResult<ProfitConvention> {
+    let name = users::table
+        .find(user_id)
+        .select(users::profit_convention)
+        .first::<Option<String>>(conn)
+        .optional()?
+        .flatten();
+    Ok(ProfitConvention::from_setting(name.as_deref()))
+}
+
+/// [`load_profit_convention`] on a connection of its own, for handlers that
+/// hand the database work to a `do_*` function. `None` on any failure.
+fn user_profit_convention(db_provider: &dyn DbProvider, user_id: Uuid) -> Option<ProfitConvention> {
+    let mut conn = db_provider.get_connection().ok()?;
+    load_profit_convention(&mut conn, user_id).ok()
+}
+
+fn profit_convention_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({
+            "error": "Failed to load profit convention"
+        })),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid time_range. Valid options: 7days, 30days, 90days, 1year, all")]
+pub struct InvalidTimeRange;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum DateRangeError {
+    #[error("Invalid {0} format. Use YYYY-MM-DD")]
+    InvalidDate(&'static str),
+    #[error("start_date must not be after end_date")]
+    StartAfterEnd,
+}
+
+/// An explicit `start_date`/`end_date` filter; either bound may be open
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DateRange {
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// No explicit dates were given, so `time_range` applies instead
+    pub fn is_unbounded(&self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+
+    /// Describes the range in export filenames, e.g. `2024-01-01_to_2024-12-31`
+    fn label(&self) -> String {
+        let bound = |date: Option<NaiveDate>, open: &str| {
+            date.map_or_else(|| open.to_string(), |date| date.to_string())
+        };
+        format!(
+            "{}_to_{}",
+            bound(self.start, "start"),
+            bound(self.end, "end")
+        )
+    }
+}
+
+/// Parse the `start_date` and `end_date` query values (inclusive bounds)
+pub fn parse_date_range(
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<DateRange, DateRangeError> {
+    let parse = |date: Option<&str>, name| {
+        date.map(|date| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| DateRangeError::InvalidDate(name))
+        })
+        .transpose()
+    };
+    let range = DateRange {
+        start: parse(start_date, "start_date")?,
+        end: parse(end_date, "end_date")?,
+    };
+    if range
+        .start
+        .zip(range.end)
+        .is_some_and(|(start, end)| start > end)
+    {
+        return Err(DateRangeError::StartAfterEnd);
+    }
+    Ok(range)
+}
+
+/// Convert a `time_range` query value into the earliest session date it covers
+pub fn time_range_cutoff(time_range: Option<&str>) -> Result<Option<NaiveDate>, InvalidTimeRange> {
+    let days = match time_range {
+        Some("7days") => 7,
+        Some("30days") => 30,
+        Some("90days") => 90,
+        Some("1year") => 365,
+        Some("all") | None => return Ok(None),
+        Some(_) => return Err(InvalidTimeRange),
+    };
+
+    Ok(Some(
+        Utc::now().naive_utc().date() - chrono::Duration::days(days),
+    ))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ExportQuery {
     pub time_range: Option<String>,
+    pub order: Option<SortOrder>,
+    /// `csv` (default), `json` or `geojson`
+    pub format: Option<String>,
+    /// Comma-separated CSV columns to include, in order; all when omitted
+    pub columns: Option<String>,
+    /// Strip free-text fields (notes, category, location) and coordinates,
+    /// keeping dates and amounts
+    #[serde(default)]
+    pub anonymize: bool,
+    /// Resume a capped export from this date (`YYYY-MM-DD`), as given by the
+    /// previous response's `X-Export-Next-Cursor` header
+    pub cursor: Option<String>,
+    /// Earliest session date (`YYYY-MM-DD`); with `end_date`, overrides `time_range`
+    pub start_date: Option<String>,
+    /// Latest session date (`YYYY-MM-DD`), inclusive
+    pub end_date: Option<String>,
 }
 
+/// Response header carrying the date a capped export continues from
+pub const EXPORT_NEXT_CURSOR_HEADER: &str = "x-export-next-cursor";
+
 pub async fn export_sessions(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
     Query(query): Query<ExportQuery>,
+    headers: HeaderMap,
 ) -> Response {
+    let format_name = query.format.as_deref().unwrap_or(ExportFormat::Csv.name());
+    let Some(format) = ExportFormat::from_name(format_name) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Unknown export format: {}", format_name)
+            })),
+        )
+            .into_response();
+    };
+    if !state.config.enabled_export_formats.contains(&format) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Export format {} is disabled", format.name())
+            })),
+        )
+            .into_response();
+    }
+
+    let mut columns = match (format, query.columns.as_deref()) {
+        (_, None) => CsvColumn::ALL.to_vec(),
+        (ExportFormat::Csv, Some(names)) => match parse_csv_columns(names) {
+            Ok(columns) => columns,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": e
+                    })),
+                )
+                    .into_response();
+            }
+        },
+        (_, Some(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": "columns is only supported for csv export"
+                })),
+            )
+                .into_response();
+        }
+    };
+    if query.anonymize {
+        columns.retain(|column| !column.is_free_text());
+    }
+
     let mut conn = match state.db_provider.get_connection() {
         Ok(conn) => conn,
         Err(_) => {
@@ -409,85 +1795,468 @@ pub async fn export_sessions(
         }
     };
 
+    let date_range = match parse_date_range(query.start_date.as_deref(), query.end_date.as_deref())
+    {
+        Ok(date_range) => date_range,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
     // Calculate cutoff date based on time range
-    let cutoff_date = match query.time_range.as_deref() {
-        Some("7days") => Some(Utc::now().naive_utc().date() - chrono::Duration::days(7)),
-        Some("30days") => Some(Utc::now().naive_utc().date() - chrono::Duration::days(30)),
-        Some("90days") => Some(Utc::now().naive_utc().date() - chrono::Duration::days(90)),
-        Some("1year") => Some(Utc::now().naive_utc().date() - chrono::Duration::days(365)),
-        Some("all") | None => None,
-        Some(_) => {
+    let time_range = query
+        .time_range
+        .as_deref()
+        .unwrap_or(&state.config.default_time_range);
+    let cutoff_date = match time_range_cutoff(Some(time_range)) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    let cursor = match query
+        .cursor
+        .as_deref()
+        .map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+    {
+        None => None,
+        Some(Ok(date)) => Some(date),
+        Some(Err(_)) => {
             return (
                 StatusCode::BAD_REQUEST,
                 Json(serde_json::json!({
-                    "error": "Invalid time_range. Valid options: 7days, 30days, 90days, 1year, all"
+                    "error": "Invalid cursor format. Use YYYY-MM-DD"
                 })),
             )
                 .into_response();
         }
     };
 
+    let order = query.order.unwrap_or(state.config.default_export_order);
+
     // Query sessions with optional date filter
-    let sessions: Vec<PokerSession> = match cutoff_date {
-        Some(date) => poker_sessions::table
-            .filter(poker_sessions::user_id.eq(user_id))
-            .filter(poker_sessions::session_date.ge(date))
-            .order(poker_sessions::session_date.asc())
-            .load::<PokerSession>(&mut conn),
-        None => poker_sessions::table
-            .filter(poker_sessions::user_id.eq(user_id))
-            .order(poker_sessions::session_date.asc())
-            .load::<PokerSession>(&mut conn),
+    let mut sessions_query = poker_sessions::table
+        .filter(poker_sessions::user_id.eq(user_id))
+        .into_boxed();
+
+    // Explicit dates take precedence over the time range
+    if date_range.is_unbounded() {
+        if let Some(date) = cutoff_date {
+            sessions_query = sessions_query.filter(poker_sessions::session_date.ge(date));
+        }
+    } else {
+        if let Some(start) = date_range.start {
+            sessions_query = sessions_query.filter(poker_sessions::session_date.ge(start));
+        }
+        if let Some(end) = date_range.end {
+            sessions_query = sessions_query.filter(poker_sessions::session_date.le(end));
+        }
+    }
+
+    // The cursor day itself was held back from the previous page
+    if let Some(date) = cursor {
+        sessions_query = match order {
+            SortOrder::Asc => sessions_query.filter(poker_sessions::session_date.ge(date)),
+            SortOrder::Desc => sessions_query.filter(poker_sessions::session_date.le(date)),
+        };
+    }
+
+    let mut sessions_query = order_by_session_date(sessions_query, order);
+
+    // One extra row tells whether anything is left after this page
+    if let Some(max_rows) = state.config.max_export_rows {
+        sessions_query = sessions_query.limit(i64::from(max_rows) + 1);
+    }
+
+    let mut sessions: Vec<PokerSession> = sessions_query
+        .load::<PokerSession>(&mut conn)
+        .unwrap_or_else(|_| vec![]);
+
+    let next_cursor = match state.config.max_export_rows {
+        None => None,
+        Some(max_rows) => match paginate_by_date(&mut sessions, max_rows as usize) {
+            Ok(next_cursor) => next_cursor,
+            Err(date) => {
+                return (
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    Json(serde_json::json!({
+                        "error": format!(
+                            "More than {} sessions on {}; they can't be split across export pages",
+                            max_rows, date
+                        )
+                    })),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    if query.anonymize {
+        sessions.iter_mut().for_each(anonymize_session);
     }
-    .unwrap_or_else(|_| vec![]);
 
-    // Generate CSV
-    let csv = generate_csv(&sessions);
+    let convention = match load_profit_convention(&mut conn, user_id) {
+        Ok(convention) => convention,
+        Err(_) => return profit_convention_error(),
+    };
+
+    let (content, content_type) = match format {
+        ExportFormat::Csv => (
+            generate_csv(&sessions, &columns, convention).into_bytes(),
+            "text/csv; charset=utf-8",
+        ),
+        ExportFormat::Json => (
+            generate_json(sessions, convention, state.config.break_even_epsilon),
+            "application/json",
+        ),
+        ExportFormat::Geojson => (
+            generate_geojson(&sessions, convention),
+            "application/geo+json",
+        ),
+    };
 
+    let range_label = if date_range.is_unbounded() {
+        time_range.to_string()
+    } else {
+        date_range.label()
+    };
     let filename = format!(
-        "attachment; filename=\"poker-sessions-{}.csv\"",
-        query.time_range.as_deref().unwrap_or("all")
+        "attachment; filename=\"poker-sessions-{}.{}\"",
+        sanitize_filename_component(&range_label),
+        format.name()
     );
 
-    (
-        StatusCode::OK,
-        [
-            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
-            (header::CONTENT_DISPOSITION, &filename),
-        ],
-        csv,
-    )
-        .into_response()
+    let range = headers
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+
+    // Exports are fully buffered, so byte ranges can be served for resumable downloads
+    let mut response = match parse_byte_range(range, content.len()) {
+        ByteRange::Full => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, content_type),
+                (header::CONTENT_DISPOSITION, &filename),
+                (header::ACCEPT_RANGES, "bytes"),
+            ],
+            content,
+        )
+            .into_response(),
+        ByteRange::Partial(start, end) => (
+            StatusCode::PARTIAL_CONTENT,
+            [
+                (header::CONTENT_TYPE, content_type),
+                (header::CONTENT_DISPOSITION, &filename),
+                (header::ACCEPT_RANGES, "bytes"),
+                (
+                    header::CONTENT_RANGE,
+                    &format!("bytes {}-{}/{}", start, end, content.len()),
+                ),
+            ],
+            content[start..=end].to_vec(),
+        )
+            .into_response(),
+        ByteRange::Unsatisfiable => (
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            [
+                (header::ACCEPT_RANGES, "bytes".to_string()),
+                (header::CONTENT_RANGE, format!("bytes */{}", content.len())),
+            ],
+        )
+            .into_response(),
+    };
+
+    if let Some(value) = next_cursor.and_then(|date| HeaderValue::from_str(&date.to_string()).ok())
+    {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(EXPORT_NEXT_CURSOR_HEADER), value);
+    }
+
+    response
+}
+
+/// Cut a page of up to `max_rows + 1` date-ordered sessions back to at most
+/// `max_rows`, dropping whole days so no day is split across pages. Returns
+/// the date the next page starts from, if any, or `Err` with the date when a
+/// single day alone has more than `max_rows` sessions.
+fn paginate_by_date(
+    sessions: &mut Vec<PokerSession>,
+    max_rows: usize,
+) -> Result<Option<NaiveDate>, NaiveDate> {
+    let Some(next_date) = sessions.get(max_rows).map(|session| session.session_date) else {
+        return Ok(None);
+    };
+
+    sessions.truncate(max_rows);
+    sessions.retain(|session| session.session_date != next_date);
+    if sessions.is_empty() {
+        return Err(next_date);
+    }
+    Ok(Some(next_date))
+}
+
+/// Clear the user's own words from a session, e.g. before sharing an export
+fn anonymize_session(session: &mut PokerSession) {
+    session.notes = None;
+    session.category = None;
+    session.location = None;
+    session.latitude = None;
+    session.longitude = None;
+}
+
+/// Sessions as a JSON array, in the same shape as `GET /api/sessions`
+fn generate_json(
+    sessions: Vec<PokerSession>,
+    convention: ProfitConvention,
+    break_even_epsilon: f64,
+) -> Vec<u8> {
+    let sessions: Vec<SessionWithProfit> = sessions
+        .into_iter()
+        .map(|session| {
+            SessionWithProfit::with_convention(session, convention)
+                .with_break_even_epsilon(break_even_epsilon)
+        })
+        .collect();
+    // Serializing plain data cannot fail, but never hand back an empty body:
+    // clients expect a parseable array even when there is nothing to export
+    serde_json::to_vec(&sessions).unwrap_or_else(|_| b"[]".to_vec())
+}
+
+/// Sessions with coordinates as a GeoJSON FeatureCollection, one point per
+/// session; sessions missing either coordinate are left out
+fn generate_geojson(sessions: &[PokerSession], convention: ProfitConvention) -> Vec<u8> {
+    let features: Vec<serde_json::Value> = sessions
+        .iter()
+        .filter_map(|session| {
+            let (latitude, longitude) = session.latitude.zip(session.longitude)?;
+            Some(serde_json::json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "Point",
+                    // GeoJSON positions are longitude first
+                    "coordinates": [longitude, latitude],
+                },
+                "properties": {
+                    "id": session.id,
+                    "date": session.session_date,
+                    "profit": convention.profit(session),
+                    "location": session.location,
+                },
+            }))
+        })
+        .collect();
+    serde_json::to_vec(&serde_json::json!({
+        "type": "FeatureCollection",
+        "features": features,
+    }))
+    .unwrap_or_default()
+}
+
+/// How to answer a request given its `Range` header and the body length
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    /// No range, or one we don't support (e.g. multiple ranges): send everything
+    Full,
+    /// Send the inclusive byte range `start..=end`
+    Partial(usize, usize),
+    /// The range starts past the end of the body
+    Unsatisfiable,
+}
+
+/// Parse a single `bytes=start-end`, `bytes=start-` or `bytes=-suffix` range.
+/// Malformed headers are ignored, as RFC 9110 allows.
+fn parse_byte_range(range: Option<&str>, len: usize) -> ByteRange {
+    let Some(spec) = range.and_then(|r| r.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    if start.is_empty() {
+        return match end.parse::<usize>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+            Ok(suffix) => ByteRange::Partial(len.saturating_sub(suffix), len - 1),
+            Err(_) => ByteRange::Full,
+        };
+    }
+
+    let Ok(start) = start.parse::<usize>() else {
+        return ByteRange::Full;
+    };
+    let end = if end.is_empty() {
+        None
+    } else {
+        match end.parse::<usize>() {
+            Ok(end) if end >= start => Some(end),
+            _ => return ByteRange::Full,
+        }
+    };
+
+    if start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial(start, end.map_or(len - 1, |end| end.min(len - 1)))
+}
+
+/// A column of the CSV export, selectable via `?columns=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CsvColumn {
+    Date,
+    Duration,
+    BuyIn,
+    Rebuy,
+    CashOut,
+    Profit,
+    GameType,
+    Stakes,
+    Location,
+    Notes,
 }
 
-fn generate_csv(sessions: &[PokerSession]) -> String {
-    let mut csv = String::from("Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Notes\n");
+impl CsvColumn {
+    /// Every column, in the default export order
+    const ALL: [CsvColumn; 10] = [
+        CsvColumn::Date,
+        CsvColumn::Duration,
+        CsvColumn::BuyIn,
+        CsvColumn::Rebuy,
+        CsvColumn::CashOut,
+        CsvColumn::Profit,
+        CsvColumn::GameType,
+        CsvColumn::Stakes,
+        CsvColumn::Location,
+        CsvColumn::Notes,
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|column| column.name() == name)
+    }
+
+    /// Name used in the `columns` query parameter
+    fn name(self) -> &'static str {
+        match self {
+            CsvColumn::Date => "date",
+            CsvColumn::Duration => "duration",
+            CsvColumn::BuyIn => "buy_in",
+            CsvColumn::Rebuy => "rebuy",
+            CsvColumn::CashOut => "cash_out",
+            CsvColumn::Profit => "profit",
+            CsvColumn::GameType => "game_type",
+            CsvColumn::Stakes => "stakes",
+            CsvColumn::Location => "location",
+            CsvColumn::Notes => "notes",
+        }
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            CsvColumn::Date => "Date",
+            CsvColumn::Duration => "Duration (hours)",
+            CsvColumn::BuyIn => "Buy-in",
+            CsvColumn::Rebuy => "Rebuy",
+            CsvColumn::CashOut => "Cash Out",
+            CsvColumn::Profit => "Profit/Loss",
+            CsvColumn::GameType => "Game Type",
+            CsvColumn::Stakes => "Stakes",
+            CsvColumn::Location => "Location",
+            CsvColumn::Notes => "Notes",
+        }
+    }
+
+    /// Whether the column holds text the user typed, dropped by `anonymize`
+    fn is_free_text(self) -> bool {
+        matches!(self, CsvColumn::Notes | CsvColumn::Location)
+    }
+
+    fn value(self, session: &PokerSession, convention: ProfitConvention) -> String {
+        match self {
+            CsvColumn::Date => session.session_date.to_string(),
+            CsvColumn::Duration => format!("{:.1}", session.duration_minutes as f64 / 60.0),
+            CsvColumn::BuyIn => format_money(&session.buy_in_amount),
+            CsvColumn::Rebuy => format_money(&session.rebuy_amount),
+            CsvColumn::CashOut => format_money(&session.cash_out_amount),
+            CsvColumn::Profit => format_money(&convention.profit(session)),
+            CsvColumn::GameType => session.game_type.clone(),
+            CsvColumn::Stakes => escape_csv_field(session.stakes.as_deref().unwrap_or("")),
+            CsvColumn::Location => escape_csv_field(session.location.as_deref().unwrap_or("")),
+            CsvColumn::Notes => escape_csv_field(session.notes.as_deref().unwrap_or("")),
+        }
+    }
+}
+
+/// Two decimal places, even for a zero the database hands back unscaled
+fn format_money(amount: &BigDecimal) -> String {
+    format!("{:.2}", amount)
+}
+
+/// Parse a comma-separated `columns` list, keeping the caller's order
+fn parse_csv_columns(names: &str) -> Result<Vec<CsvColumn>, String> {
+    names
+        .split(',')
+        .map(str::trim)
+        .map(|name| {
+            CsvColumn::from_name(name).ok_or_else(|| format!("Unknown export column: {}", name))
+        })
+        .collect()
+}
+
+fn generate_csv(
+    sessions: &[PokerSession],
+    columns: &[CsvColumn],
+    convention: ProfitConvention,
+) -> String {
+    let header: Vec<&str> = columns.iter().map(|column| column.header()).collect();
+    let mut csv = header.join(",");
+    csv.push('\n');
 
     for session in sessions {
-        let profit = calculate_profit(
-            &session.buy_in_amount,
-            &session.rebuy_amount,
-            &session.cash_out_amount,
-        );
-        let duration_hours = session.duration_minutes as f64 / 60.0;
-        let notes = session.notes.as_deref().unwrap_or("");
-        let escaped_notes = escape_csv_field(notes);
-
-        csv.push_str(&format!(
-            "{},{:.1},{},{},{},{:.2},{}\n",
-            session.session_date,
-            duration_hours,
-            session.buy_in_amount,
-            session.rebuy_amount,
-            session.cash_out_amount,
-            profit,
-            escaped_notes
-        ));
+        let row: Vec<String> = columns
+            .iter()
+            .map(|column| column.value(session, convention))
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
     }
 
     csv
 }
 
+/// Restrict a value interpolated into a Content-Disposition filename to a safe
+/// character set, so header injection (CR/LF, quotes) is impossible whatever
+/// the input source.
+fn sanitize_filename_component(component: &str) -> String {
+    let sanitized: String = component
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .take(64)
+        .collect();
+
+    if sanitized.is_empty() {
+        "export".to_string()
+    } else {
+        sanitized
+    }
+}
+
 fn escape_csv_field(field: &str) -> String {
     if field.contains(',') || field.contains('"') || field.contains('\n') {
         format!("\"{}\"", field.replace('"', "\"\""))
@@ -500,7 +2269,7 @@ fn escape_csv_field(field: &str) -> String {
 mod tests {
     use super::*;
     use bigdecimal::FromPrimitive;
-    use chrono::{NaiveDate, Utc};
+    use chrono::{Datelike, NaiveDate, Utc};
     use proptest::prelude::*;
 
     // CSV field escaping tests
@@ -546,15 +2315,274 @@ mod tests {
         assert_eq!(result, "\"Text, with \"\"quotes\"\" and\nnewlines\"");
     }
 
+    // LIKE pattern escaping tests
+    #[test]
+    fn test_escape_like_pattern() {
+        assert_eq!(escape_like_pattern("Bellagio"), "Bellagio");
+        assert_eq!(escape_like_pattern("100%_club\\"), "100\\%\\_club\\\\");
+    }
+
+    // Date range tests
+    #[test]
+    fn test_parse_date_range() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(parse_date_range(None, None), Ok(DateRange::default()));
+        assert_eq!(
+            parse_date_range(Some("2024-01-01"), None),
+            Ok(DateRange {
+                start: Some(date("2024-01-01")),
+                end: None
+            })
+        );
+        assert_eq!(
+            parse_date_range(Some("2024-01-01"), Some("2024-01-01")),
+            Ok(DateRange {
+                start: Some(date("2024-01-01")),
+                end: Some(date("2024-01-01"))
+            })
+        );
+        assert_eq!(
+            parse_date_range(Some("2024-13-01"), None),
+            Err(DateRangeError::InvalidDate("start_date"))
+        );
+        assert_eq!(
+            parse_date_range(None, Some("yesterday")),
+            Err(DateRangeError::InvalidDate("end_date"))
+        );
+        assert_eq!(
+            parse_date_range(Some("2024-02-01"), Some("2024-01-01")),
+            Err(DateRangeError::StartAfterEnd)
+        );
+    }
+
+    #[test]
+    fn test_date_range_label() {
+        let range = parse_date_range(Some("2024-01-01"), Some("2024-12-31")).unwrap();
+        assert_eq!(range.label(), "2024-01-01_to_2024-12-31");
+        let range = parse_date_range(None, Some("2024-12-31")).unwrap();
+        assert_eq!(range.label(), "start_to_2024-12-31");
+    }
+
+    // Filename sanitization tests
+    #[test]
+    fn test_sanitize_filename_component_keeps_safe_values() {
+        assert_eq!(sanitize_filename_component("30days"), "30days");
+        assert_eq!(sanitize_filename_component("all"), "all");
+    }
+
+    #[test]
+    fn test_sanitize_filename_component_strips_header_injection() {
+        let malicious = "7days\"\r\nSet-Cookie: session=evil; x=\"";
+        let sanitized = sanitize_filename_component(malicious);
+        assert_eq!(sanitized, "7daysSet-Cookiesessionevilx");
+
+        let header = format!("attachment; filename=\"poker-sessions-{}.csv\"", sanitized);
+        assert!(!header.contains('\r'));
+        assert!(!header.contains('\n'));
+        assert_eq!(header.matches('"').count(), 2);
+        assert!(axum::http::HeaderValue::from_str(&header).is_ok());
+    }
+
+    #[test]
+    fn test_sanitize_filename_component_empty_falls_back() {
+        assert_eq!(sanitize_filename_component(""), "export");
+        assert_eq!(sanitize_filename_component("\r\n\"/"), "export");
+    }
+
+    // Byte range tests
+    #[test]
+    fn test_parse_byte_range_forms() {
+        assert_eq!(parse_byte_range(None, 100), ByteRange::Full);
+        assert_eq!(
+            parse_byte_range(Some("bytes=0-9"), 100),
+            ByteRange::Partial(0, 9)
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=90-"), 100),
+            ByteRange::Partial(90, 99)
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=-10"), 100),
+            ByteRange::Partial(90, 99)
+        );
+        // An end past the body is clamped to the last byte
+        assert_eq!(
+            parse_byte_range(Some("bytes=50-500"), 100),
+            ByteRange::Partial(50, 99)
+        );
+    }
+
+    #[test]
+    fn test_parse_byte_range_unsatisfiable() {
+        assert_eq!(
+            parse_byte_range(Some("bytes=100-"), 100),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=-0"), 100),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=-5"), 0),
+            ByteRange::Unsatisfiable
+        );
+    }
+
+    #[test]
+    fn test_parse_byte_range_ignores_unsupported_or_malformed() {
+        assert_eq!(
+            parse_byte_range(Some("bytes=0-1,5-6"), 100),
+            ByteRange::Full
+        );
+        assert_eq!(parse_byte_range(Some("items=0-1"), 100), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("bytes=9-2"), 100), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("bytes=a-b"), 100), ByteRange::Full);
+    }
+
+    // Pagination tests
+    fn session_on(day: u32) -> PokerSession {
+        PokerSession {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            session_date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+            duration_minutes: 120,
+            buy_in_amount: BigDecimal::from(100),
+            rebuy_amount: BigDecimal::from(0),
+            cash_out_amount: BigDecimal::from(150),
+            notes: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            category: None,
+            start_time: None,
+            confirmed: true,
+            game_type: "cash".to_string(),
+            stakes: None,
+            location: None,
+            latitude: None,
+            longitude: None,
+        }
+    }
+
+    fn dates(sessions: &[PokerSession]) -> Vec<u32> {
+        sessions
+            .iter()
+            .map(|session| session.session_date.day())
+            .collect()
+    }
+
+    #[test]
+    fn test_paginate_by_date_under_cap_has_no_cursor() {
+        let mut sessions = vec![session_on(1), session_on(2)];
+        assert_eq!(paginate_by_date(&mut sessions, 2), Ok(None));
+        assert_eq!(dates(&sessions), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_paginate_by_date_keeps_days_whole() {
+        // Cap of 3, with one extra row loaded: day 2 would be split, so it
+        // moves to the next page entirely
+        let mut sessions = vec![session_on(1), session_on(2), session_on(2), session_on(2)];
+        assert_eq!(
+            paginate_by_date(&mut sessions, 3),
+            Ok(Some(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()))
+        );
+        assert_eq!(dates(&sessions), vec![1]);
+
+        let mut sessions = vec![session_on(1), session_on(2), session_on(3), session_on(4)];
+        assert_eq!(
+            paginate_by_date(&mut sessions, 3),
+            Ok(Some(NaiveDate::from_ymd_opt(2024, 1, 4).unwrap()))
+        );
+        assert_eq!(dates(&sessions), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_paginate_by_date_single_day_over_cap() {
+        let mut sessions = vec![session_on(5), session_on(5), session_on(5)];
+        assert_eq!(
+            paginate_by_date(&mut sessions, 2),
+            Err(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap())
+        );
+    }
+
+    // JSON generation tests
+    #[test]
+    fn test_generate_json_empty() {
+        let json = generate_json(vec![], ProfitConvention::default(), 0.0);
+        assert_eq!(json, b"[]");
+    }
+
     // CSV generation tests
     #[test]
     fn test_generate_csv_empty() {
         let sessions: Vec<PokerSession> = vec![];
-        let csv = generate_csv(&sessions);
+        let csv = generate_csv(&sessions, &CsvColumn::ALL, ProfitConvention::default());
         assert_eq!(
             csv,
-            "Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Notes\n"
+            "Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Game Type,Stakes,Location,Notes\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_csv_columns() {
+        assert_eq!(
+            parse_csv_columns("date, profit"),
+            Ok(vec![CsvColumn::Date, CsvColumn::Profit])
+        );
+        assert_eq!(
+            parse_csv_columns("date,profit_loss"),
+            Err("Unknown export column: profit_loss".to_string())
+        );
+        assert!(parse_csv_columns("").is_err());
+    }
+
+    #[test]
+    fn test_generate_csv_column_subset() {
+        let session = PokerSession {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            session_date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            duration_minutes: 120,
+            buy_in_amount: BigDecimal::from(100),
+            rebuy_amount: BigDecimal::from(0),
+            cash_out_amount: BigDecimal::from(150),
+            notes: Some("Good session".to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            category: None,
+            start_time: None,
+            confirmed: true,
+            game_type: "cash".to_string(),
+            stakes: None,
+            location: None,
+            latitude: None,
+            longitude: None,
+        };
+
+        let csv = generate_csv(
+            &[session],
+            &[CsvColumn::Profit, CsvColumn::Date],
+            ProfitConvention::default(),
+        );
+        assert_eq!(csv, "Profit/Loss,Date\n50.00,2024-01-15\n");
+    }
+
+    #[test]
+    fn test_generate_csv_profit_follows_convention() {
+        let mut session = session_on(15);
+        session.rebuy_amount = BigDecimal::from(40);
+        let columns = [CsvColumn::Rebuy, CsvColumn::Profit];
+
+        let standard = generate_csv(
+            std::slice::from_ref(&session),
+            &columns,
+            ProfitConvention::Standard,
         );
+        assert_eq!(standard, "Rebuy,Profit/Loss\n40.00,10.00\n");
+
+        // The rebuy is still exported; it just doesn't count against profit
+        let exclude_rebuy = generate_csv(&[session], &columns, ProfitConvention::ExcludeRebuy);
+        assert_eq!(exclude_rebuy, "Rebuy,Profit/Loss\n40.00,50.00\n");
     }
 
     #[test]
@@ -568,17 +2596,25 @@ mod tests {
             rebuy_amount: BigDecimal::from_f64(50.0).unwrap(),
             cash_out_amount: BigDecimal::from_f64(200.0).unwrap(),
             notes: Some("Good session".to_string()),
-            created_at: Utc::now().naive_utc(),
-            updated_at: Utc::now().naive_utc(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            category: None,
+            start_time: None,
+            confirmed: true,
+            game_type: "cash".to_string(),
+            stakes: None,
+            location: None,
+            latitude: None,
+            longitude: None,
         };
 
-        let csv = generate_csv(&[session]);
+        let csv = generate_csv(&[session], &CsvColumn::ALL, ProfitConvention::default());
         let lines: Vec<&str> = csv.lines().collect();
 
         assert_eq!(lines.len(), 2); // header + 1 data row
         assert_eq!(
             lines[0],
-            "Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Notes"
+            "Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Game Type,Stakes,Location,Notes"
         );
         assert!(lines[1].contains("2024-01-15"));
         assert!(lines[1].contains("2.0")); // 120 minutes = 2.0 hours
@@ -601,8 +2637,16 @@ mod tests {
                 rebuy_amount: BigDecimal::from_f64(0.0).unwrap(),
                 cash_out_amount: BigDecimal::from_f64(150.0).unwrap(),
                 notes: None,
-                created_at: Utc::now().naive_utc(),
-                updated_at: Utc::now().naive_utc(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                category: None,
+                start_time: None,
+                confirmed: true,
+                game_type: "cash".to_string(),
+                stakes: None,
+                location: None,
+                latitude: None,
+                longitude: None,
             },
             PokerSession {
                 id: Uuid::new_v4(),
@@ -613,12 +2657,20 @@ mod tests {
                 rebuy_amount: BigDecimal::from_f64(100.0).unwrap(),
                 cash_out_amount: BigDecimal::from_f64(250.0).unwrap(),
                 notes: Some("Lost session".to_string()),
-                created_at: Utc::now().naive_utc(),
-                updated_at: Utc::now().naive_utc(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                category: None,
+                start_time: None,
+                confirmed: true,
+                game_type: "cash".to_string(),
+                stakes: None,
+                location: None,
+                latitude: None,
+                longitude: None,
             },
         ];
 
-        let csv = generate_csv(&sessions);
+        let csv = generate_csv(&sessions, &CsvColumn::ALL, ProfitConvention::default());
         let lines: Vec<&str> = csv.lines().collect();
 
         assert_eq!(lines.len(), 3); // header + 2 data rows
@@ -635,11 +2687,19 @@ mod tests {
             rebuy_amount: BigDecimal::from_f64(0.0).unwrap(),
             cash_out_amount: BigDecimal::from_f64(100.0).unwrap(),
             notes: Some("Notes with, comma and \"quotes\"".to_string()),
-            created_at: Utc::now().naive_utc(),
-            updated_at: Utc::now().naive_utc(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            category: None,
+            start_time: None,
+            confirmed: true,
+            game_type: "cash".to_string(),
+            stakes: None,
+            location: None,
+            latitude: None,
+            longitude: None,
         };
 
-        let csv = generate_csv(&[session]);
+        let csv = generate_csv(&[session], &CsvColumn::ALL, ProfitConvention::default());
         let lines: Vec<&str> = csv.lines().collect();
 
         // The notes field should be escaped with quotes
@@ -657,11 +2717,19 @@ mod tests {
             rebuy_amount: BigDecimal::from_f64(100.0).unwrap(),
             cash_out_amount: BigDecimal::from_f64(200.0).unwrap(),
             notes: None,
-            created_at: Utc::now().naive_utc(),
-            updated_at: Utc::now().naive_utc(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            category: None,
+            start_time: None,
+            confirmed: true,
+            game_type: "cash".to_string(),
+            stakes: None,
+            location: None,
+            latitude: None,
+            longitude: None,
         };
 
-        let csv = generate_csv(&[session]);
+        let csv = generate_csv(&[session], &CsvColumn::ALL, ProfitConvention::default());
         let lines: Vec<&str> = csv.lines().collect();
 
         // Should show -100.00 profit
@@ -689,11 +2757,19 @@ mod tests {
                 rebuy_amount: BigDecimal::from_f64(0.0).unwrap(),
                 cash_out_amount: BigDecimal::from_f64(100.0).unwrap(),
                 notes: None,
-                created_at: Utc::now().naive_utc(),
-                updated_at: Utc::now().naive_utc(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                category: None,
+                start_time: None,
+                confirmed: true,
+                game_type: "cash".to_string(),
+                stakes: None,
+                location: None,
+                latitude: None,
+                longitude: None,
             };
 
-            let csv = generate_csv(&[session]);
+            let csv = generate_csv(&[session], &CsvColumn::ALL, ProfitConvention::default());
             let lines: Vec<&str> = csv.lines().collect();
             assert!(
                 lines[1].contains(expected_hours),
@@ -804,11 +2880,19 @@ mod tests {
                 rebuy_amount: BigDecimal::from_f64(0.0).unwrap(),
                 cash_out_amount: BigDecimal::from_f64(100.0).unwrap(),
                 notes: None,
-                created_at: Utc::now().naive_utc(),
-                updated_at: Utc::now().naive_utc(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                category: None,
+                start_time: None,
+                confirmed: true,
+                game_type: "cash".to_string(),
+                stakes: None,
+                location: None,
+                latitude: None,
+                longitude: None,
             };
 
-            let csv = generate_csv(&[session]);
+            let csv = generate_csv(&[session], &CsvColumn::ALL, ProfitConvention::default());
             let lines: Vec<&str> = csv.lines().collect();
 
             // The formatted hours should be close to expected
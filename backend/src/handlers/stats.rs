@@ -0,0 +1,986 @@
+use axum::{
+    Extension,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive, Zero};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde::Deserialize;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::app::AppState;
+use crate::handlers::poker_session::{load_profit_convention, time_range_cutoff};
+use crate::models::{
+    BankrollPoint, BuyInBucket, CachedSessionStats, CategoryStats, CumulativeStats,
+    DurationCorrelation, GameTypeDashboard, HourBucket, KellyStake, PokerSession, Rankings,
+    RiskOfRuin, RollingStats, RunningStats, SessionStats, UserStatsCache, WeekdayWeekendStats,
+    bankroll_series, estimated_amount, kelly_fraction, risk_of_ruin,
+};
+use crate::schema::{poker_sessions, user_stats_cache};
+use crate::utils::DbProvider;
+
+#[derive(Debug, Error)]
+pub enum StatsError {
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum StatsCacheError {
+    #[error("Failed to encode statistics: {0}")]
+    Encoding(#[from] serde_json::Error),
+    #[error(transparent)]
+    Stats(#[from] StatsError),
+}
+
+fn stats_error_response(error: StatsError) -> Response {
+    match error {
+        StatsError::DatabaseConnection => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database connection failed"
+            })),
+        )
+            .into_response(),
+        StatsError::Database(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Failed to compute statistics"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Fewer sessions than this give a variance estimate too noisy to be useful;
+/// applies to both risk of ruin and the Kelly stake
+pub const MIN_SESSIONS_FOR_RISK: u64 = 10;
+
+#[derive(Debug, Error)]
+pub enum RiskError {
+    #[error("bankroll must be greater than 0")]
+    InvalidBankroll,
+    #[error("At least {MIN_SESSIONS_FOR_RISK} sessions are required, found {0}")]
+    InsufficientSessions(u64),
+    #[error(transparent)]
+    Stats(#[from] StatsError),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    pub time_range: Option<String>,
+    /// Leave out sessions still awaiting review (`confirmed: false`)
+    #[serde(default)]
+    pub confirmed_only: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RollingQuery {
+    /// Leave out sessions still awaiting review (`confirmed: false`)
+    #[serde(default)]
+    pub confirmed_only: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RiskQuery {
+    pub bankroll: BigDecimal,
+    pub time_range: Option<String>,
+    /// Leave out sessions still awaiting review (`confirmed: false`)
+    #[serde(default)]
+    pub confirmed_only: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum BuyInBucketError {
+    #[error("size must be greater than 0")]
+    InvalidSize,
+    #[error(transparent)]
+    Stats(#[from] StatsError),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BuyInBucketQuery {
+    pub size: BigDecimal,
+    pub time_range: Option<String>,
+    /// Leave out sessions still awaiting review (`confirmed: false`)
+    #[serde(default)]
+    pub confirmed_only: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum RankingsError {
+    #[error("min_hours must be a non-negative number")]
+    InvalidMinHours,
+    #[error(transparent)]
+    Stats(#[from] StatsError),
+}
+
+/// Groups with less play than this are left out of rankings by default
+pub const DEFAULT_RANKING_MIN_HOURS: f64 = 10.0;
+
+#[derive(Debug, Deserialize)]
+pub struct RankingsQuery {
+    pub min_hours: Option<f64>,
+    pub time_range: Option<String>,
+    /// Leave out sessions still awaiting review (`confirmed: false`)
+    #[serde(default)]
+    pub confirmed_only: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BankrollSeriesQuery {
+    pub starting_bankroll: Option<BigDecimal>,
+    /// Add a point for every day between sessions, not just days played
+    #[serde(default)]
+    pub fill_gaps: bool,
+    pub time_range: Option<String>,
+    /// Leave out sessions still awaiting review (`confirmed: false`)
+    #[serde(default)]
+    pub confirmed_only: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CumulativeQuery {
+    pub as_of: String,
+    /// Leave out sessions still awaiting review (`confirmed: false`)
+    #[serde(default)]
+    pub confirmed_only: bool,
+}
+
+/// Load the user's sessions on or after the optional cutoff date, optionally
+/// skipping unconfirmed ones, with the user's profit convention applied.
+/// Sessions shorter than `min_duration_minutes` are left out of every
+/// aggregate (`min_duration_for_stats_minutes`).
+pub fn load_filtered_sessions(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    cutoff_date: Option<NaiveDate>,
+    confirmed_only: bool,
+    min_duration_minutes: u32,
+) -> Result<Vec<PokerSession>, StatsError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| StatsError::DatabaseConnection)?;
+
+    let mut query = poker_sessions::table
+        .filter(poker_sessions::user_id.eq(user_id))
+        .into_boxed();
+
+    if let Some(date) = cutoff_date {
+        query = query.filter(poker_sessions::session_date.ge(date));
+    }
+
+    if confirmed_only {
+        query = query.filter(poker_sessions::confirmed.eq(true));
+    }
+
+    if min_duration_minutes > 0 {
+        query =
+            query.filter(poker_sessions::duration_minutes.ge(min_duration(min_duration_minutes)));
+    }
+
+    let sessions = query
+        .order((
+            poker_sessions::session_date.asc(),
+            poker_sessions::created_at.asc(),
+        ))
+        .load::<PokerSession>(&mut conn)?;
+
+    let convention = load_profit_convention(&mut conn, user_id)?;
+    Ok(sessions
+        .into_iter()
+        .map(|session| convention.apply(session))
+        .collect())
+}
+
+/// `min_duration_for_stats_minutes` as a `duration_minutes` bound; a threshold
+/// past what the column holds excludes everything, as it would if it fit
+fn min_duration(minutes: u32) -> i32 {
+    i32::try_from(minutes).unwrap_or(i32::MAX)
+}
+
+/// Business logic for computing aggregate session statistics
+pub fn do_get_stats(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    cutoff_date: Option<NaiveDate>,
+    confirmed_only: bool,
+    min_duration_minutes: u32,
+) -> Result<SessionStats, StatsError> {
+    let sessions = load_filtered_sessions(
+        db_provider,
+        user_id,
+        cutoff_date,
+        confirmed_only,
+        min_duration_minutes,
+    )?;
+    Ok(SessionStats::from_sessions(&sessions))
+}
+
+/// How many sessions the user has and when one last changed. Creating,
+/// updating or deleting a session changes at least one of the two.
+fn session_fingerprint(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> QueryResult<(i64, Option<DateTime<Utc>>)> {
+    poker_sessions::table
+        .filter(poker_sessions::user_id.eq(user_id))
+        .select((
+            diesel::dsl::count_star(),
+            diesel::dsl::max(poker_sessions::updated_at),
+        ))
+        .first(conn)
+}
+
+/// Business logic for rebuilding the user's cached all-time statistics
+pub fn do_refresh_stats_cache(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    min_duration_minutes: u32,
+) -> Result<CachedSessionStats, StatsCacheError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| StatsError::DatabaseConnection)?;
+    // Taken before the sessions are read, so a write racing the refresh
+    // leaves the cache marked stale rather than wrongly fresh
+    let (session_count, sessions_updated_at) =
+        session_fingerprint(&mut conn, user_id).map_err(StatsError::from)?;
+    let convention = load_profit_convention(&mut conn, user_id).map_err(StatsError::from)?;
+    let stats = do_get_stats(db_provider, user_id, None, false, min_duration_minutes)?;
+
+    let row = UserStatsCache {
+        user_id,
+        stats: serde_json::to_string(&stats)?,
+        session_count,
+        sessions_updated_at,
+        profit_convention: convention.name().to_string(),
+        refreshed_at: Utc::now(),
+    };
+    diesel::insert_into(user_stats_cache::table)
+        .values(&row)
+        .on_conflict(user_stats_cache::user_id)
+        .do_update()
+        .set(&row)
+        .execute(&mut conn)
+        .map_err(StatsError::from)?;
+
+    Ok(CachedSessionStats {
+        stats,
+        stale: false,
+        refreshed_at: Some(row.refreshed_at),
+    })
+}
+
+/// Business logic for reading the user's cached all-time statistics, marked
+/// stale when sessions or the profit convention changed since the refresh.
+/// `None` when the user never refreshed, or the cached JSON no longer parses
+/// (e.g. written by an older version), so the caller computes them instead.
+pub fn do_get_cached_stats(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+) -> Result<Option<CachedSessionStats>, StatsError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| StatsError::DatabaseConnection)?;
+
+    let Some(row) = user_stats_cache::table
+        .find(user_id)
+        .first::<UserStatsCache>(&mut conn)
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    let Ok(stats) = serde_json::from_str::<SessionStats>(&row.stats) else {
+        return Ok(None);
+    };
+
+    let fingerprint = session_fingerprint(&mut conn, user_id)?;
+    let convention = load_profit_convention(&mut conn, user_id)?;
+    let stale = fingerprint != (row.session_count, row.sessions_updated_at)
+        || convention.name() != row.profit_convention;
+
+    Ok(Some(CachedSessionStats {
+        stats,
+        stale,
+        refreshed_at: Some(row.refreshed_at),
+    }))
+}
+
+/// All-time statistics come from the stats cache once the user has refreshed
+/// it; filtered statistics are always computed
+pub async fn get_stats(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<StatsQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    let all_time = cutoff_date.is_none() && !query.confirmed_only;
+    let cached = if all_time {
+        do_get_cached_stats(state.db_provider.as_ref(), user_id)
+    } else {
+        Ok(None)
+    };
+    let result = cached.and_then(|cached| match cached {
+        Some(cached) => Ok(cached),
+        None => do_get_stats(
+            state.db_provider.as_ref(),
+            user_id,
+            cutoff_date,
+            query.confirmed_only,
+            state.config.min_duration_for_stats_minutes,
+        )
+        .map(|stats| CachedSessionStats {
+            stats,
+            stale: false,
+            refreshed_at: None,
+        }),
+    });
+
+    match result {
+        Ok(stats) => (StatusCode::OK, Json(stats)).into_response(),
+        Err(e) => stats_error_response(e),
+    }
+}
+
+pub async fn refresh_stats_cache(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Response {
+    match do_refresh_stats_cache(
+        state.db_provider.as_ref(),
+        user_id,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(stats) => (StatusCode::OK, Json(stats)).into_response(),
+        Err(StatsCacheError::Stats(e @ StatsError::DatabaseConnection)) => stats_error_response(e),
+        Err(e) => {
+            tracing::error!(error = %e, "stats cache refresh failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to refresh statistics"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+pub async fn get_stats_by_category(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<StatsQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match load_filtered_sessions(
+        state.db_provider.as_ref(),
+        user_id,
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(sessions) => (
+            StatusCode::OK,
+            Json(CategoryStats::group_sessions(sessions)),
+        )
+            .into_response(),
+        Err(e) => stats_error_response(e),
+    }
+}
+
+/// Business logic for the rolling 30/90/365-day and all-time totals, computed
+/// from a single load of the user's sessions
+pub fn do_get_rolling(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    confirmed_only: bool,
+    min_duration_minutes: u32,
+) -> Result<RollingStats, StatsError> {
+    let sessions = load_filtered_sessions(
+        db_provider,
+        user_id,
+        None,
+        confirmed_only,
+        min_duration_minutes,
+    )?;
+    Ok(RollingStats::from_sessions(
+        &sessions,
+        Utc::now().date_naive(),
+    ))
+}
+
+pub async fn get_rolling(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<RollingQuery>,
+) -> Response {
+    match do_get_rolling(
+        state.db_provider.as_ref(),
+        user_id,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(rolling) => (StatusCode::OK, Json(rolling)).into_response(),
+        Err(e) => stats_error_response(e),
+    }
+}
+
+pub async fn get_dashboard(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<StatsQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match load_filtered_sessions(
+        state.db_provider.as_ref(),
+        user_id,
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(sessions) => (
+            StatusCode::OK,
+            Json(GameTypeDashboard::from_sessions(&sessions)),
+        )
+            .into_response(),
+        Err(e) => stats_error_response(e),
+    }
+}
+
+pub async fn get_weekday_vs_weekend(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<StatsQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match load_filtered_sessions(
+        state.db_provider.as_ref(),
+        user_id,
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(sessions) => (
+            StatusCode::OK,
+            Json(WeekdayWeekendStats::from_sessions(
+                &sessions,
+                state.config.day_cutoff_hour,
+            )),
+        )
+            .into_response(),
+        Err(e) => stats_error_response(e),
+    }
+}
+
+pub async fn get_stats_by_hour(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<StatsQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match load_filtered_sessions(
+        state.db_provider.as_ref(),
+        user_id,
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(sessions) => {
+            (StatusCode::OK, Json(HourBucket::group_sessions(&sessions))).into_response()
+        }
+        Err(e) => stats_error_response(e),
+    }
+}
+
+/// Business logic for ranking the user's session groups by profit per hour
+pub fn do_get_rankings(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    min_hours: f64,
+    cutoff_date: Option<NaiveDate>,
+    confirmed_only: bool,
+    min_duration_minutes: u32,
+) -> Result<Rankings, RankingsError> {
+    if !min_hours.is_finite() || min_hours < 0.0 {
+        return Err(RankingsError::InvalidMinHours);
+    }
+
+    let sessions = load_filtered_sessions(
+        db_provider,
+        user_id,
+        cutoff_date,
+        confirmed_only,
+        min_duration_minutes,
+    )?;
+    Ok(Rankings::from_sessions(sessions, min_hours))
+}
+
+pub async fn get_rankings(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<RankingsQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match do_get_rankings(
+        state.db_provider.as_ref(),
+        user_id,
+        query.min_hours.unwrap_or(DEFAULT_RANKING_MIN_HOURS),
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(rankings) => (StatusCode::OK, Json(rankings)).into_response(),
+        Err(e @ RankingsError::InvalidMinHours) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        Err(RankingsError::Stats(e)) => stats_error_response(e),
+    }
+}
+
+/// Business logic for splitting profit by buy-in range
+pub fn do_get_buy_in_buckets(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    size: &BigDecimal,
+    cutoff_date: Option<NaiveDate>,
+    confirmed_only: bool,
+    min_duration_minutes: u32,
+) -> Result<Vec<BuyInBucket>, BuyInBucketError> {
+    if *size <= BigDecimal::zero() {
+        return Err(BuyInBucketError::InvalidSize);
+    }
+
+    let sessions = load_filtered_sessions(
+        db_provider,
+        user_id,
+        cutoff_date,
+        confirmed_only,
+        min_duration_minutes,
+    )?;
+    Ok(BuyInBucket::group_sessions(&sessions, size))
+}
+
+pub async fn get_buy_in_buckets(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<BuyInBucketQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match do_get_buy_in_buckets(
+        state.db_provider.as_ref(),
+        user_id,
+        &query.size,
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(buckets) => (StatusCode::OK, Json(buckets)).into_response(),
+        Err(e @ BuyInBucketError::InvalidSize) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        Err(BuyInBucketError::Stats(e)) => stats_error_response(e),
+    }
+}
+
+/// Business logic for estimating risk of ruin from the user's session results
+pub fn do_get_risk(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    bankroll: &BigDecimal,
+    cutoff_date: Option<NaiveDate>,
+    confirmed_only: bool,
+    min_duration_minutes: u32,
+) -> Result<RiskOfRuin, RiskError> {
+    if *bankroll <= BigDecimal::zero() {
+        return Err(RiskError::InvalidBankroll);
+    }
+
+    let sessions = load_filtered_sessions(
+        db_provider,
+        user_id,
+        cutoff_date,
+        confirmed_only,
+        min_duration_minutes,
+    )?;
+    let running: RunningStats = sessions.iter().map(PokerSession::profit_f64).collect();
+
+    if running.count() < MIN_SESSIONS_FOR_RISK {
+        return Err(RiskError::InsufficientSessions(running.count()));
+    }
+    let (Some(mean), Some(variance)) = (running.mean(), running.variance()) else {
+        return Err(RiskError::InsufficientSessions(running.count()));
+    };
+
+    Ok(RiskOfRuin {
+        bankroll: bankroll.clone(),
+        session_count: running.count(),
+        mean_profit: estimated_amount(mean),
+        std_dev: estimated_amount(variance.sqrt()),
+        risk_of_ruin: risk_of_ruin(bankroll.to_f64().unwrap_or(0.0), mean, variance),
+    })
+}
+
+pub async fn get_risk(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<RiskQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match do_get_risk(
+        state.db_provider.as_ref(),
+        user_id,
+        &query.bankroll,
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(risk) => (StatusCode::OK, Json(risk)).into_response(),
+        Err(e @ (RiskError::InvalidBankroll | RiskError::InsufficientSessions(_))) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        Err(RiskError::Stats(e)) => stats_error_response(e),
+    }
+}
+
+/// Business logic for cumulative totals through a given date (inclusive)
+pub fn do_get_cumulative(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    as_of: NaiveDate,
+    confirmed_only: bool,
+    min_duration_minutes: u32,
+) -> Result<CumulativeStats, StatsError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| StatsError::DatabaseConnection)?;
+
+    let mut query = poker_sessions::table
+        .filter(poker_sessions::user_id.eq(user_id))
+        .filter(poker_sessions::session_date.le(as_of))
+        .filter(poker_sessions::duration_minutes.ge(min_duration(min_duration_minutes)))
+        .into_boxed();
+    if confirmed_only {
+        query = query.filter(poker_sessions::confirmed.eq(true));
+    }
+    let sessions = query.load::<PokerSession>(&mut conn)?;
+    let convention = load_profit_convention(&mut conn, user_id)?;
+    let sessions: Vec<PokerSession> = sessions
+        .into_iter()
+        .map(|session| convention.apply(session))
+        .collect();
+
+    Ok(CumulativeStats::from_sessions(as_of, &sessions))
+}
+
+/// Business logic for the Kelly-optimal stake given the user's results
+pub fn do_get_kelly(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    bankroll: &BigDecimal,
+    cutoff_date: Option<NaiveDate>,
+    confirmed_only: bool,
+    min_duration_minutes: u32,
+) -> Result<KellyStake, RiskError> {
+    if *bankroll <= BigDecimal::zero() {
+        return Err(RiskError::InvalidBankroll);
+    }
+
+    let sessions = load_filtered_sessions(
+        db_provider,
+        user_id,
+        cutoff_date,
+        confirmed_only,
+        min_duration_minutes,
+    )?;
+    let running: RunningStats = sessions.iter().map(PokerSession::profit_f64).collect();
+
+    if running.count() < MIN_SESSIONS_FOR_RISK {
+        return Err(RiskError::InsufficientSessions(running.count()));
+    }
+    let (Some(mean), Some(variance)) = (running.mean(), running.variance()) else {
+        return Err(RiskError::InsufficientSessions(running.count()));
+    };
+
+    let average_buy_in = sessions
+        .iter()
+        .map(|s| &s.buy_in_amount + &s.rebuy_amount)
+        .sum::<BigDecimal>()
+        / BigDecimal::from(running.count());
+    let fraction = kelly_fraction(mean, variance, average_buy_in.to_f64().unwrap_or(0.0));
+
+    Ok(KellyStake {
+        bankroll: bankroll.clone(),
+        session_count: running.count(),
+        mean_profit: estimated_amount(mean),
+        std_dev: estimated_amount(variance.sqrt()),
+        average_buy_in: average_buy_in.with_scale_round(2, RoundingMode::HalfUp),
+        kelly_fraction: fraction,
+        half_kelly_fraction: fraction / 2.0,
+        suggested_buy_in: estimated_amount(fraction * bankroll.to_f64().unwrap_or(0.0)),
+    })
+}
+
+pub async fn get_kelly(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<RiskQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match do_get_kelly(
+        state.db_provider.as_ref(),
+        user_id,
+        &query.bankroll,
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(kelly) => (StatusCode::OK, Json(kelly)).into_response(),
+        Err(e @ (RiskError::InvalidBankroll | RiskError::InsufficientSessions(_))) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response(),
+        Err(RiskError::Stats(e)) => stats_error_response(e),
+    }
+}
+
+pub async fn get_cumulative(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<CumulativeQuery>,
+) -> Response {
+    let Ok(as_of) = NaiveDate::parse_from_str(&query.as_of, "%Y-%m-%d") else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Invalid as_of date. Expected YYYY-MM-DD"
+            })),
+        )
+            .into_response();
+    };
+
+    match do_get_cumulative(
+        state.db_provider.as_ref(),
+        user_id,
+        as_of,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(cumulative) => (StatusCode::OK, Json(cumulative)).into_response(),
+        Err(e) => stats_error_response(e),
+    }
+}
+
+/// Business logic for the day-by-day bankroll, oldest first
+pub fn do_get_bankroll_series(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    starting_bankroll: &BigDecimal,
+    fill_gaps: bool,
+    cutoff_date: Option<NaiveDate>,
+    confirmed_only: bool,
+    min_duration_minutes: u32,
+) -> Result<Vec<BankrollPoint>, StatsError> {
+    let sessions = load_filtered_sessions(
+        db_provider,
+        user_id,
+        cutoff_date,
+        confirmed_only,
+        min_duration_minutes,
+    )?;
+    Ok(bankroll_series(&sessions, starting_bankroll, fill_gaps))
+}
+
+pub async fn get_bankroll_series(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<BankrollSeriesQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match do_get_bankroll_series(
+        state.db_provider.as_ref(),
+        user_id,
+        &query.starting_bankroll.unwrap_or_else(BigDecimal::zero),
+        query.fill_gaps,
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(series) => (StatusCode::OK, Json(series)).into_response(),
+        Err(e) => stats_error_response(e),
+    }
+}
+
+pub async fn get_duration_correlation(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<StatsQuery>,
+) -> Response {
+    let cutoff_date = match time_range_cutoff(query.time_range.as_deref()) {
+        Ok(cutoff) => cutoff,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match load_filtered_sessions(
+        state.db_provider.as_ref(),
+        user_id,
+        cutoff_date,
+        query.confirmed_only,
+        state.config.min_duration_for_stats_minutes,
+    ) {
+        Ok(sessions) => (
+            StatusCode::OK,
+            Json(DurationCorrelation::from_sessions(&sessions)),
+        )
+            .into_response(),
+        Err(e) => stats_error_response(e),
+    }
+}
@@ -0,0 +1,112 @@
+use axum::{
+    Extension,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use diesel::prelude::*;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::app::AppState;
+use crate::handlers::json_body::JsonBody;
+use crate::models::{CreateTemplateRequest, NewTemplate, Template};
+use crate::schema::templates;
+use crate::utils::DbProvider;
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+/// Business logic for creating a notes template
+pub fn do_create_template(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+    req: CreateTemplateRequest,
+) -> Result<Template, TemplateError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| TemplateError::DatabaseConnection)?;
+
+    let new_template = NewTemplate {
+        user_id,
+        name: req.name,
+        body: req.body,
+    };
+
+    Ok(diesel::insert_into(templates::table)
+        .values(&new_template)
+        .get_result::<Template>(&mut conn)?)
+}
+
+/// Business logic for listing the user's notes templates, oldest first
+pub fn do_list_templates(
+    db_provider: &dyn DbProvider,
+    user_id: Uuid,
+) -> Result<Vec<Template>, TemplateError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| TemplateError::DatabaseConnection)?;
+
+    Ok(templates::table
+        .filter(templates::user_id.eq(user_id))
+        .order(templates::created_at.asc())
+        .load::<Template>(&mut conn)?)
+}
+
+fn template_error_response(error: TemplateError) -> Response {
+    match error {
+        TemplateError::DatabaseConnection => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database connection failed"
+            })),
+        )
+            .into_response(),
+        TemplateError::Database(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Database operation failed"
+            })),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn create_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    JsonBody(req): JsonBody<CreateTemplateRequest>,
+) -> Response {
+    if let Err(errors) = req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    match do_create_template(state.db_provider.as_ref(), user_id, req) {
+        Ok(template) => (StatusCode::CREATED, Json(template)).into_response(),
+        Err(e) => template_error_response(e),
+    }
+}
+
+pub async fn list_templates(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Response {
+    match do_list_templates(state.db_provider.as_ref(), user_id) {
+        Ok(templates) => (StatusCode::OK, Json(templates)).into_response(),
+        Err(e) => template_error_response(e),
+    }
+}
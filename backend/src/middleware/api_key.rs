@@ -0,0 +1,84 @@
+use axum::{
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::app::AppState;
+use crate::handlers::api_key::do_authenticate_api_key;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Key management needs a login token, so a leaked key can't mint more keys
+const API_KEY_MANAGEMENT_PATH: &str = "/api/auth/api-keys";
+
+/// Admin routes, impersonation included, need a login token too, so an
+/// admin's key can't bypass the checks `AuthLayer` makes on tokens
+const ADMIN_PATH: &str = "/api/admin/";
+
+/// The owner of the `X-API-Key` a request was authenticated with; `AuthLayer`
+/// accepts it in place of a JWT
+#[derive(Debug, Clone, Copy)]
+pub struct ApiKeyUser(pub Uuid);
+
+/// Authenticate requests carrying an `X-API-Key` header. Requests without one
+/// are left to `AuthLayer`, so this must run outside it.
+pub async fn api_key_auth(
+    State(state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = req.headers().get(API_KEY_HEADER) else {
+        return next.run(req).await;
+    };
+
+    let key = key.to_str().unwrap_or_default();
+    let (user_id, read_only) = match do_authenticate_api_key(state.db_provider.as_ref(), key) {
+        Ok(Some(owner)) => owner,
+        Ok(None) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Invalid API key"})),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database operation failed"})),
+            )
+                .into_response();
+        }
+    };
+
+    if req.uri().path().starts_with(API_KEY_MANAGEMENT_PATH) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "API keys cannot manage API keys"})),
+        )
+            .into_response();
+    }
+
+    if req.uri().path().starts_with(ADMIN_PATH) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "API keys cannot use admin routes"})),
+        )
+            .into_response();
+    }
+
+    if read_only && req.method() != Method::GET && req.method() != Method::HEAD {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "API key is read-only"})),
+        )
+            .into_response();
+    }
+
+    req.extensions_mut().insert(ApiKeyUser(user_id));
+    next.run(req).await
+}
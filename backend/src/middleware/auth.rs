@@ -4,12 +4,15 @@ use axum::{
     response::{IntoResponse, Json, Response},
 };
 use serde_json::json;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use thiserror::Error;
 use tower::{Layer, Service};
 use uuid::Uuid;
 
-use crate::utils::jwt::decode_jwt;
+use crate::middleware::ApiKeyUser;
+use crate::utils::DbProvider;
+use crate::utils::jwt::{decode_jwt, is_jwt_revoked};
 
 /// Error type for token extraction failures
 #[derive(Debug, Error, PartialEq)]
@@ -22,34 +25,103 @@ pub enum TokenError {
     InvalidToken,
     #[error("Invalid user ID in token claims")]
     InvalidUserId,
+    #[error("Token has been revoked")]
+    Revoked,
+}
+
+/// The admin behind an impersonation token. `AuthLayer` inserts it into the
+/// request extensions next to the impersonated user's id.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Impersonator(pub Uuid);
+
+/// The caller a valid bearer token identifies
+#[derive(Debug, PartialEq)]
+pub struct TokenUser {
+    pub user_id: Uuid,
+    pub impersonator: Option<Impersonator>,
+}
+
+/// Routes an impersonation token can't reach, so a short support session
+/// can't be turned into lasting access (an API key, a new password) or admin
+/// rights
+const IMPERSONATION_BLOCKED_PATHS: [&str; 3] = [
+    "/api/auth/api-keys",
+    "/api/auth/change-password",
+    "/api/admin/",
+];
+
+fn blocked_while_impersonating(path: &str) -> bool {
+    IMPERSONATION_BLOCKED_PATHS
+        .iter()
+        .any(|blocked| path.starts_with(blocked))
 }
 
 /// Extract and validate a Bearer token from an Authorization header value.
-/// Returns the user UUID if valid, or an error describing what went wrong.
-pub fn extract_user_id_from_auth_header(
+/// Returns the user and their impersonator, if any, or an error describing
+/// what went wrong. `is_revoked` is asked about the token's `jti`, when it
+/// has one.
+pub fn authenticate_bearer(
     auth_header: Option<&str>,
     jwt_secret: &str,
-) -> Result<Uuid, TokenError> {
+    leeway_secs: u64,
+    is_revoked: impl FnOnce(Uuid) -> bool,
+) -> Result<TokenUser, TokenError> {
     let header = auth_header.ok_or(TokenError::Missing)?;
 
     let token = header
         .strip_prefix("Bearer ")
         .ok_or(TokenError::InvalidFormat)?;
 
-    let claims = decode_jwt(token, jwt_secret).map_err(|_| TokenError::InvalidToken)?;
+    let claims =
+        decode_jwt(token, jwt_secret, leeway_secs).map_err(|_| TokenError::InvalidToken)?;
+
+    if let Some(jti) = claims.jti.as_deref() {
+        let jti = Uuid::parse_str(jti).map_err(|_| TokenError::InvalidToken)?;
+        if is_revoked(jti) {
+            return Err(TokenError::Revoked);
+        }
+    }
+
+    let impersonator = claims
+        .impersonated_by
+        .as_deref()
+        .map(|admin_id| Uuid::parse_str(admin_id).map(Impersonator))
+        .transpose()
+        .map_err(|_| TokenError::InvalidToken)?;
+
+    Ok(TokenUser {
+        user_id: Uuid::parse_str(&claims.sub).map_err(|_| TokenError::InvalidUserId)?,
+        impersonator,
+    })
+}
 
-    Uuid::parse_str(&claims.sub).map_err(|_| TokenError::InvalidUserId)
+/// Extract and validate a Bearer token from an Authorization header value.
+/// Returns the user UUID if valid, or an error describing what went wrong.
+#[allow(dead_code)] // the middleware uses authenticate_bearer for the impersonator
+pub fn extract_user_id_from_auth_header(
+    auth_header: Option<&str>,
+    jwt_secret: &str,
+    leeway_secs: u64,
+    is_revoked: impl FnOnce(Uuid) -> bool,
+) -> Result<Uuid, TokenError> {
+    authenticate_bearer(auth_header, jwt_secret, leeway_secs, is_revoked).map(|user| user.user_id)
 }
 
 /// Auth middleware as an Axum layer
 #[derive(Clone)]
 pub struct AuthLayer {
     jwt_secret: String,
+    leeway_secs: u64,
+    db_provider: Arc<dyn DbProvider>,
 }
 
 impl AuthLayer {
-    pub fn new(jwt_secret: String) -> Self {
-        AuthLayer { jwt_secret }
+    pub fn new(jwt_secret: String, leeway_secs: u64, db_provider: Arc<dyn DbProvider>) -> Self {
+        AuthLayer {
+            jwt_secret,
+            leeway_secs,
+            db_provider,
+        }
     }
 }
 
@@ -60,6 +132,8 @@ impl<S> Layer<S> for AuthLayer {
         AuthService {
             inner,
             jwt_secret: self.jwt_secret.clone(),
+            leeway_secs: self.leeway_secs,
+            db_provider: self.db_provider.clone(),
         }
     }
 }
@@ -68,6 +142,8 @@ impl<S> Layer<S> for AuthLayer {
 pub struct AuthService<S> {
     inner: S,
     jwt_secret: String,
+    leeway_secs: u64,
+    db_provider: Arc<dyn DbProvider>,
 }
 
 impl<S> Service<Request> for AuthService<S>
@@ -88,7 +164,22 @@ where
     fn call(&mut self, req: Request) -> Self::Future {
         // Skip auth for public routes
         let path = req.uri().path();
-        if path == "/api/health" || path == "/api/auth/register" || path == "/api/auth/login" {
+        if path == "/api/health"
+            || path == "/api/health/live"
+            || path == "/api/version"
+            || path == "/api/auth/register"
+            || path == "/api/auth/login"
+        {
+            let future = self.inner.call(req);
+            return Box::pin(future);
+        }
+
+        // Already authenticated by `api_key_auth`
+        if let Some(ApiKeyUser(user_id)) = req.extensions().get::<ApiKeyUser>().copied() {
+            let (mut parts, body) = req.into_parts();
+            parts.extensions.insert(user_id);
+            let req = Request::from_parts(parts, body);
+
             let future = self.inner.call(req);
             return Box::pin(future);
         }
@@ -99,11 +190,35 @@ where
             .get("authorization")
             .and_then(|h| h.to_str().ok());
 
-        match extract_user_id_from_auth_header(auth_header, &self.jwt_secret) {
-            Ok(user_id) => {
+        // Fail closed: a revocation we can't check is treated as revoked
+        let is_revoked = |jti| {
+            is_jwt_revoked(self.db_provider.as_ref(), jti, self.leeway_secs).unwrap_or_else(|e| {
+                tracing::error!(error = %e, "token revocation check failed");
+                true
+            })
+        };
+
+        match authenticate_bearer(auth_header, &self.jwt_secret, self.leeway_secs, is_revoked) {
+            Ok(TokenUser {
+                impersonator: Some(_),
+                ..
+            }) if blocked_while_impersonating(req.uri().path()) => Box::pin(async move {
+                Ok((
+                    StatusCode::FORBIDDEN,
+                    Json(json!({"error": "Not allowed while impersonating"})),
+                )
+                    .into_response())
+            }),
+            Ok(TokenUser {
+                user_id,
+                impersonator,
+            }) => {
                 // Insert user_id into request extensions
                 let (mut parts, body) = req.into_parts();
                 parts.extensions.insert(user_id);
+                if let Some(impersonator) = impersonator {
+                    parts.extensions.insert(impersonator);
+                }
                 let req = Request::from_parts(parts, body);
 
                 let future = self.inner.call(req);
@@ -126,57 +241,129 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::jwt::create_jwt;
+    use crate::utils::jwt::{create_impersonation_jwt, create_jwt};
     use proptest::prelude::*;
 
     const TEST_SECRET: &str = "test_secret_key_for_testing";
+    const TEST_EXPIRY_HOURS: u32 = 168;
+    const TEST_LEEWAY: u64 = 60;
+
+    fn not_revoked(_: Uuid) -> bool {
+        false
+    }
 
     #[test]
     fn test_extract_user_id_missing_header() {
-        let result = extract_user_id_from_auth_header(None, TEST_SECRET);
+        let result = extract_user_id_from_auth_header(None, TEST_SECRET, TEST_LEEWAY, not_revoked);
         assert_eq!(result, Err(TokenError::Missing));
     }
 
     #[test]
     fn test_extract_user_id_invalid_format() {
-        let result = extract_user_id_from_auth_header(Some("InvalidFormat"), TEST_SECRET);
+        let result = extract_user_id_from_auth_header(
+            Some("InvalidFormat"),
+            TEST_SECRET,
+            TEST_LEEWAY,
+            not_revoked,
+        );
         assert_eq!(result, Err(TokenError::InvalidFormat));
     }
 
     #[test]
     fn test_extract_user_id_invalid_token() {
-        let result = extract_user_id_from_auth_header(Some("Bearer invalid_token"), TEST_SECRET);
+        let result = extract_user_id_from_auth_header(
+            Some("Bearer invalid_token"),
+            TEST_SECRET,
+            TEST_LEEWAY,
+            not_revoked,
+        );
         assert_eq!(result, Err(TokenError::InvalidToken));
     }
 
     #[test]
     fn test_extract_user_id_success() {
         let user_id = Uuid::new_v4();
-        let token = create_jwt(user_id, TEST_SECRET).unwrap();
+        let token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
         let auth_header = format!("Bearer {}", token);
-        let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+        let result = extract_user_id_from_auth_header(
+            Some(&auth_header),
+            TEST_SECRET,
+            TEST_LEEWAY,
+            not_revoked,
+        );
         assert_eq!(result, Ok(user_id));
     }
 
+    #[test]
+    fn test_authenticate_bearer_reports_impersonator() {
+        let user_id = Uuid::new_v4();
+        let admin_id = Uuid::new_v4();
+        let token = create_impersonation_jwt(user_id, admin_id, TEST_SECRET).unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let result = authenticate_bearer(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
+        assert_eq!(
+            result,
+            Ok(TokenUser {
+                user_id,
+                impersonator: Some(Impersonator(admin_id)),
+            })
+        );
+
+        let token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let result = authenticate_bearer(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
+        assert_eq!(result.unwrap().impersonator, None);
+    }
+
+    #[test]
+    fn test_blocked_while_impersonating() {
+        assert!(blocked_while_impersonating("/api/auth/api-keys"));
+        assert!(blocked_while_impersonating("/api/auth/change-password"));
+        assert!(blocked_while_impersonating("/api/admin/users"));
+        assert!(!blocked_while_impersonating("/api/sessions"));
+        assert!(!blocked_while_impersonating("/api/auth/me"));
+    }
+
+    #[test]
+    fn test_extract_user_id_revoked_token() {
+        let token = create_jwt(Uuid::new_v4(), TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
+        let auth_header = format!("Bearer {}", token);
+        let result =
+            extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, |_| {
+                true
+            });
+        assert_eq!(result, Err(TokenError::Revoked));
+    }
+
     #[test]
     fn test_extract_user_id_case_sensitive_bearer() {
         let user_id = Uuid::new_v4();
-        let token = create_jwt(user_id, TEST_SECRET).unwrap();
+        let token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
 
         // Test lowercase "bearer" - should fail
         let auth_header = format!("bearer {}", token);
-        let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+        let result = extract_user_id_from_auth_header(
+            Some(&auth_header),
+            TEST_SECRET,
+            TEST_LEEWAY,
+            not_revoked,
+        );
         assert_eq!(result, Err(TokenError::InvalidFormat));
     }
 
     #[test]
     fn test_extract_user_id_with_whitespace() {
         let user_id = Uuid::new_v4();
-        let token = create_jwt(user_id, TEST_SECRET).unwrap();
+        let token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
 
         // Test with extra whitespace
         let auth_header = format!("Bearer  {}", token);
-        let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+        let result = extract_user_id_from_auth_header(
+            Some(&auth_header),
+            TEST_SECRET,
+            TEST_LEEWAY,
+            not_revoked,
+        );
         // This should fail because strip_prefix expects exactly one space
         assert_eq!(result, Err(TokenError::InvalidToken));
     }
@@ -184,13 +371,18 @@ mod tests {
     #[test]
     fn test_extract_user_id_with_tampered_token() {
         let user_id = Uuid::new_v4();
-        let mut token = create_jwt(user_id, TEST_SECRET).unwrap();
+        let mut token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
 
         // Tamper with the token by appending a character
         token.push('x');
 
         let auth_header = format!("Bearer {}", token);
-        let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+        let result = extract_user_id_from_auth_header(
+            Some(&auth_header),
+            TEST_SECRET,
+            TEST_LEEWAY,
+            not_revoked,
+        );
         assert_eq!(result, Err(TokenError::InvalidToken));
     }
 
@@ -200,7 +392,7 @@ mod tests {
         fn missing_bearer_prefix_fails(s in "[a-zA-Z0-9_.-]{10,100}") {
             // Any string without "Bearer " prefix should fail
             if !s.starts_with("Bearer ") {
-                let result = extract_user_id_from_auth_header(Some(&s), TEST_SECRET);
+                let result = extract_user_id_from_auth_header(Some(&s), TEST_SECRET, TEST_LEEWAY, not_revoked);
                 prop_assert_eq!(result, Err(TokenError::InvalidFormat));
             }
         }
@@ -208,14 +400,14 @@ mod tests {
         #[test]
         fn lowercase_bearer_fails(token in "[a-zA-Z0-9_.-]{20,100}") {
             let auth_header = format!("bearer {}", token);
-            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
             prop_assert_eq!(result, Err(TokenError::InvalidFormat));
         }
 
         #[test]
         fn uppercase_bearer_fails(token in "[a-zA-Z0-9_.-]{20,100}") {
             let auth_header = format!("BEARER {}", token);
-            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
             prop_assert_eq!(result, Err(TokenError::InvalidFormat));
         }
 
@@ -223,26 +415,26 @@ mod tests {
         fn invalid_token_after_bearer_fails(token in "[a-zA-Z0-9]{10,50}") {
             // Random alphanumeric strings are not valid JWTs
             let auth_header = format!("Bearer {}", token);
-            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
             prop_assert_eq!(result, Err(TokenError::InvalidToken));
         }
 
         #[test]
         fn valid_jwt_roundtrip_works(_dummy in 0..100_i32) {
             let user_id = Uuid::new_v4();
-            let token = create_jwt(user_id, TEST_SECRET).unwrap();
+            let token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
             let auth_header = format!("Bearer {}", token);
-            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
             prop_assert_eq!(result, Ok(user_id));
         }
 
         #[test]
         fn extra_spaces_after_bearer_fails(spaces in 2..=5_usize) {
             let user_id = Uuid::new_v4();
-            let token = create_jwt(user_id, TEST_SECRET).unwrap();
+            let token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
             let space_str: String = (0..spaces).map(|_| ' ').collect();
             let auth_header = format!("Bearer{}{}", space_str, token);
-            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
             // "Bearer  token" doesn't match "Bearer " prefix correctly
             prop_assert!(result.is_err());
         }
@@ -250,35 +442,35 @@ mod tests {
         #[test]
         fn token_with_prefix_whitespace_fails(spaces in 1..=3_usize) {
             let user_id = Uuid::new_v4();
-            let token = create_jwt(user_id, TEST_SECRET).unwrap();
+            let token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
             let space_str: String = (0..spaces).map(|_| ' ').collect();
             let auth_header = format!("Bearer {}{}", space_str, token);
             // Leading whitespace in token part should cause invalid token
-            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
             prop_assert_eq!(result, Err(TokenError::InvalidToken));
         }
 
         #[test]
         fn tampered_token_fails(char_to_append in "[a-zA-Z0-9]") {
             let user_id = Uuid::new_v4();
-            let mut token = create_jwt(user_id, TEST_SECRET).unwrap();
+            let mut token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
             token.push_str(&char_to_append);
             let auth_header = format!("Bearer {}", token);
-            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
             prop_assert_eq!(result, Err(TokenError::InvalidToken));
         }
 
         #[test]
         fn truncated_token_fails(truncate_amount in 1..=10_usize) {
             let user_id = Uuid::new_v4();
-            let token = create_jwt(user_id, TEST_SECRET).unwrap();
+            let token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
             let truncated = if token.len() > truncate_amount {
                 &token[..token.len() - truncate_amount]
             } else {
                 ""
             };
             let auth_header = format!("Bearer {}", truncated);
-            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET);
+            let result = extract_user_id_from_auth_header(Some(&auth_header), TEST_SECRET, TEST_LEEWAY, not_revoked);
             prop_assert_eq!(result, Err(TokenError::InvalidToken));
         }
     }
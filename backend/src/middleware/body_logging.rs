@@ -0,0 +1,120 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Only session endpoints have their bodies logged; auth bodies carry passwords
+const LOGGED_PATH_PREFIX: &str = "/api/sessions";
+const NEVER_LOGGED_PATH_PREFIX: &str = "/api/auth";
+
+/// Largest body we'll buffer in order to log it, matching axum's default limit
+const MAX_BUFFERED_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Bodies are truncated to this many bytes in the log line
+const MAX_LOGGED_BODY_BYTES: usize = 1024;
+
+fn should_log_body(path: &str) -> bool {
+    path.starts_with(LOGGED_PATH_PREFIX) && !path.starts_with(NEVER_LOGGED_PATH_PREFIX)
+}
+
+/// Body text for the log line, cut at [`MAX_LOGGED_BODY_BYTES`]
+fn truncated_body(bytes: &[u8]) -> String {
+    if bytes.len() <= MAX_LOGGED_BODY_BYTES {
+        return String::from_utf8_lossy(bytes).into_owned();
+    }
+    format!(
+        "{}... ({} bytes total)",
+        String::from_utf8_lossy(&bytes[..MAX_LOGGED_BODY_BYTES]),
+        bytes.len()
+    )
+}
+
+/// Log session request bodies at trace level. Only installed when
+/// `debug_log_bodies` is on; never logs `/api/auth/*` bodies.
+pub async fn log_request_bodies(req: Request, next: Next) -> Response {
+    if !should_log_body(req.uri().path()) {
+        return next.run(req).await;
+    }
+
+    let (parts, body) = req.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_BUFFERED_BODY_BYTES).await else {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    };
+
+    if !bytes.is_empty() {
+        tracing::trace!(
+            method = %parts.method,
+            path = %parts.uri.path(),
+            body = %truncated_body(&bytes),
+            "request body"
+        );
+    }
+
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_logs::capture_logs;
+    use axum::{Router, routing::post};
+    use axum_test::TestServer;
+
+    fn test_server() -> TestServer {
+        let echo = |body: String| async move { body };
+        let router = Router::new()
+            .route("/api/auth/login", post(echo))
+            .route("/api/sessions", post(echo))
+            .layer(axum::middleware::from_fn(log_request_bodies));
+        TestServer::new(router).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_body_is_logged_and_forwarded() {
+        let (logs, _guard) = capture_logs(tracing::Level::TRACE);
+        let server = test_server();
+
+        let response = server.post("/api/sessions").text("buy_in=100").await;
+
+        response.assert_status_ok();
+        response.assert_text("buy_in=100");
+        assert!(logs.contents().contains("buy_in=100"));
+    }
+
+    #[tokio::test]
+    async fn test_auth_body_is_never_logged() {
+        let (logs, _guard) = capture_logs(tracing::Level::TRACE);
+        let server = test_server();
+
+        let response = server
+            .post("/api/auth/login")
+            .text("password=hunter2")
+            .await;
+
+        response.assert_status_ok();
+        assert!(!logs.contents().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_should_log_body() {
+        assert!(should_log_body("/api/sessions"));
+        assert!(should_log_body("/api/sessions/preview-profit"));
+        assert!(!should_log_body("/api/auth/register"));
+        assert!(!should_log_body("/api/auth/change-password"));
+        assert!(!should_log_body("/api/admin/users"));
+    }
+
+    #[test]
+    fn test_truncated_body() {
+        assert_eq!(truncated_body(b"short"), "short");
+
+        let long = vec![b'a'; MAX_LOGGED_BODY_BYTES + 10];
+        let logged = truncated_body(&long);
+        assert!(logged.starts_with(&"a".repeat(MAX_LOGGED_BODY_BYTES)));
+        assert!(logged.ends_with(&format!("... ({} bytes total)", long.len())));
+    }
+}
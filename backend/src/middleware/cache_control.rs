@@ -0,0 +1,37 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, Method, header},
+    middleware::Next,
+    response::Response,
+};
+
+/// Statistics only change when sessions do, so these may be cached briefly
+const CACHEABLE_PATH_PREFIX: &str = "/api/sessions/stats";
+
+/// Mark successful stats GET responses as cacheable by the requesting client for
+/// `max_age_secs`. `private` and `Vary: Authorization, X-API-Key` keep shared
+/// caches from serving one user's stats to another; the `Vary` is appended so
+/// the CORS layer's `Vary: Origin` survives. Only installed when
+/// `stats_cache_max_age_secs` is non-zero.
+pub async fn stats_cache_headers(
+    State(max_age_secs): State<u64>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let cacheable =
+        req.method() == Method::GET && req.uri().path().starts_with(CACHEABLE_PATH_PREFIX);
+    let mut response = next.run(req).await;
+
+    if cacheable && response.status().is_success() {
+        let headers = response.headers_mut();
+        if let Ok(value) = HeaderValue::from_str(&format!("private, max-age={}", max_age_secs)) {
+            headers.insert(header::CACHE_CONTROL, value);
+        }
+        headers.append(
+            header::VARY,
+            HeaderValue::from_static("Authorization, X-API-Key"),
+        );
+    }
+
+    response
+}
@@ -0,0 +1,111 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::{Map, Value};
+
+use crate::middleware::pretty_json::is_json;
+
+/// Upper bound on a JSON body we're willing to buffer for renaming
+const MAX_RENAMED_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// `buy_in_amount` -> `buyInAmount`
+fn snake_to_camel(key: &str) -> String {
+    let mut camel = String::with_capacity(key.len());
+    let mut upper_next = false;
+    for c in key.chars() {
+        if c == '_' && !camel.is_empty() {
+            upper_next = true;
+        } else if upper_next {
+            camel.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+/// Rename every object key in the document to camelCase
+fn camel_case_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (snake_to_camel(&key), camel_case_keys(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(camel_case_keys).collect()),
+        other => other,
+    }
+}
+
+/// Rewrite JSON response keys from snake_case to camelCase for clients that
+/// expect it, when `camel_case_json` is enabled. Downloads (responses with
+/// `Content-Disposition`) keep their original field names.
+pub async fn camel_case_json(State(enabled): State<bool>, req: Request, next: Next) -> Response {
+    let response = next.run(req).await;
+
+    if !enabled
+        || !is_json(&response)
+        || response.headers().contains_key(header::CONTENT_DISPOSITION)
+    {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_RENAMED_BODY_BYTES).await else {
+        return axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    let renamed = serde_json::from_slice::<Value>(&bytes)
+        .ok()
+        .and_then(|value| serde_json::to_vec(&camel_case_keys(value)).ok());
+
+    match renamed {
+        Some(renamed_body) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(renamed_body))
+        }
+        None => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_snake_to_camel() {
+        assert_eq!(snake_to_camel("buy_in_amount"), "buyInAmount");
+        assert_eq!(snake_to_camel("profit"), "profit");
+        assert_eq!(
+            snake_to_camel("overall_profit_per_hour"),
+            "overallProfitPerHour"
+        );
+        assert_eq!(snake_to_camel("_private"), "_private");
+    }
+
+    #[test]
+    fn test_camel_case_keys_is_recursive() {
+        let renamed = camel_case_keys(json!({
+            "user_id": "abc",
+            "max_drawdown": { "peak_date": "2024-01-02" },
+            "sessions": [{ "cash_out_amount": "150.00" }],
+            "category": "live_game"
+        }));
+
+        assert_eq!(
+            renamed,
+            json!({
+                "userId": "abc",
+                "maxDrawdown": { "peakDate": "2024-01-02" },
+                "sessions": [{ "cashOutAmount": "150.00" }],
+                "category": "live_game"
+            })
+        );
+    }
+}
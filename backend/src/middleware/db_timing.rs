@@ -0,0 +1,22 @@
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use std::sync::Arc;
+
+use crate::utils::{DbQueryStats, REQUEST_DB_STATS};
+
+/// Report the number of database queries a request made, and the time spent
+/// in them, as `X-DB-Queries` / `X-DB-Time-Ms` response headers.
+/// Only installed when `debug_timing` is enabled.
+pub async fn record_db_timing(req: Request, next: Next) -> Response {
+    let stats = Arc::new(DbQueryStats::default());
+    let mut response = REQUEST_DB_STATS.scope(stats.clone(), next.run(req)).await;
+
+    let headers = response.headers_mut();
+    headers.insert("x-db-queries", HeaderValue::from(stats.queries()));
+    if let Ok(value) =
+        HeaderValue::from_str(&format!("{:.3}", stats.elapsed().as_secs_f64() * 1000.0))
+    {
+        headers.insert("x-db-time-ms", value);
+    }
+
+    response
+}
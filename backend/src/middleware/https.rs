@@ -0,0 +1,60 @@
+use axum::{
+    extract::Request,
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::json;
+
+/// Health probes usually come straight from the orchestrator, not through the
+/// TLS-terminating proxy
+const HTTPS_EXEMPT_PATHS: [&str; 2] = ["/api/health", "/api/health/live"];
+
+/// Reject requests the proxy received over plain HTTP, as reported by
+/// `X-Forwarded-Proto`. Only installed when `require_https` and `trust_proxy`
+/// are both set, since the header is otherwise client-controlled.
+pub async fn https_guard(req: Request, next: Next) -> Response {
+    if !HTTPS_EXEMPT_PATHS.contains(&req.uri().path()) && !forwarded_https(req.headers()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "HTTPS is required"})),
+        )
+            .into_response();
+    }
+
+    next.run(req).await
+}
+
+/// Whether the client reached the first proxy over HTTPS. Each proxy appends
+/// its own hop to `X-Forwarded-Proto`, so the first entry is the client's.
+fn forwarded_https(headers: &HeaderMap) -> bool {
+    headers
+        .get("x-forwarded-proto")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(proto: Option<&'static str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(proto) = proto {
+            headers.insert("x-forwarded-proto", HeaderValue::from_static(proto));
+        }
+        headers
+    }
+
+    #[test]
+    fn test_forwarded_https() {
+        assert!(forwarded_https(&headers(Some("https"))));
+        assert!(forwarded_https(&headers(Some("HTTPS"))));
+        assert!(forwarded_https(&headers(Some("https, http"))));
+        assert!(!forwarded_https(&headers(Some("http"))));
+        assert!(!forwarded_https(&headers(Some("http, https"))));
+        assert!(!forwarded_https(&headers(None)));
+    }
+}
@@ -0,0 +1,44 @@
+use axum::{
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::app::AppState;
+
+/// Seconds clients are told to wait before retrying during maintenance
+pub const MAINTENANCE_RETRY_AFTER_SECS: u64 = 120;
+
+/// Routes that keep working in maintenance mode: health probes, and login plus
+/// the admin toggle so an admin can still sign in and switch maintenance off
+/// again at runtime.
+const MAINTENANCE_EXEMPT_PATHS: [&str; 4] = [
+    "/api/health",
+    "/api/health/live",
+    "/api/auth/login",
+    "/api/admin/maintenance",
+];
+
+/// Reject requests with 503 while the application is in maintenance mode
+pub async fn maintenance_guard(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if state.is_maintenance_mode() && !MAINTENANCE_EXEMPT_PATHS.contains(&req.uri().path()) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(
+                header::RETRY_AFTER,
+                MAINTENANCE_RETRY_AFTER_SECS.to_string(),
+            )],
+            Json(json!({"error": "Service is undergoing maintenance"})),
+        )
+            .into_response();
+    }
+
+    next.run(req).await
+}
@@ -1,3 +1,25 @@
+pub mod api_key;
 pub mod auth;
+pub mod body_logging;
+pub mod cache_control;
+pub mod camel_case;
+pub mod db_timing;
+pub mod https;
+pub mod maintenance;
+pub mod password_change;
+pub mod pretty_json;
+pub mod request_logging;
+#[cfg(test)]
+mod test_logs;
 
+pub use api_key::*;
 pub use auth::*;
+pub use body_logging::*;
+pub use cache_control::*;
+pub use camel_case::*;
+pub use db_timing::*;
+pub use https::*;
+pub use maintenance::*;
+pub use password_change::*;
+pub use pretty_json::*;
+pub use request_logging::*;
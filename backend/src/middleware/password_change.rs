@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use diesel::prelude::*;
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::app::AppState;
+use crate::schema::users;
+
+/// Routes a user flagged with `must_change_password` may still access
+const PASSWORD_CHANGE_EXEMPT_PATHS: [&str; 2] = ["/api/auth/change-password", "/api/auth/me"];
+
+/// Block authenticated users with a pending forced password change from
+/// everything except changing their password and viewing their profile.
+/// Must run inside `AuthLayer`, which provides the user id extension.
+pub async fn require_password_change(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(user_id) = req.extensions().get::<Uuid>().copied() else {
+        return next.run(req).await;
+    };
+
+    // The profile may be viewed but not edited
+    let path = req.uri().path();
+    if PASSWORD_CHANGE_EXEMPT_PATHS.contains(&path)
+        && (path != "/api/auth/me" || req.method() == Method::GET)
+    {
+        return next.run(req).await;
+    }
+
+    let must_change_password = {
+        let mut conn = match state.db_provider.get_connection() {
+            Ok(conn) => conn,
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database connection failed"})),
+                )
+                    .into_response();
+            }
+        };
+
+        // Fail closed: a flag we can't read must not grant access
+        match users::table
+            .find(user_id)
+            .select(users::must_change_password)
+            .first::<bool>(&mut conn)
+            .optional()
+        {
+            Ok(flag) => flag.unwrap_or(false),
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database operation failed"})),
+                )
+                    .into_response();
+            }
+        }
+    };
+
+    if must_change_password {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Password change required"})),
+        )
+            .into_response();
+    }
+
+    next.run(req).await
+}
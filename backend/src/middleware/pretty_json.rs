@@ -0,0 +1,60 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Upper bound on a JSON body we're willing to buffer for re-formatting
+const MAX_PRETTY_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Whether the request asked for pretty-printed JSON via `?pretty=true`
+fn wants_pretty(req: &Request) -> bool {
+    req.uri()
+        .query()
+        .map(|query| {
+            query
+                .split('&')
+                .any(|pair| pair.eq_ignore_ascii_case("pretty=true"))
+        })
+        .unwrap_or(false)
+}
+
+pub(crate) fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/json"))
+        .unwrap_or(false)
+}
+
+/// Re-serialize JSON responses with indentation when the client passes
+/// `?pretty=true`, so every handler gets it without changing how it responds.
+/// Responses stay compact by default.
+pub async fn pretty_json(req: Request, next: Next) -> Response {
+    let pretty = wants_pretty(&req);
+    let response = next.run(req).await;
+
+    if !pretty || !is_json(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_PRETTY_BODY_BYTES).await else {
+        return axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    let formatted = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok());
+
+    match formatted {
+        Some(pretty_body) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(pretty_body))
+        }
+        None => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
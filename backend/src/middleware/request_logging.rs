@@ -0,0 +1,81 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::time::{Duration, Instant};
+
+/// Log every request's latency, escalating to `warn` when it exceeds the
+/// configured slow-request threshold so regressions stand out in the logs.
+pub async fn log_request_latency(
+    State(threshold): State<Duration>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let method = req.method().clone();
+    let path = req.uri().path().to_owned();
+    let start = Instant::now();
+
+    let response = next.run(req).await;
+
+    let elapsed = start.elapsed();
+    let duration_ms = elapsed.as_millis() as u64;
+    let status = response.status().as_u16();
+
+    if elapsed > threshold {
+        tracing::warn!(%method, %path, status, duration_ms, "slow request");
+    } else {
+        tracing::debug!(%method, %path, status, duration_ms, "request completed");
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_logs::capture_logs;
+    use axum::{Router, routing::get};
+    use axum_test::TestServer;
+
+    fn test_server(threshold: Duration) -> TestServer {
+        let router = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    "done"
+                }),
+            )
+            .route("/fast", get(|| async { "done" }))
+            .layer(axum::middleware::from_fn_with_state(
+                threshold,
+                log_request_latency,
+            ));
+        TestServer::new(router).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_emits_warning() {
+        let (logs, _guard) = capture_logs(tracing::Level::WARN);
+        let server = test_server(Duration::from_millis(10));
+
+        server.get("/slow").await.assert_status_ok();
+
+        let output = logs.contents();
+        assert!(output.contains("WARN"), "no warning logged: {output}");
+        assert!(output.contains("slow request"));
+        assert!(output.contains("path=/slow"));
+        assert!(output.contains("duration_ms="));
+    }
+
+    #[tokio::test]
+    async fn test_fast_request_does_not_warn() {
+        let (logs, _guard) = capture_logs(tracing::Level::WARN);
+        let server = test_server(Duration::from_secs(10));
+
+        server.get("/fast").await.assert_status_ok();
+
+        assert!(logs.contents().is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::api_keys;
+
+/// A long-lived credential for scripts; only a hash of the key is stored, and
+/// it is never loaded, only compared in SQL
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = api_keys)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub label: String,
+    /// Limits the key to GET and HEAD requests
+    pub read_only: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = api_keys)]
+pub struct NewApiKey {
+    pub user_id: Uuid,
+    pub key_hash: String,
+    pub label: String,
+    pub read_only: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateApiKeyRequest {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Label must be between 1 and 100 characters"
+    ))]
+    pub label: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Returned once on creation; the key can't be retrieved again
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatedApiKey {
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_api_key_request_defaults_to_full_access() {
+        let req: CreateApiKeyRequest =
+            serde_json::from_value(serde_json::json!({"label": "exports"})).unwrap();
+        assert!(req.validate().is_ok());
+        assert!(!req.read_only);
+    }
+
+    #[test]
+    fn test_create_api_key_request_empty_label() {
+        let req = CreateApiKeyRequest {
+            label: String::new(),
+            read_only: false,
+        };
+        let errors = req.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("label"));
+    }
+
+    #[test]
+    fn test_api_key_hash_is_not_serialized() {
+        let api_key = ApiKey {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            label: "exports".to_string(),
+            read_only: false,
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        let json = serde_json::to_value(&api_key).unwrap();
+        assert!(json.get("key_hash").is_none());
+        assert_eq!(json["label"], "exports");
+    }
+}
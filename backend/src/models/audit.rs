@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::audit_log;
+
+/// Security-relevant things a user did, recorded for later review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Login,
+    PasswordChange,
+    ApiKeyRevoke,
+    Logout,
+    /// An admin minted a token acting as another user
+    Impersonate,
+}
+
+impl AuditAction {
+    /// Name stored in `audit_log.action`
+    pub fn name(self) -> &'static str {
+        match self {
+            AuditAction::Login => "login",
+            AuditAction::PasswordChange => "password_change",
+            AuditAction::ApiKeyRevoke => "api_key_revoke",
+            AuditAction::Logout => "logout",
+            AuditAction::Impersonate => "impersonate",
+        }
+    }
+}
+
+/// One audit log row. Never holds passwords, tokens or keys.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub action: String,
+    pub created_at: DateTime<Utc>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// The user the action was done to, when not `user_id` itself
+    pub target_user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = audit_log)]
+pub struct NewAuditEntry {
+    pub user_id: Uuid,
+    pub action: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub target_user_id: Option<Uuid>,
+}
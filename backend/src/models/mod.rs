@@ -1,5 +1,17 @@
+pub mod api_key;
+pub mod audit;
 pub mod poker_session;
+pub mod revoked_token;
+pub mod stats;
+pub mod stats_cache;
+pub mod template;
 pub mod user;
 
+pub use api_key::*;
+pub use audit::*;
 pub use poker_session::*;
+pub use revoked_token::*;
+pub use stats::*;
+pub use stats_cache::*;
+pub use template::*;
 pub use user::*;
@@ -1,7 +1,9 @@
-use bigdecimal::BigDecimal;
-use chrono::{NaiveDate, NaiveDateTime};
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive, Zero};
+use chrono::{DateTime, NaiveDate, NaiveTime, Timelike, Utc};
 use diesel::{Insertable, Queryable};
+use serde::de::{self, Deserializer, Visitor};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use uuid::Uuid;
 use validator::Validate;
 
@@ -17,8 +19,21 @@ pub struct PokerSession {
     pub rebuy_amount: BigDecimal,
     pub cash_out_amount: BigDecimal,
     pub notes: Option<String>,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub category: Option<String>,
+    pub start_time: Option<NaiveTime>,
+    /// False for quick entries whose result still needs double-checking
+    pub confirmed: bool,
+    /// Name of the session's [`GameType`]
+    pub game_type: String,
+    /// Blinds or stakes level as the user writes it, e.g. "1/2"
+    pub stakes: Option<String>,
+    /// Casino or home game the session was played at
+    pub location: Option<String>,
+    /// Where the session was played, in decimal degrees (WGS 84)
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
 }
 
 #[derive(Debug, Deserialize, Validate, Insertable)]
@@ -32,63 +47,553 @@ pub struct NewPokerSession {
     pub rebuy_amount: BigDecimal,
     pub cash_out_amount: BigDecimal,
     pub notes: Option<String>,
+    pub category: Option<String>,
+    pub start_time: Option<NaiveTime>,
+    pub confirmed: bool,
+    pub game_type: String,
+    pub stakes: Option<String>,
+    pub location: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+/// Amounts may be given as decimal numbers (`buy_in_amount`) or, to avoid
+/// floating point entirely, as integer minor units (`buy_in_cents`)
+#[derive(Debug, Default, Deserialize, Validate)]
 pub struct CreatePokerSessionRequest {
     pub session_date: String, // Will be parsed to NaiveDate
+    /// Local time the session started, `HH:MM` or `HH:MM:SS`
+    pub start_time: Option<String>,
     #[validate(range(min = 1, message = "Duration must be at least 1 minute"))]
+    #[serde(deserialize_with = "deserialize_duration")]
     pub duration_minutes: i32,
-    pub buy_in_amount: f64,
+    pub buy_in_amount: Option<f64>,
     pub rebuy_amount: Option<f64>,
-    pub cash_out_amount: f64,
+    pub cash_out_amount: Option<f64>,
     pub notes: Option<String>,
+    /// Prefill `notes` from one of the user's templates when `notes` is omitted
+    pub template_id: Option<Uuid>,
+    /// Free-text label such as "online" or "live"; see [`normalize_category`]
+    #[validate(length(max = 50, message = "Category must be at most 50 characters"))]
+    pub category: Option<String>,
+    pub buy_in_cents: Option<i64>,
+    pub rebuy_cents: Option<i64>,
+    pub cash_out_cents: Option<i64>,
+    /// Acknowledge a loss above `large_loss_confirm_threshold`
+    #[serde(default)]
+    pub confirm_large_loss: bool,
+    /// Send `false` for a quick entry to review later; defaults to confirmed
+    pub confirmed: Option<bool>,
+    /// Defaults to a cash game
+    pub game_type: Option<GameType>,
+    /// Blinds or stakes level, e.g. "1/2" or "2/5"
+    #[validate(length(max = 20, message = "Stakes must be at most 20 characters"))]
+    pub stakes: Option<String>,
+    /// Casino or home game, e.g. "Bellagio"
+    #[validate(length(max = 100, message = "Location must be at most 100 characters"))]
+    pub location: Option<String>,
+    /// Decimal degrees; sessions with both show up in the GeoJSON export
+    #[validate(range(min = -90.0, max = 90.0, message = "Latitude must be between -90 and 90"))]
+    pub latitude: Option<f64>,
+    #[validate(range(
+        min = -180.0,
+        max = 180.0,
+        message = "Longitude must be between -180 and 180"
+    ))]
+    pub longitude: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Default, Deserialize, Validate)]
 pub struct UpdatePokerSessionRequest {
     pub session_date: Option<String>,
+    /// An empty string clears the start time
+    pub start_time: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_duration")]
     pub duration_minutes: Option<i32>,
     pub buy_in_amount: Option<f64>,
     pub rebuy_amount: Option<f64>,
     pub cash_out_amount: Option<f64>,
     pub notes: Option<String>,
+    /// An empty string clears the category
+    #[validate(length(max = 50, message = "Category must be at most 50 characters"))]
+    pub category: Option<String>,
+    pub buy_in_cents: Option<i64>,
+    pub rebuy_cents: Option<i64>,
+    pub cash_out_cents: Option<i64>,
+    /// Acknowledge a loss above `large_loss_confirm_threshold`
+    #[serde(default)]
+    pub confirm_large_loss: bool,
+    pub game_type: Option<GameType>,
+    #[validate(length(max = 20, message = "Stakes must be at most 20 characters"))]
+    pub stakes: Option<String>,
+    #[validate(length(max = 100, message = "Location must be at most 100 characters"))]
+    pub location: Option<String>,
+    #[validate(range(min = -90.0, max = 90.0, message = "Latitude must be between -90 and 90"))]
+    pub latitude: Option<f64>,
+    #[validate(range(
+        min = -180.0,
+        max = 180.0,
+        message = "Longitude must be between -180 and 180"
+    ))]
+    pub longitude: Option<f64>,
+}
+
+/// Deserialization error for a whole-number duration that doesn't fit an
+/// `i32`; the JSON body extractor turns it into a 400
+pub const DURATION_OUT_OF_RANGE: &str = "duration out of range";
+
+/// A duration in minutes, parsed wide so an oversized value is reported as
+/// [`DURATION_OUT_OF_RANGE`] rather than a generic type error
+struct Minutes(i32);
+
+impl<'de> Deserialize<'de> for Minutes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MinutesVisitor;
+
+        impl Visitor<'_> for MinutesVisitor {
+            type Value = Minutes;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a whole number of minutes")
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Minutes, E> {
+                i32::try_from(v)
+                    .map(Minutes)
+                    .map_err(|_| E::custom(DURATION_OUT_OF_RANGE))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Minutes, E> {
+                i32::try_from(v)
+                    .map(Minutes)
+                    .map_err(|_| E::custom(DURATION_OUT_OF_RANGE))
+            }
+
+            // Integers too large for u64 arrive as floats; other floats are
+            // rejected as before
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Minutes, E> {
+                if v.is_finite() && v.fract() == 0.0 && v.abs() > f64::from(i32::MAX) {
+                    return Err(E::custom(DURATION_OUT_OF_RANGE));
+                }
+                Err(E::invalid_type(de::Unexpected::Float(v), &self))
+            }
+        }
+
+        deserializer.deserialize_i64(MinutesVisitor)
+    }
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+    Minutes::deserialize(deserializer).map(|minutes| minutes.0)
+}
+
+fn deserialize_optional_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<i32>, D::Error> {
+    Ok(Option::<Minutes>::deserialize(deserializer)?.map(|minutes| minutes.0))
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AmountError {
+    #[error("{0} must be given as either an amount or cents, not both")]
+    Conflicting(&'static str),
+    #[error("{0} is required (as an amount or in cents)")]
+    Missing(&'static str),
+    #[error("{0} is not a valid amount")]
+    Invalid(&'static str),
+}
+
+/// Exact decimal value of an amount in minor units, e.g. 10033 -> 100.33
+pub fn cents_to_decimal(cents: i64) -> BigDecimal {
+    BigDecimal::new(cents.into(), 2)
+}
+
+/// Resolve an amount supplied either as a float or as integer cents.
+/// Returns `None` when neither was supplied.
+pub fn resolve_amount(
+    field: &'static str,
+    amount: Option<f64>,
+    cents: Option<i64>,
+) -> Result<Option<BigDecimal>, AmountError> {
+    match (amount, cents) {
+        (Some(_), Some(_)) => Err(AmountError::Conflicting(field)),
+        (Some(amount), None) => BigDecimal::from_f64(amount)
+            .map(Some)
+            .ok_or(AmountError::Invalid(field)),
+        (None, Some(cents)) => Ok(Some(cents_to_decimal(cents))),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Fields that may be set on many sessions at once. Amounts and dates are
+/// deliberately absent, and unknown fields are rejected, so one request can't
+/// rewrite results across sessions.
+#[derive(Debug, Default, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct BulkUpdateFields {
+    /// An empty string clears the category
+    #[validate(length(max = 50, message = "Category must be at most 50 characters"))]
+    pub category: Option<String>,
+    /// An empty string clears the location
+    #[validate(length(max = 100, message = "Location must be at most 100 characters"))]
+    pub location: Option<String>,
+    pub game_type: Option<GameType>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkUpdateRequest {
+    #[validate(length(min = 1, message = "At least one session id is required"))]
+    pub ids: Vec<Uuid>,
+    #[validate(nested)]
+    pub set: BulkUpdateFields,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AppendNotesRequest {
+    #[validate(length(min = 1, message = "Text must not be empty"))]
+    pub text: String,
+    /// Prefix the appended line with the current UTC time
+    #[serde(default)]
+    pub timestamp: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SessionWithProfit {
     #[serde(flatten)]
     pub session: PokerSession,
-    pub profit: f64,
+    /// Exact, serialized as a decimal string like `"50.00"`
+    pub profit: BigDecimal,
+    pub result: SessionResult,
+}
+
+/// One line of the admin export: a session tagged with its owner's username
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminExportRow {
+    pub username: String,
+    #[serde(flatten)]
+    pub session: SessionWithProfit,
+}
+
+impl PokerSession {
+    /// Exact profit for this session: cash-out minus buy-in and rebuy
+    pub fn profit(&self) -> BigDecimal {
+        calculate_profit_decimal(
+            &self.buy_in_amount,
+            &self.rebuy_amount,
+            &self.cash_out_amount,
+        )
+    }
+
+    /// Profit as the `f64` used in statistics
+    pub fn profit_f64(&self) -> f64 {
+        profit_to_f64(&self.profit())
+    }
+
+    /// The day this session's play counts toward: one that started before
+    /// `day_cutoff_hour` (local time) belongs to the previous day's play.
+    /// Sessions without a `start_time` keep their `session_date`.
+    pub fn play_date(&self, day_cutoff_hour: u32) -> NaiveDate {
+        let before_cutoff = self
+            .start_time
+            .is_some_and(|start_time| start_time.hour() < day_cutoff_hour);
+        if before_cutoff {
+            self.session_date.pred_opt().unwrap_or(self.session_date)
+        } else {
+            self.session_date
+        }
+    }
+}
+
+impl SessionWithProfit {
+    /// Pair a session with its profit as the user's convention counts it
+    pub fn with_convention(session: PokerSession, convention: ProfitConvention) -> Self {
+        let profit = convention.profit(&session);
+        SessionWithProfit {
+            result: SessionResult::classify(&profit, 0.0),
+            session,
+            profit,
+        }
+    }
+
+    /// Reclassify `result`, counting profits within `epsilon` of zero as
+    /// break-even (`break_even_epsilon`)
+    pub fn with_break_even_epsilon(mut self, epsilon: f64) -> Self {
+        self.result = SessionResult::classify(&self.profit, epsilon);
+        self
+    }
+}
+
+/// Whether a session won or lost, so clients don't compare floats themselves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionResult {
+    Win,
+    Loss,
+    BreakEven,
 }
 
-/// Calculate profit from buy-in, rebuy, and cash-out amounts
+impl SessionResult {
+    pub fn classify(profit: &BigDecimal, epsilon: f64) -> Self {
+        let epsilon = BigDecimal::from_f64(epsilon).unwrap_or_default();
+        if profit.abs() <= epsilon {
+            SessionResult::BreakEven
+        } else if *profit > BigDecimal::zero() {
+            SessionResult::Win
+        } else {
+            SessionResult::Loss
+        }
+    }
+}
+
+impl From<PokerSession> for SessionWithProfit {
+    fn from(session: PokerSession) -> Self {
+        Self::with_convention(session, ProfitConvention::default())
+    }
+}
+
+/// Kind of game a session was played in. Stored by name in
+/// `poker_sessions.game_type`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameType {
+    #[default]
+    Cash,
+    Tournament,
+}
+
+impl GameType {
+    pub const ALL: [GameType; 2] = [GameType::Cash, GameType::Tournament];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|game_type| game_type.name() == name)
+    }
+
+    /// Name stored in `poker_sessions.game_type` and used in the API
+    pub fn name(self) -> &'static str {
+        match self {
+            GameType::Cash => "cash",
+            GameType::Tournament => "tournament",
+        }
+    }
+}
+
+/// How a user counts a session's profit. Stored per user by name; users who
+/// never chose one get the default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfitConvention {
+    /// Cash-out minus buy-in and rebuy
+    #[default]
+    Standard,
+    /// Cash-out minus buy-in only, for players who track rebuys separately
+    ExcludeRebuy,
+}
+
+impl ProfitConvention {
+    pub const ALL: [ProfitConvention; 2] =
+        [ProfitConvention::Standard, ProfitConvention::ExcludeRebuy];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|convention| convention.name() == name)
+    }
+
+    /// The convention for a stored setting; unset or unknown names get the
+    /// default
+    pub fn from_setting(name: Option<&str>) -> Self {
+        name.and_then(Self::from_name).unwrap_or_default()
+    }
+
+    /// Name stored in `users.profit_convention` and used in the API
+    pub fn name(self) -> &'static str {
+        match self {
+            ProfitConvention::Standard => "standard",
+            ProfitConvention::ExcludeRebuy => "exclude_rebuy",
+        }
+    }
+
+    /// Exact profit for a session under this convention
+    pub fn profit(self, session: &PokerSession) -> BigDecimal {
+        self.apply(session.clone()).profit()
+    }
+
+    /// Adjust a session's amounts so `PokerSession::profit` follows this
+    /// convention. Statistics read profit straight from the session, so their
+    /// loaders apply the convention once instead of threading it through
+    /// every calculation.
+    pub fn apply(self, mut session: PokerSession) -> PokerSession {
+        session.rebuy_amount = self.counted_rebuy(&session.rebuy_amount);
+        session
+    }
+
+    /// The rebuy this convention counts toward profit, for amounts that are
+    /// not a saved session yet; [`apply`](Self::apply) stores the same value
+    pub fn counted_rebuy(self, rebuy: &BigDecimal) -> BigDecimal {
+        match self {
+            ProfitConvention::Standard => rebuy.clone(),
+            ProfitConvention::ExcludeRebuy => BigDecimal::zero(),
+        }
+    }
+}
+
+/// Computed results for a session that has not been saved
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProfitPreview {
+    /// Exact, serialized as a decimal string like `"50.00"`
+    pub profit: BigDecimal,
+    /// Profit over total invested (buy-in plus rebuy), e.g. 0.5 for +50%;
+    /// `None` when nothing was invested
+    pub roi: Option<f64>,
+    pub profit_per_hour: f64,
+}
+
+impl ProfitPreview {
+    pub fn new(
+        buy_in: &BigDecimal,
+        rebuy: &BigDecimal,
+        cash_out: &BigDecimal,
+        duration_minutes: i32,
+    ) -> Self {
+        let profit = calculate_profit_decimal(buy_in, rebuy, cash_out);
+        let profit_f64 = profit_to_f64(&profit);
+        let invested = (buy_in + rebuy).to_f64().unwrap_or(0.0);
+        ProfitPreview {
+            profit,
+            roi: (invested != 0.0).then(|| profit_f64 / invested),
+            profit_per_hour: profit_f64 / (f64::from(duration_minutes) / 60.0),
+        }
+    }
+}
+
+/// Exact profit from buy-in, rebuy, and cash-out amounts
+pub fn calculate_profit_decimal(
+    buy_in: &BigDecimal,
+    rebuy: &BigDecimal,
+    cash_out: &BigDecimal,
+) -> BigDecimal {
+    cash_out - (buy_in + rebuy)
+}
+
+/// Round a profit to the nearest `f64`. A value that can't be represented
+/// becomes NaN (serialized as `null`) and is logged, rather than passing for
+/// break-even.
+fn profit_to_f64(profit: &BigDecimal) -> f64 {
+    profit.to_f64().unwrap_or_else(|| {
+        tracing::error!(%profit, "profit cannot be represented as f64");
+        f64::NAN
+    })
+}
+
+/// Calculate profit from buy-in, rebuy, and cash-out amounts, rounded to the
+/// nearest `f64`
+#[deprecated(note = "rounds money to f64; use calculate_profit_decimal")]
+#[allow(dead_code)] // only the existing tests still call it
 pub fn calculate_profit(buy_in: &BigDecimal, rebuy: &BigDecimal, cash_out: &BigDecimal) -> f64 {
-    let total_invested = buy_in + rebuy;
-    (cash_out - &total_invested)
-        .to_string()
-        .parse::<f64>()
-        .unwrap_or(0.0)
+    profit_to_f64(&calculate_profit_decimal(buy_in, rebuy, cash_out))
+}
+
+/// Append a line to existing notes, separated by a newline and optionally
+/// prefixed with the time it was written
+pub fn append_note(
+    existing: Option<&str>,
+    text: &str,
+    written_at: Option<DateTime<Utc>>,
+) -> String {
+    let line = match written_at {
+        Some(at) => format!("[{}] {}", at.format("%Y-%m-%d %H:%M UTC"), text),
+        None => text.to_string(),
+    };
+
+    match existing {
+        Some(notes) if !notes.is_empty() => format!("{}\n{}", notes, line),
+        _ => line,
+    }
+}
+
+/// Parse a session start time given as `HH:MM` or `HH:MM:SS`
+pub fn parse_start_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+        .ok()
+}
+
+/// Categories are compared case-insensitively and ignoring surrounding
+/// whitespace, so they are stored trimmed and lowercased. Blank means none.
+pub fn normalize_category(category: &str) -> Option<String> {
+    let trimmed = category.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_lowercase())
+}
+
+/// The loss on a session when it exceeds `threshold` and hasn't been
+/// confirmed, so the client can ask before saving what may be a typo
+pub fn unconfirmed_large_loss(
+    profit: &BigDecimal,
+    threshold: Option<f64>,
+    confirmed: bool,
+) -> Option<BigDecimal> {
+    let threshold = BigDecimal::from_f64(threshold?)?;
+    let loss = -profit;
+    (!confirmed && loss > threshold).then_some(loss)
+}
+
+/// A rebuy only makes sense on top of an initial buy-in, so a non-zero rebuy
+/// paired with a zero buy-in is almost always a data-entry mistake.
+pub fn is_rebuy_without_buy_in(buy_in: &BigDecimal, rebuy: &BigDecimal) -> bool {
+    !rebuy.is_zero() && buy_in.is_zero()
 }
 
 #[cfg(test)]
+// The f64 `calculate_profit` tests stay until the helper is removed
+#[allow(deprecated)]
 mod tests {
     use super::*;
-    use bigdecimal::FromPrimitive;
     use chrono::Datelike;
     use proptest::prelude::*;
+    use std::str::FromStr;
     use validator::Validate;
 
     // CreatePokerSessionRequest validation tests
+    #[test]
+    fn test_oversized_duration_is_out_of_range() {
+        let body = r#"{"session_date": "2024-01-15", "duration_minutes": 2147483648}"#;
+        let err = serde_json::from_str::<CreatePokerSessionRequest>(body).unwrap_err();
+        assert!(err.to_string().contains(DURATION_OUT_OF_RANGE));
+
+        let body = r#"{"duration_minutes": -99999999999999999999}"#;
+        let err = serde_json::from_str::<UpdatePokerSessionRequest>(body).unwrap_err();
+        assert!(err.to_string().contains(DURATION_OUT_OF_RANGE));
+    }
+
+    #[test]
+    fn test_duration_deserialization_accepts_i32_and_rejects_fractions() {
+        let body = r#"{"session_date": "2024-01-15", "duration_minutes": 2147483647}"#;
+        let req: CreatePokerSessionRequest = serde_json::from_str(body).unwrap();
+        assert_eq!(req.duration_minutes, i32::MAX);
+
+        let req: UpdatePokerSessionRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(req.duration_minutes, None);
+        let req: UpdatePokerSessionRequest =
+            serde_json::from_str(r#"{"duration_minutes": null}"#).unwrap();
+        assert_eq!(req.duration_minutes, None);
+
+        let err = serde_json::from_str::<UpdatePokerSessionRequest>(r#"{"duration_minutes": 1.5}"#)
+            .unwrap_err();
+        assert!(!err.to_string().contains(DURATION_OUT_OF_RANGE));
+    }
+
     #[test]
     fn test_create_session_request_valid() {
         let req = CreatePokerSessionRequest {
             session_date: "2024-01-15".to_string(),
             duration_minutes: 120,
-            buy_in_amount: 100.0,
+            buy_in_amount: Some(100.0),
             rebuy_amount: Some(50.0),
-            cash_out_amount: 200.0,
+            cash_out_amount: Some(200.0),
             notes: Some("Good session".to_string()),
+            ..Default::default()
         };
         assert!(req.validate().is_ok());
     }
@@ -98,10 +603,11 @@ mod tests {
         let req = CreatePokerSessionRequest {
             session_date: "2024-01-15".to_string(),
             duration_minutes: 0,
-            buy_in_amount: 100.0,
+            buy_in_amount: Some(100.0),
             rebuy_amount: None,
-            cash_out_amount: 150.0,
+            cash_out_amount: Some(150.0),
             notes: None,
+            ..Default::default()
         };
         let result = req.validate();
         assert!(result.is_err());
@@ -114,10 +620,11 @@ mod tests {
         let req = CreatePokerSessionRequest {
             session_date: "2024-01-15".to_string(),
             duration_minutes: -10,
-            buy_in_amount: 100.0,
+            buy_in_amount: Some(100.0),
             rebuy_amount: None,
-            cash_out_amount: 150.0,
+            cash_out_amount: Some(150.0),
             notes: None,
+            ..Default::default()
         };
         let result = req.validate();
         assert!(result.is_err());
@@ -130,10 +637,11 @@ mod tests {
         let req = CreatePokerSessionRequest {
             session_date: "2024-01-15".to_string(),
             duration_minutes: 1, // minimum valid
-            buy_in_amount: 100.0,
+            buy_in_amount: Some(100.0),
             rebuy_amount: None,
-            cash_out_amount: 150.0,
+            cash_out_amount: Some(150.0),
             notes: None,
+            ..Default::default()
         };
         assert!(req.validate().is_ok());
     }
@@ -149,6 +657,14 @@ mod tests {
             rebuy_amount: BigDecimal::from_f64(0.0).unwrap(),
             cash_out_amount: BigDecimal::from_f64(150.0).unwrap(),
             notes: None,
+            category: None,
+            start_time: None,
+            confirmed: true,
+            game_type: "cash".to_string(),
+            stakes: None,
+            location: None,
+            latitude: None,
+            longitude: None,
         };
         assert!(session.validate().is_ok());
     }
@@ -163,6 +679,14 @@ mod tests {
             rebuy_amount: BigDecimal::from_f64(0.0).unwrap(),
             cash_out_amount: BigDecimal::from_f64(150.0).unwrap(),
             notes: None,
+            category: None,
+            start_time: None,
+            confirmed: true,
+            game_type: "cash".to_string(),
+            stakes: None,
+            location: None,
+            latitude: None,
+            longitude: None,
         };
         let result = session.validate();
         assert!(result.is_err());
@@ -225,6 +749,53 @@ mod tests {
         assert!((profit - 25.50).abs() < 0.01);
     }
 
+    #[test]
+    fn test_calculate_profit_never_silently_zero() {
+        // Largest DECIMAL(10,2) amounts, and a sub-cent difference that has
+        // no exact f64 representation
+        let cases = [
+            ("0", "0", "99999999.99", 99999999.99),
+            ("99999999.99", "0", "0", -99999999.99),
+            ("12345678.91", "0", "12345678.92", 0.01),
+            ("0", "0", "0.000000000000000000000000001", 1e-27),
+        ];
+
+        for (buy_in, rebuy, cash_out, expected) in cases {
+            let profit = calculate_profit(
+                &BigDecimal::from_str(buy_in).unwrap(),
+                &BigDecimal::from_str(rebuy).unwrap(),
+                &BigDecimal::from_str(cash_out).unwrap(),
+            );
+            assert!(profit != 0.0, "{cash_out} - {buy_in} became 0");
+            assert!(
+                (profit - expected).abs() <= expected.abs() * 1e-9,
+                "{cash_out} - {buy_in}: got {profit}, expected {expected}"
+            );
+        }
+    }
+
+    // Rebuy/buy-in consistency tests
+    #[test]
+    fn test_rebuy_without_buy_in_detected() {
+        let buy_in = BigDecimal::from_f64(0.0).unwrap();
+        let rebuy = BigDecimal::from_f64(100.0).unwrap();
+        assert!(is_rebuy_without_buy_in(&buy_in, &rebuy));
+    }
+
+    #[test]
+    fn test_rebuy_with_buy_in_allowed() {
+        let buy_in = BigDecimal::from_f64(100.0).unwrap();
+        let rebuy = BigDecimal::from_f64(100.0).unwrap();
+        assert!(!is_rebuy_without_buy_in(&buy_in, &rebuy));
+    }
+
+    #[test]
+    fn test_no_rebuy_without_buy_in_allowed() {
+        let buy_in = BigDecimal::from_f64(0.0).unwrap();
+        let rebuy = BigDecimal::from_f64(0.0).unwrap();
+        assert!(!is_rebuy_without_buy_in(&buy_in, &rebuy));
+    }
+
     // Date parsing tests (testing the format used by handlers)
     #[test]
     fn test_date_parsing_valid() {
@@ -251,6 +822,306 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // Amount resolution tests
+    #[test]
+    fn test_cents_to_decimal_is_exact() {
+        assert_eq!(
+            cents_to_decimal(10033),
+            "100.33".parse::<BigDecimal>().unwrap()
+        );
+        assert_eq!(cents_to_decimal(-5), "-0.05".parse::<BigDecimal>().unwrap());
+    }
+
+    #[test]
+    fn test_resolve_amount_from_float_or_cents() {
+        assert_eq!(
+            resolve_amount("buy_in", Some(100.5), None),
+            Ok(Some("100.5".parse::<BigDecimal>().unwrap()))
+        );
+        assert_eq!(
+            resolve_amount("buy_in", None, Some(10050)),
+            Ok(Some("100.50".parse::<BigDecimal>().unwrap()))
+        );
+        assert_eq!(resolve_amount("buy_in", None, None), Ok(None));
+    }
+
+    #[test]
+    fn test_resolve_amount_rejects_both_and_non_finite() {
+        assert_eq!(
+            resolve_amount("buy_in", Some(100.0), Some(10000)),
+            Err(AmountError::Conflicting("buy_in"))
+        );
+        assert_eq!(
+            resolve_amount("buy_in", Some(f64::NAN), None),
+            Err(AmountError::Invalid("buy_in"))
+        );
+    }
+
+    // append_note tests
+    #[test]
+    fn test_append_note_to_empty_notes() {
+        assert_eq!(append_note(None, "first", None), "first");
+        assert_eq!(append_note(Some(""), "first", None), "first");
+    }
+
+    #[test]
+    fn test_append_note_uses_newline_separator() {
+        assert_eq!(append_note(Some("first"), "second", None), "first\nsecond");
+    }
+
+    #[test]
+    fn test_append_note_with_timestamp_prefix() {
+        let at = NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(21, 5, 0)
+            .unwrap()
+            .and_utc();
+        assert_eq!(
+            append_note(Some("first"), "second", Some(at)),
+            "first\n[2024-01-15 21:05 UTC] second"
+        );
+    }
+
+    // Large loss confirmation tests
+    #[test]
+    fn test_profit_preview() {
+        let preview = ProfitPreview::new(
+            &BigDecimal::from(100),
+            &BigDecimal::from(50),
+            &BigDecimal::from(225),
+            90,
+        );
+        assert_eq!(preview.profit, BigDecimal::from(75));
+        assert_eq!(preview.roi, Some(0.5));
+        assert_eq!(preview.profit_per_hour, 50.0);
+    }
+
+    #[test]
+    fn test_profit_preview_without_investment_has_no_roi() {
+        let preview = ProfitPreview::new(
+            &BigDecimal::from(0),
+            &BigDecimal::from(0),
+            &BigDecimal::from(30),
+            60,
+        );
+        assert_eq!(preview.roi, None);
+        assert_eq!(preview.profit_per_hour, 30.0);
+    }
+
+    #[test]
+    fn test_unconfirmed_large_loss_disabled_without_threshold() {
+        let profit = BigDecimal::from(-10_000);
+        assert_eq!(unconfirmed_large_loss(&profit, None, false), None);
+    }
+
+    #[test]
+    fn test_unconfirmed_large_loss_above_threshold() {
+        let profit = BigDecimal::from(-1500);
+        assert_eq!(
+            unconfirmed_large_loss(&profit, Some(1000.0), false),
+            Some(BigDecimal::from(1500))
+        );
+        assert_eq!(unconfirmed_large_loss(&profit, Some(1000.0), true), None);
+    }
+
+    #[test]
+    fn test_unconfirmed_large_loss_at_or_below_threshold() {
+        assert_eq!(
+            unconfirmed_large_loss(&BigDecimal::from(-1000), Some(1000.0), false),
+            None
+        );
+        assert_eq!(
+            unconfirmed_large_loss(&BigDecimal::from(500), Some(1000.0), false),
+            None
+        );
+    }
+
+    // parse_start_time tests
+    #[test]
+    fn test_parse_start_time_formats() {
+        let expected = NaiveTime::from_hms_opt(21, 30, 0);
+        assert_eq!(parse_start_time("21:30"), expected);
+        assert_eq!(parse_start_time("21:30:00"), expected);
+    }
+
+    #[test]
+    fn test_parse_start_time_invalid() {
+        assert_eq!(parse_start_time("25:00"), None);
+        assert_eq!(parse_start_time("9pm"), None);
+        assert_eq!(parse_start_time(""), None);
+    }
+
+    // normalize_category tests
+    #[test]
+    fn test_normalize_category_trims_and_lowercases() {
+        assert_eq!(normalize_category("  Online "), Some("online".to_string()));
+        assert_eq!(normalize_category("LIVE"), Some("live".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_category_blank_is_none() {
+        assert_eq!(normalize_category(""), None);
+        assert_eq!(normalize_category("   "), None);
+    }
+
+    #[test]
+    fn test_create_session_request_category_too_long() {
+        let req = CreatePokerSessionRequest {
+            session_date: "2024-01-15".to_string(),
+            duration_minutes: 120,
+            buy_in_amount: Some(100.0),
+            cash_out_amount: Some(150.0),
+            category: Some("x".repeat(51)),
+            ..Default::default()
+        };
+        let errors = req.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("category"));
+    }
+
+    // PokerSession::profit tests
+    fn session_with_amounts(buy_in: &str, rebuy: &str, cash_out: &str) -> PokerSession {
+        PokerSession {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            session_date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            duration_minutes: 120,
+            buy_in_amount: buy_in.parse().unwrap(),
+            rebuy_amount: rebuy.parse().unwrap(),
+            cash_out_amount: cash_out.parse().unwrap(),
+            notes: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            category: None,
+            start_time: None,
+            confirmed: true,
+            game_type: "cash".to_string(),
+            stakes: None,
+            location: None,
+            latitude: None,
+            longitude: None,
+        }
+    }
+
+    #[test]
+    fn test_session_profit_is_exact_decimal() {
+        let session = session_with_amounts("100.10", "50.20", "200.40");
+        assert_eq!(session.profit(), "50.10".parse::<BigDecimal>().unwrap());
+    }
+
+    #[test]
+    fn test_session_profit_loss() {
+        let session = session_with_amounts("100", "50", "0");
+        assert_eq!(session.profit(), BigDecimal::from(-150));
+    }
+
+    #[test]
+    fn test_session_with_profit_from_session() {
+        let session = session_with_amounts("100", "0", "175.5");
+        let with_profit = SessionWithProfit::from(session);
+        assert_eq!(with_profit.profit, "75.5".parse::<BigDecimal>().unwrap());
+        assert_eq!(
+            serde_json::to_value(&with_profit).unwrap()["profit"],
+            serde_json::json!("75.5")
+        );
+    }
+
+    #[test]
+    fn test_calculate_profit_decimal_is_exact() {
+        let buy_in = "0.1".parse::<BigDecimal>().unwrap();
+        let rebuy = "0.2".parse::<BigDecimal>().unwrap();
+        let cash_out = BigDecimal::from(1);
+        assert_eq!(
+            calculate_profit_decimal(&buy_in, &rebuy, &cash_out),
+            "0.7".parse::<BigDecimal>().unwrap()
+        );
+    }
+
+    // ProfitConvention tests
+    #[test]
+    fn test_profit_convention_standard_counts_rebuy() {
+        let session = session_with_amounts("100", "50", "200");
+        assert_eq!(
+            ProfitConvention::Standard.profit(&session),
+            BigDecimal::from(50)
+        );
+        assert_eq!(
+            ProfitConvention::Standard.profit(&session),
+            session.profit()
+        );
+    }
+
+    #[test]
+    fn test_profit_convention_exclude_rebuy_ignores_rebuy() {
+        let session = session_with_amounts("100", "50", "200");
+        assert_eq!(
+            ProfitConvention::ExcludeRebuy.profit(&session),
+            BigDecimal::from(100)
+        );
+        assert_eq!(
+            ProfitConvention::ExcludeRebuy.apply(session).profit_f64(),
+            100.0
+        );
+    }
+
+    #[test]
+    fn test_profit_convention_default_is_standard() {
+        assert_eq!(ProfitConvention::default(), ProfitConvention::Standard);
+        let session = session_with_amounts("100", "50", "200");
+        assert_eq!(
+            SessionWithProfit::from(session).profit,
+            BigDecimal::from(50)
+        );
+    }
+
+    #[test]
+    fn test_profit_convention_apply_keeps_other_amounts() {
+        let session = session_with_amounts("100", "50", "200");
+        let applied = ProfitConvention::ExcludeRebuy.apply(session.clone());
+        assert_eq!(applied.buy_in_amount, session.buy_in_amount);
+        assert_eq!(applied.cash_out_amount, session.cash_out_amount);
+        assert_eq!(applied.profit(), BigDecimal::from(100));
+    }
+
+    #[test]
+    fn test_profit_convention_names_round_trip() {
+        for convention in ProfitConvention::ALL {
+            assert_eq!(
+                ProfitConvention::from_name(convention.name()),
+                Some(convention)
+            );
+        }
+        assert_eq!(ProfitConvention::from_name("gross"), None);
+    }
+
+    #[test]
+    fn test_session_result_classification() {
+        let classify = |profit: &str, epsilon| {
+            SessionResult::classify(&profit.parse::<BigDecimal>().unwrap(), epsilon)
+        };
+        assert_eq!(classify("25", 0.0), SessionResult::Win);
+        assert_eq!(classify("-25", 0.0), SessionResult::Loss);
+        assert_eq!(classify("0.00", 0.0), SessionResult::BreakEven);
+        assert_eq!(classify("0.5", 1.0), SessionResult::BreakEven);
+        assert_eq!(classify("-1", 1.0), SessionResult::BreakEven);
+        assert_eq!(classify("1.01", 1.0), SessionResult::Win);
+        assert_eq!(
+            serde_json::to_value(SessionResult::BreakEven).unwrap(),
+            serde_json::json!("break_even")
+        );
+    }
+
+    #[test]
+    fn test_game_type_names_match_serde() {
+        for game_type in GameType::ALL {
+            assert_eq!(GameType::from_name(game_type.name()), Some(game_type));
+            assert_eq!(
+                serde_json::to_value(game_type).unwrap(),
+                serde_json::json!(game_type.name())
+            );
+        }
+        assert_eq!(GameType::default(), GameType::Cash);
+    }
+
     // Property-based tests for calculate_profit
     proptest! {
         #[test]
@@ -335,10 +1206,11 @@ mod tests {
             let req = CreatePokerSessionRequest {
                 session_date: "2024-01-15".to_string(),
                 duration_minutes: duration,
-                buy_in_amount: 100.0,
+                buy_in_amount: Some(100.0),
                 rebuy_amount: None,
-                cash_out_amount: 150.0,
+                cash_out_amount: Some(150.0),
                 notes: None,
+                ..Default::default()
             };
             prop_assert!(req.validate().is_ok(),
                 "Duration {} should be valid", duration);
@@ -349,10 +1221,11 @@ mod tests {
             let req = CreatePokerSessionRequest {
                 session_date: "2024-01-15".to_string(),
                 duration_minutes: duration,
-                buy_in_amount: 100.0,
+                buy_in_amount: Some(100.0),
                 rebuy_amount: None,
-                cash_out_amount: 150.0,
+                cash_out_amount: Some(150.0),
                 notes: None,
+                ..Default::default()
             };
             let result = req.validate();
             prop_assert!(result.is_err(),
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable};
+use uuid::Uuid;
+
+use crate::schema::revoked_tokens;
+
+/// A token logged out before its expiry, identified by its `jti` claim
+#[derive(Debug, Clone, Queryable, Insertable)]
+#[diesel(table_name = revoked_tokens)]
+pub struct RevokedToken {
+    pub jti: Uuid,
+    pub user_id: Uuid,
+    /// The token's own expiry; the row can be purged after this
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,1239 @@
+use bigdecimal::{BigDecimal, FromPrimitive, RoundingMode, Signed, ToPrimitive, Zero};
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::{GameType, PokerSession};
+
+/// Running mean and variance, accumulated with Welford's online algorithm so
+/// that large or similar values don't lose precision to cancellation.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunningStats {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl RunningStats {
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Sample variance (n - 1 denominator); undefined for fewer than 2 values
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.m2 / (self.count - 1) as f64)
+    }
+
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+}
+
+impl FromIterator<f64> for RunningStats {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut stats = RunningStats::default();
+        for value in iter {
+            stats.push(value);
+        }
+        stats
+    }
+}
+
+/// An amount estimated in floating point, such as a standard deviation of
+/// results, rounded to cents like the amounts it was estimated from
+pub fn estimated_amount(value: f64) -> BigDecimal {
+    BigDecimal::from_f64(value)
+        .unwrap_or_default()
+        .with_scale_round(2, RoundingMode::HalfUp)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionStats {
+    pub session_count: u64,
+    pub total_profit: BigDecimal,
+    /// Rounded to cents
+    pub average_profit: Option<BigDecimal>,
+    /// In squared currency units, so not itself an amount
+    pub result_variance: Option<f64>,
+    /// Rounded to cents
+    pub result_std_dev: Option<BigDecimal>,
+    /// Hours played; the sample size behind `overall_profit_per_hour`
+    pub total_hours: f64,
+    /// Total profit over total hours (pooled, not a mean of per-session rates)
+    pub overall_profit_per_hour: Option<f64>,
+    /// `None` with fewer than 2 sessions
+    pub max_drawdown: Option<MaxDrawdown>,
+    /// Sessions per week between the first and last session dates; `None`
+    /// with fewer than 2 sessions or when they all fall on one day
+    pub avg_sessions_per_week: Option<f64>,
+    /// Hours played per week over the same span
+    pub avg_hours_per_week: Option<f64>,
+}
+
+impl SessionStats {
+    pub fn from_sessions(sessions: &[PokerSession]) -> Self {
+        let running: RunningStats = sessions.iter().map(PokerSession::profit_f64).collect();
+        let total_profit: BigDecimal = sessions.iter().map(PokerSession::profit).sum();
+        let total_minutes: i64 = sessions.iter().map(|s| s.duration_minutes as i64).sum();
+        let total_hours = total_minutes as f64 / 60.0;
+        let weeks = weeks_spanned(sessions);
+
+        SessionStats {
+            session_count: running.count(),
+            average_profit: (running.count() > 0).then(|| {
+                (&total_profit / BigDecimal::from(running.count()))
+                    .with_scale_round(2, RoundingMode::HalfUp)
+            }),
+            result_variance: running.variance(),
+            result_std_dev: running.std_dev().map(estimated_amount),
+            total_hours,
+            overall_profit_per_hour: (total_minutes > 0)
+                .then(|| total_profit.to_f64().map(|profit| profit / total_hours))
+                .flatten(),
+            total_profit,
+            max_drawdown: MaxDrawdown::from_sessions(sessions),
+            avg_sessions_per_week: weeks.map(|weeks| running.count() as f64 / weeks),
+            avg_hours_per_week: weeks.map(|weeks| total_hours / weeks),
+        }
+    }
+}
+
+/// Weeks from the earliest to the latest session date. Session dates carry no
+/// time zone and a span isn't aligned to week boundaries, so neither matters.
+fn weeks_spanned(sessions: &[PokerSession]) -> Option<f64> {
+    let first = sessions.iter().map(|s| s.session_date).min()?;
+    let last = sessions.iter().map(|s| s.session_date).max()?;
+    let days = (last - first).num_days();
+    (days > 0).then(|| days as f64 / 7.0)
+}
+
+/// All-time statistics, possibly served from the user's stats cache
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedSessionStats {
+    #[serde(flatten)]
+    pub stats: SessionStats,
+    /// Sessions changed since `refreshed_at`, so the numbers may be out of date
+    pub stale: bool,
+    /// When the cache was last rebuilt; `null` when computed for this request
+    pub refreshed_at: Option<DateTime<Utc>>,
+}
+
+/// The largest peak-to-trough drop in cumulative profit
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct MaxDrawdown {
+    pub amount: BigDecimal,
+    /// Date of the session that set the peak
+    pub peak_date: NaiveDate,
+    /// Date of the session at the bottom of the drop
+    pub trough_date: NaiveDate,
+}
+
+impl MaxDrawdown {
+    /// Walk the cumulative profit curve of chronologically ordered sessions,
+    /// tracking the running peak and the deepest dip below it. The curve starts
+    /// at 0 on the first session's date, so an opening losing run counts as a
+    /// drawdown; with no dip the amount is 0 and both dates are the first
+    /// session's.
+    pub fn from_sessions(sessions: &[PokerSession]) -> Option<Self> {
+        if sessions.len() < 2 {
+            return None;
+        }
+
+        let mut cumulative = BigDecimal::zero();
+        let mut peak = (BigDecimal::zero(), sessions[0].session_date);
+        let mut max_drawdown = MaxDrawdown {
+            amount: BigDecimal::zero(),
+            peak_date: peak.1,
+            trough_date: peak.1,
+        };
+
+        for session in sessions {
+            cumulative += session.profit();
+            if cumulative >= peak.0 {
+                peak = (cumulative.clone(), session.session_date);
+                continue;
+            }
+
+            let drawdown = &peak.0 - &cumulative;
+            if drawdown > max_drawdown.amount {
+                max_drawdown = MaxDrawdown {
+                    amount: drawdown,
+                    peak_date: peak.1,
+                    trough_date: session.session_date,
+                };
+            }
+        }
+
+        Some(max_drawdown)
+    }
+}
+
+/// Aggregate statistics for the sessions sharing one category; `category` is
+/// `null` for sessions without one
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryStats {
+    pub category: Option<String>,
+    #[serde(flatten)]
+    pub stats: SessionStats,
+}
+
+impl CategoryStats {
+    /// Group sessions by category, ordered by name with uncategorized last
+    pub fn group_sessions(sessions: Vec<PokerSession>) -> Vec<Self> {
+        let mut by_category: BTreeMap<String, Vec<PokerSession>> = BTreeMap::new();
+        let mut uncategorized = Vec::new();
+        for session in sessions {
+            match session.category.clone() {
+                Some(category) => by_category.entry(category).or_default().push(session),
+                None => uncategorized.push(session),
+            }
+        }
+
+        let mut groups: Vec<Self> = by_category
+            .into_iter()
+            .map(|(category, sessions)| CategoryStats {
+                category: Some(category),
+                stats: SessionStats::from_sessions(&sessions),
+            })
+            .collect();
+        if !uncategorized.is_empty() {
+            groups.push(CategoryStats {
+                category: None,
+                stats: SessionStats::from_sessions(&uncategorized),
+            });
+        }
+        groups
+    }
+}
+
+/// One ranked group of sessions; `name` is `null` for sessions without a value
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RankingEntry {
+    pub name: Option<String>,
+    pub session_count: u64,
+    pub total_hours: f64,
+    pub profit_per_hour: f64,
+}
+
+/// The user's own session groups ranked by profit per hour, best first, for
+/// each way sessions can be grouped
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Rankings {
+    pub categories: Vec<RankingEntry>,
+    pub locations: Vec<RankingEntry>,
+    pub game_types: Vec<RankingEntry>,
+    pub stakes: Vec<RankingEntry>,
+}
+
+impl Rankings {
+    /// Rank categories, locations, game types and stakes by pooled profit per
+    /// hour, leaving out groups with fewer than `min_hours` played as too
+    /// small a sample
+    pub fn from_sessions(sessions: Vec<PokerSession>, min_hours: f64) -> Self {
+        Rankings {
+            locations: rank_groups(&sessions, |session| session.location.clone(), min_hours),
+            game_types: rank_groups(
+                &sessions,
+                |session| Some(session.game_type.clone()),
+                min_hours,
+            ),
+            stakes: rank_groups(&sessions, |session| session.stakes.clone(), min_hours),
+            categories: rank_groups(&sessions, |session| session.category.clone(), min_hours),
+        }
+    }
+}
+
+/// Group sessions by `key` and rank the groups with at least `min_hours`
+/// played; sessions without a value form the `null` group
+fn rank_groups(
+    sessions: &[PokerSession],
+    key: impl Fn(&PokerSession) -> Option<String>,
+    min_hours: f64,
+) -> Vec<RankingEntry> {
+    let mut groups: BTreeMap<Option<String>, Vec<PokerSession>> = BTreeMap::new();
+    for session in sessions {
+        groups
+            .entry(key(session))
+            .or_default()
+            .push(session.clone());
+    }
+
+    let mut entries: Vec<RankingEntry> = groups
+        .into_iter()
+        .filter_map(|(name, sessions)| {
+            let stats = SessionStats::from_sessions(&sessions);
+            if stats.total_hours < min_hours {
+                return None;
+            }
+            Some(RankingEntry {
+                profit_per_hour: stats.overall_profit_per_hour?,
+                name,
+                session_count: stats.session_count,
+                total_hours: stats.total_hours,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.profit_per_hour.total_cmp(&a.profit_per_hour));
+    entries
+}
+
+/// Compact totals for a group of sessions
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfitSummary {
+    pub session_count: u64,
+    pub total_profit: BigDecimal,
+    pub total_hours: f64,
+    /// Pooled over the group's hours; `null` when no hours are recorded
+    pub profit_per_hour: Option<f64>,
+}
+
+impl ProfitSummary {
+    pub fn from_sessions<'a>(sessions: impl IntoIterator<Item = &'a PokerSession>) -> Self {
+        let mut session_count = 0;
+        let mut total_profit = BigDecimal::from(0);
+        let mut total_minutes: i64 = 0;
+        for session in sessions {
+            session_count += 1;
+            total_profit += session.profit();
+            total_minutes += session.duration_minutes as i64;
+        }
+        let total_hours = total_minutes as f64 / 60.0;
+
+        ProfitSummary {
+            session_count,
+            profit_per_hour: (total_minutes > 0)
+                .then(|| total_profit.to_f64().map(|profit| profit / total_hours))
+                .flatten(),
+            total_profit,
+            total_hours,
+        }
+    }
+}
+
+/// Sessions split by whether their play date is Monday-Friday or
+/// Saturday/Sunday; see [`PokerSession::play_date`]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WeekdayWeekendStats {
+    pub weekday: ProfitSummary,
+    pub weekend: ProfitSummary,
+}
+
+impl WeekdayWeekendStats {
+    pub fn from_sessions(sessions: &[PokerSession], day_cutoff_hour: u32) -> Self {
+        let (weekend, weekday): (Vec<&PokerSession>, Vec<&PokerSession>) = sessions
+            .iter()
+            .partition(|session| is_weekend(session.play_date(day_cutoff_hour)));
+
+        WeekdayWeekendStats {
+            weekday: ProfitSummary::from_sessions(weekday),
+            weekend: ProfitSummary::from_sessions(weekend),
+        }
+    }
+}
+
+/// Totals over the trailing 30, 90 and 365 days and over all time. Each
+/// window covers sessions dated on or after `today` minus its length, the
+/// same cutoff as the matching `time_range`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollingStats {
+    pub last_30: ProfitSummary,
+    pub last_90: ProfitSummary,
+    pub last_365: ProfitSummary,
+    pub all_time: ProfitSummary,
+}
+
+impl RollingStats {
+    pub fn from_sessions(sessions: &[PokerSession], today: NaiveDate) -> Self {
+        let window = |days: i64| {
+            let cutoff = today - chrono::Duration::days(days);
+            ProfitSummary::from_sessions(
+                sessions
+                    .iter()
+                    .filter(|session| session.session_date >= cutoff),
+            )
+        };
+
+        RollingStats {
+            last_30: window(30),
+            last_90: window(90),
+            last_365: window(365),
+            all_time: ProfitSummary::from_sessions(sessions),
+        }
+    }
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Sessions whose buy-in falls in `[bucket_min, bucket_max)`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuyInBucket {
+    pub bucket_min: BigDecimal,
+    pub bucket_max: BigDecimal,
+    pub session_count: u64,
+    pub total_profit: BigDecimal,
+    /// Pooled over the bucket's hours; `null` when no hours are recorded
+    pub profit_per_hour: Option<f64>,
+}
+
+impl BuyInBucket {
+    /// Group sessions into buy-in ranges of width `size`, returning only
+    /// non-empty buckets in ascending order. `size` must be positive.
+    pub fn group_sessions(sessions: &[PokerSession], size: &BigDecimal) -> Vec<Self> {
+        // (session count, total profit, total minutes) per bucket index
+        let mut buckets: BTreeMap<i64, (u64, BigDecimal, i64)> = BTreeMap::new();
+        for session in sessions {
+            let index = (&session.buy_in_amount / size)
+                .with_scale_round(0, RoundingMode::Floor)
+                .to_i64()
+                .unwrap_or(i64::MAX);
+            let bucket = buckets.entry(index).or_default();
+            bucket.0 += 1;
+            bucket.1 += session.profit();
+            bucket.2 += session.duration_minutes as i64;
+        }
+
+        buckets
+            .into_iter()
+            .map(|(index, (session_count, total_profit, total_minutes))| {
+                let bucket_min = size * BigDecimal::from(index);
+                BuyInBucket {
+                    bucket_max: &bucket_min + size,
+                    bucket_min,
+                    session_count,
+                    profit_per_hour: (total_minutes > 0)
+                        .then(|| {
+                            total_profit
+                                .to_f64()
+                                .map(|profit| profit / (total_minutes as f64 / 60.0))
+                        })
+                        .flatten(),
+                    total_profit,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Sessions that started during `hour` (0-23)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HourBucket {
+    pub hour: u32,
+    pub session_count: u64,
+    pub total_profit: BigDecimal,
+}
+
+impl HourBucket {
+    /// One bucket for every hour of the day, including empty ones. Sessions
+    /// without a recorded start time are left out.
+    pub fn group_sessions(sessions: &[PokerSession]) -> Vec<Self> {
+        let mut buckets: Vec<Self> = (0..24)
+            .map(|hour| HourBucket {
+                hour,
+                session_count: 0,
+                total_profit: BigDecimal::zero(),
+            })
+            .collect();
+
+        for session in sessions {
+            if let Some(start_time) = session.start_time {
+                let bucket = &mut buckets[start_time.hour() as usize];
+                bucket.session_count += 1;
+                bucket.total_profit += session.profit();
+            }
+        }
+        buckets
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DurationCorrelation {
+    pub sample_size: u64,
+    pub correlation: Option<f64>,
+}
+
+/// Correlation needs at least this many points to be more than a line through two
+pub const MIN_SESSIONS_FOR_CORRELATION: usize = 3;
+
+/// Pearson correlation coefficient of paired samples, computed in a single
+/// pass with running co-moments. Returns `None` for fewer than
+/// `MIN_SESSIONS_FOR_CORRELATION` pairs or when either series is constant.
+pub fn pearson_correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < MIN_SESSIONS_FOR_CORRELATION {
+        return None;
+    }
+
+    let (mut mean_x, mut mean_y) = (0.0, 0.0);
+    let (mut m2_x, mut m2_y, mut co_moment) = (0.0, 0.0, 0.0);
+
+    for (i, &(x, y)) in pairs.iter().enumerate() {
+        let n = (i + 1) as f64;
+        let delta_x = x - mean_x;
+        let delta_y = y - mean_y;
+        mean_x += delta_x / n;
+        mean_y += delta_y / n;
+        m2_x += delta_x * (x - mean_x);
+        m2_y += delta_y * (y - mean_y);
+        co_moment += delta_x * (y - mean_y);
+    }
+
+    if m2_x <= 0.0 || m2_y <= 0.0 {
+        return None;
+    }
+
+    Some((co_moment / (m2_x * m2_y).sqrt()).clamp(-1.0, 1.0))
+}
+
+impl DurationCorrelation {
+    pub fn from_sessions(sessions: &[PokerSession]) -> Self {
+        let pairs: Vec<(f64, f64)> = sessions
+            .iter()
+            .map(|s| (s.duration_minutes as f64, s.profit_f64()))
+            .collect();
+
+        DurationCorrelation {
+            sample_size: pairs.len() as u64,
+            correlation: pearson_correlation(&pairs),
+        }
+    }
+}
+
+/// Running totals for every session up to and including `as_of`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CumulativeStats {
+    pub as_of: NaiveDate,
+    pub session_count: u64,
+    pub total_profit: BigDecimal,
+    pub total_hours: BigDecimal,
+}
+
+impl CumulativeStats {
+    pub fn from_sessions(as_of: NaiveDate, sessions: &[PokerSession]) -> Self {
+        let total_profit = sessions.iter().map(PokerSession::profit).sum();
+        let total_minutes: i64 = sessions.iter().map(|s| s.duration_minutes as i64).sum();
+        let total_hours = (BigDecimal::from(total_minutes) / BigDecimal::from(60))
+            .with_scale_round(2, RoundingMode::HalfUp);
+
+        CumulativeStats {
+            as_of,
+            session_count: sessions.len() as u64,
+            total_profit,
+            total_hours,
+        }
+    }
+}
+
+/// Cash games measured by hourly rate and tournaments by return on
+/// investment, side by side, since one blended figure suits neither
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameTypeDashboard {
+    pub cash: CashGameStats,
+    pub tournament: TournamentStats,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CashGameStats {
+    pub session_count: u64,
+    pub total_profit: BigDecimal,
+    pub total_hours: BigDecimal,
+    /// Total profit over total hours; `None` without recorded hours
+    pub profit_per_hour: Option<BigDecimal>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TournamentStats {
+    pub tournament_count: u64,
+    /// Buy-ins plus rebuys
+    pub total_invested: BigDecimal,
+    pub total_profit: BigDecimal,
+    /// Total profit over total invested, e.g. 0.25 for +25%; `None` when
+    /// nothing was invested
+    pub roi: Option<BigDecimal>,
+    /// Tournaments with any cash-out
+    pub itm_count: u64,
+    /// Share of tournaments finished in the money; `None` without tournaments
+    pub itm_rate: Option<BigDecimal>,
+}
+
+impl GameTypeDashboard {
+    pub fn from_sessions(sessions: &[PokerSession]) -> Self {
+        let (tournaments, cash): (Vec<&PokerSession>, Vec<&PokerSession>) = sessions
+            .iter()
+            .partition(|s| GameType::from_name(&s.game_type) == Some(GameType::Tournament));
+
+        GameTypeDashboard {
+            cash: CashGameStats::from_sessions(&cash),
+            tournament: TournamentStats::from_sessions(&tournaments),
+        }
+    }
+}
+
+impl CashGameStats {
+    fn from_sessions(sessions: &[&PokerSession]) -> Self {
+        let total_profit: BigDecimal = sessions.iter().map(|s| s.profit()).sum();
+        let total_minutes: i64 = sessions.iter().map(|s| s.duration_minutes as i64).sum();
+        let profit_per_hour = (total_minutes > 0).then(|| {
+            (&total_profit * BigDecimal::from(60) / BigDecimal::from(total_minutes))
+                .with_scale_round(2, RoundingMode::HalfUp)
+        });
+
+        CashGameStats {
+            session_count: sessions.len() as u64,
+            total_profit,
+            total_hours: (BigDecimal::from(total_minutes) / BigDecimal::from(60))
+                .with_scale_round(2, RoundingMode::HalfUp),
+            profit_per_hour,
+        }
+    }
+}
+
+impl TournamentStats {
+    fn from_sessions(sessions: &[&PokerSession]) -> Self {
+        let total_invested: BigDecimal = sessions
+            .iter()
+            .map(|s| &s.buy_in_amount + &s.rebuy_amount)
+            .sum();
+        let total_profit: BigDecimal = sessions.iter().map(|s| s.profit()).sum();
+        let itm_count = sessions
+            .iter()
+            .filter(|s| s.cash_out_amount.is_positive())
+            .count() as u64;
+        let tournament_count = sessions.len() as u64;
+
+        let roi = total_invested
+            .is_positive()
+            .then(|| (&total_profit / &total_invested).with_scale_round(4, RoundingMode::HalfUp));
+        let itm_rate = (tournament_count > 0).then(|| {
+            (BigDecimal::from(itm_count) / BigDecimal::from(tournament_count))
+                .with_scale_round(4, RoundingMode::HalfUp)
+        });
+
+        TournamentStats {
+            tournament_count,
+            total_invested,
+            total_profit,
+            roi,
+            itm_count,
+            itm_rate,
+        }
+    }
+}
+
+/// The bankroll at the end of one day
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BankrollPoint {
+    pub date: NaiveDate,
+    pub balance: BigDecimal,
+}
+
+/// Bankroll after each day with sessions, starting from `starting_bankroll`.
+/// Sessions on the same day make a single point. With `fill_gaps`, days
+/// without sessions between the first and last carry the balance forward.
+pub fn bankroll_series(
+    sessions: &[PokerSession],
+    starting_bankroll: &BigDecimal,
+    fill_gaps: bool,
+) -> Vec<BankrollPoint> {
+    let mut daily_profit: BTreeMap<NaiveDate, BigDecimal> = BTreeMap::new();
+    for session in sessions {
+        *daily_profit
+            .entry(session.session_date)
+            .or_insert_with(|| BigDecimal::from(0)) += session.profit();
+    }
+
+    let mut balance = starting_bankroll.clone();
+    let mut series: Vec<BankrollPoint> = Vec::new();
+    for (date, profit) in daily_profit {
+        let previous_date = series.last().map(|point| point.date);
+        if let Some(previous_date) = previous_date.filter(|_| fill_gaps) {
+            for gap_date in previous_date
+                .iter_days()
+                .skip(1)
+                .take_while(|day| *day < date)
+            {
+                series.push(BankrollPoint {
+                    date: gap_date,
+                    balance: balance.clone(),
+                });
+            }
+        }
+        balance += profit;
+        series.push(BankrollPoint {
+            date,
+            balance: balance.clone(),
+        });
+    }
+
+    series
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RiskOfRuin {
+    pub bankroll: BigDecimal,
+    pub session_count: u64,
+    /// Mean per-session profit; this and `std_dev` are rounded to cents
+    pub mean_profit: BigDecimal,
+    pub std_dev: BigDecimal,
+    pub risk_of_ruin: f64,
+}
+
+/// Estimate the probability of losing the entire bankroll.
+///
+/// Uses the classic diffusion approximation `exp(-2 * bankroll * mean / variance)`,
+/// where `mean` and `variance` are per-session profit. It assumes sessions are
+/// independent and identically distributed, that results are roughly normal, and
+/// that stakes stay fixed as the bankroll changes. A non-positive mean means ruin
+/// is certain in the long run; zero variance with a positive mean means it never
+/// happens.
+pub fn risk_of_ruin(bankroll: f64, mean: f64, variance: f64) -> f64 {
+    if mean <= 0.0 {
+        return 1.0;
+    }
+    if variance <= 0.0 {
+        return 0.0;
+    }
+    (-2.0 * bankroll * mean / variance).exp().min(1.0)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KellyStake {
+    pub bankroll: BigDecimal,
+    pub session_count: u64,
+    /// Mean per-session profit; this and `std_dev` are rounded to cents
+    pub mean_profit: BigDecimal,
+    pub std_dev: BigDecimal,
+    /// Mean buy-in plus rebuys: the amount put at risk per session at current stakes
+    pub average_buy_in: BigDecimal,
+    /// Fraction of the bankroll to put at risk per session at full Kelly
+    pub kelly_fraction: f64,
+    /// Half Kelly, the usual practical choice given the estimates' noise
+    pub half_kelly_fraction: f64,
+    /// `kelly_fraction * bankroll`: the full-Kelly buy-in
+    pub suggested_buy_in: BigDecimal,
+}
+
+/// Kelly-optimal fraction of the bankroll to put at risk per session.
+///
+/// Playing at `k` times the current stakes scales each session's profit by
+/// `k`, so log-bankroll growth is approximately `k * mean / B - k^2 * variance
+/// / (2 * B^2)`, maximized at `k = B * mean / variance`. The resulting buy-in
+/// as a fraction of the bankroll `B` is `average_buy_in * mean / variance`.
+///
+/// This assumes sessions are independent, that results scale linearly with
+/// stakes (win rate doesn't change when moving up), and that the sample mean
+/// and variance are the true ones, which they rarely are for small samples.
+/// A non-positive mean gives 0 (no stake is favorable); the result is capped
+/// at 1, i.e. never more than the whole bankroll.
+pub fn kelly_fraction(mean: f64, variance: f64, average_buy_in: f64) -> f64 {
+    if mean <= 0.0 {
+        return 0.0;
+    }
+    if variance <= 0.0 {
+        return 1.0;
+    }
+    (average_buy_in * mean / variance).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    const TOLERANCE: f64 = 1e-9;
+
+    fn session(duration_minutes: i32, profit: i64) -> PokerSession {
+        PokerSession {
+            id: uuid::Uuid::new_v4(),
+            user_id: uuid::Uuid::new_v4(),
+            session_date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            duration_minutes,
+            buy_in_amount: BigDecimal::from(100),
+            rebuy_amount: BigDecimal::from(0),
+            cash_out_amount: BigDecimal::from(100 + profit),
+            notes: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            category: None,
+            start_time: None,
+            confirmed: true,
+            game_type: "cash".to_string(),
+            stakes: None,
+            location: None,
+            latitude: None,
+            longitude: None,
+        }
+    }
+
+    #[test]
+    fn test_overall_profit_per_hour_is_pooled_rate() {
+        // 100 over 1 hour and 0 over 9 hours: pooled 100 / 10 = 10 per hour,
+        // whereas the mean of per-session rates would be (100 + 0) / 2 = 50
+        let stats = SessionStats::from_sessions(&[session(60, 100), session(540, 0)]);
+        assert!((stats.total_hours - 10.0).abs() < TOLERANCE);
+        assert!((stats.overall_profit_per_hour.unwrap() - 10.0).abs() < TOLERANCE);
+    }
+
+    fn session_on(day: u32, profit: i64) -> PokerSession {
+        PokerSession {
+            session_date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+            ..session(60, profit)
+        }
+    }
+
+    fn categorized(category: &str, duration_minutes: i32, profit: i64) -> PokerSession {
+        PokerSession {
+            category: Some(category.to_string()),
+            ..session(duration_minutes, profit)
+        }
+    }
+
+    #[test]
+    fn test_rankings_sorted_by_profit_per_hour_above_threshold() {
+        let sessions = vec![
+            categorized("live", 600, 200),   // 20/h over 10h
+            categorized("online", 300, 250), // 50/h over 5h
+            categorized("home", 60, 500),    // 500/h, but only 1h
+            session(600, -100),              // uncategorized, -10/h over 10h
+        ];
+
+        let rankings = Rankings::from_sessions(sessions, 5.0);
+
+        let names: Vec<Option<&str>> = rankings
+            .categories
+            .iter()
+            .map(|entry| entry.name.as_deref())
+            .collect();
+        assert_eq!(names, vec![Some("online"), Some("live"), None]);
+        assert_eq!(rankings.categories[0].session_count, 1);
+        assert!((rankings.categories[0].profit_per_hour - 50.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_rankings_cover_locations_game_types_and_stakes() {
+        let at = |location: &str, stakes: &str, game_type: &str, minutes, profit| PokerSession {
+            location: Some(location.to_string()),
+            stakes: Some(stakes.to_string()),
+            game_type: game_type.to_string(),
+            ..session(minutes, profit)
+        };
+        let sessions = vec![
+            at("Aria", "2/5", "cash", 600, 300),        // 30/h over 10h
+            at("Bellagio", "1/2", "cash", 600, 100),    // 10/h over 10h
+            at("Wynn", "2/5", "tournament", 120, 1000), // only 2h
+        ];
+
+        let rankings = Rankings::from_sessions(sessions, 5.0);
+
+        let names = |entries: &[RankingEntry]| -> Vec<Option<String>> {
+            entries.iter().map(|entry| entry.name.clone()).collect()
+        };
+        assert_eq!(
+            names(&rankings.locations),
+            vec![Some("Aria".to_string()), Some("Bellagio".to_string())]
+        );
+        assert_eq!(names(&rankings.game_types), vec![Some("cash".to_string())]);
+        assert_eq!(rankings.game_types[0].session_count, 2);
+        // 2/5 pools Aria and Wynn: 1300 over 12h
+        assert_eq!(
+            names(&rankings.stakes),
+            vec![Some("2/5".to_string()), Some("1/2".to_string())]
+        );
+        assert!((rankings.stakes[0].total_hours - 12.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_bankroll_series_aggregates_same_day_sessions() {
+        let sessions = vec![session_on(1, 50), session_on(1, -20), session_on(4, 100)];
+
+        let series = bankroll_series(&sessions, &BigDecimal::from(1000), false);
+
+        let points: Vec<(u32, BigDecimal)> = series
+            .into_iter()
+            .map(|point| (point.date.day(), point.balance))
+            .collect();
+        assert_eq!(
+            points,
+            vec![(1, BigDecimal::from(1030)), (4, BigDecimal::from(1130))]
+        );
+    }
+
+    #[test]
+    fn test_bankroll_series_fill_gaps_carries_balance_forward() {
+        let sessions = vec![session_on(4, 100), session_on(1, 30)];
+
+        let series = bankroll_series(&sessions, &BigDecimal::from(1000), true);
+
+        let points: Vec<(u32, BigDecimal)> = series
+            .into_iter()
+            .map(|point| (point.date.day(), point.balance))
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                (1, BigDecimal::from(1030)),
+                (2, BigDecimal::from(1030)),
+                (3, BigDecimal::from(1030)),
+                (4, BigDecimal::from(1130)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bankroll_series_empty() {
+        assert!(bankroll_series(&[], &BigDecimal::from(1000), true).is_empty());
+    }
+
+    #[test]
+    fn test_rolling_stats_windows() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let days_ago = |days: i64, profit: i64| PokerSession {
+            session_date: today - chrono::Duration::days(days),
+            ..session(60, profit)
+        };
+        let sessions = vec![
+            days_ago(0, 10),
+            days_ago(30, 20),
+            days_ago(31, 40),
+            days_ago(100, 80),
+            days_ago(400, 160),
+        ];
+
+        let rolling = RollingStats::from_sessions(&sessions, today);
+
+        assert_eq!(rolling.last_30.session_count, 2);
+        assert_eq!(rolling.last_30.total_profit, BigDecimal::from(30));
+        assert_eq!(rolling.last_90.session_count, 3);
+        assert_eq!(rolling.last_365.session_count, 4);
+        assert_eq!(rolling.last_365.total_profit, BigDecimal::from(150));
+        assert_eq!(rolling.all_time.session_count, 5);
+        assert!((rolling.all_time.profit_per_hour.unwrap() - 62.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_weekday_vs_weekend_splits_on_session_date() {
+        let on = |date: &str, duration_minutes: i32, profit: i64| PokerSession {
+            session_date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            ..session(duration_minutes, profit)
+        };
+        let sessions = vec![
+            on("2024-01-12", 120, 40),  // Friday
+            on("2024-01-13", 60, 100),  // Saturday
+            on("2024-01-14", 180, -40), // Sunday
+            on("2024-01-15", 60, -10),  // Monday
+        ];
+
+        let stats = WeekdayWeekendStats::from_sessions(&sessions, 0);
+
+        assert_eq!(stats.weekday.session_count, 2);
+        assert_eq!(stats.weekday.total_profit, BigDecimal::from(30));
+        assert!((stats.weekday.total_hours - 3.0).abs() < TOLERANCE);
+        assert!((stats.weekday.profit_per_hour.unwrap() - 10.0).abs() < TOLERANCE);
+        assert_eq!(stats.weekend.session_count, 2);
+        assert_eq!(stats.weekend.total_profit, BigDecimal::from(60));
+        assert!((stats.weekend.profit_per_hour.unwrap() - 15.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_weekday_vs_weekend_empty_groups() {
+        let stats = WeekdayWeekendStats::from_sessions(&[], 0);
+        assert_eq!(stats.weekday.session_count, 0);
+        assert_eq!(stats.weekday.total_profit, BigDecimal::from(0));
+        assert!(stats.weekend.profit_per_hour.is_none());
+    }
+
+    #[test]
+    fn test_weekday_vs_weekend_day_cutoff_shifts_early_sessions() {
+        let at = |date: &str, start_time: Option<&str>| PokerSession {
+            session_date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            start_time: start_time.map(|t| NaiveTime::parse_from_str(t, "%H:%M").unwrap()),
+            ..session(60, 10)
+        };
+        let sessions = vec![
+            at("2024-01-13", Some("02:00")), // Saturday 2am: Friday night's play
+            at("2024-01-13", Some("04:00")), // Saturday at the cutoff: Saturday
+            at("2024-01-13", None),          // no start time: stays on Saturday
+        ];
+
+        let midnight = WeekdayWeekendStats::from_sessions(&sessions, 0);
+        assert_eq!(midnight.weekday.session_count, 0);
+        assert_eq!(midnight.weekend.session_count, 3);
+
+        let shifted = WeekdayWeekendStats::from_sessions(&sessions, 4);
+        assert_eq!(shifted.weekday.session_count, 1);
+        assert_eq!(shifted.weekend.session_count, 2);
+
+        // Early Monday play belongs to Sunday night, so it moves into the weekend
+        let monday = at("2024-01-15", Some("03:59"));
+        assert_eq!(monday.play_date(4).weekday(), Weekday::Sun);
+        assert_eq!(monday.play_date(0), monday.session_date);
+    }
+
+    #[test]
+    fn test_max_drawdown_finds_largest_peak_to_trough() {
+        // Cumulative: 100, 300 (peak), 150, 50 (trough), 250, 400 (new peak), 300.
+        // The 300 -> 50 drop (250) beats the later 400 -> 300 one (100).
+        let sessions = [
+            session_on(1, 100),
+            session_on(2, 200),
+            session_on(3, -150),
+            session_on(4, -100),
+            session_on(5, 200),
+            session_on(6, 150),
+            session_on(7, -100),
+        ];
+
+        let drawdown = MaxDrawdown::from_sessions(&sessions).unwrap();
+
+        assert_eq!(drawdown.amount, BigDecimal::from(250));
+        assert_eq!(
+            drawdown.peak_date,
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+        );
+        assert_eq!(
+            drawdown.trough_date,
+            NaiveDate::from_ymd_opt(2024, 1, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_max_drawdown_zero_when_never_below_peak() {
+        let sessions = [session_on(1, 100), session_on(2, 50)];
+
+        let drawdown = MaxDrawdown::from_sessions(&sessions).unwrap();
+
+        assert_eq!(drawdown.amount, BigDecimal::from(0));
+        assert_eq!(drawdown.peak_date, drawdown.trough_date);
+    }
+
+    #[test]
+    fn test_max_drawdown_counts_an_opening_losing_run() {
+        // Cumulative: -100, -300 (trough), -250; the peak is the 0 start
+        let sessions = [session_on(1, -100), session_on(2, -200), session_on(3, 50)];
+
+        let drawdown = MaxDrawdown::from_sessions(&sessions).unwrap();
+
+        assert_eq!(drawdown.amount, BigDecimal::from(300));
+        assert_eq!(
+            drawdown.peak_date,
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
+        assert_eq!(
+            drawdown.trough_date,
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_max_drawdown_null_with_fewer_than_two_sessions() {
+        assert!(MaxDrawdown::from_sessions(&[]).is_none());
+        assert!(MaxDrawdown::from_sessions(&[session_on(1, -100)]).is_none());
+    }
+
+    #[test]
+    fn test_cadence_over_first_to_last_session_span() {
+        // Jan 1 to Jan 29 is 4 weeks; five 1-hour sessions in any order
+        let sessions = [
+            session_on(29, 0),
+            session_on(1, 0),
+            session_on(8, 0),
+            session_on(15, 0),
+            session_on(15, 0),
+        ];
+        let stats = SessionStats::from_sessions(&sessions);
+        assert!((stats.avg_sessions_per_week.unwrap() - 1.25).abs() < TOLERANCE);
+        assert!((stats.avg_hours_per_week.unwrap() - 1.25).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_cadence_null_without_a_span() {
+        let single = SessionStats::from_sessions(&[session_on(1, 0)]);
+        assert!(single.avg_sessions_per_week.is_none());
+        assert!(single.avg_hours_per_week.is_none());
+
+        let same_day = SessionStats::from_sessions(&[session_on(1, 0), session_on(1, 0)]);
+        assert!(same_day.avg_sessions_per_week.is_none());
+        assert!(same_day.avg_hours_per_week.is_none());
+    }
+
+    #[test]
+    fn test_dashboard_splits_cash_and_tournaments() {
+        let tournament = |buy_in: i64, cash_out: i64| PokerSession {
+            game_type: GameType::Tournament.name().to_string(),
+            buy_in_amount: BigDecimal::from(buy_in),
+            cash_out_amount: BigDecimal::from(cash_out),
+            ..session(300, 0)
+        };
+        let sessions = [
+            session(60, 30),
+            session(180, 90),
+            tournament(100, 0),
+            tournament(100, 0),
+            tournament(100, 0),
+            tournament(100, 500),
+        ];
+
+        let dashboard = GameTypeDashboard::from_sessions(&sessions);
+        assert_eq!(dashboard.cash.session_count, 2);
+        assert_eq!(dashboard.cash.total_profit, BigDecimal::from(120));
+        assert_eq!(dashboard.cash.profit_per_hour, Some(BigDecimal::from(30)));
+        // 500 back on 400 invested
+        assert_eq!(dashboard.tournament.tournament_count, 4);
+        assert_eq!(dashboard.tournament.total_invested, BigDecimal::from(400));
+        assert_eq!(dashboard.tournament.roi, Some("0.25".parse().unwrap()));
+        assert_eq!(dashboard.tournament.itm_count, 1);
+        assert_eq!(dashboard.tournament.itm_rate, Some("0.25".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_dashboard_empty_blocks_have_no_rates() {
+        let dashboard = GameTypeDashboard::from_sessions(&[]);
+        assert!(dashboard.cash.profit_per_hour.is_none());
+        assert!(dashboard.tournament.roi.is_none());
+        assert!(dashboard.tournament.itm_rate.is_none());
+    }
+
+    #[test]
+    fn test_overall_profit_per_hour_null_without_hours() {
+        let stats = SessionStats::from_sessions(&[]);
+        assert_eq!(stats.total_hours, 0.0);
+        assert!(stats.overall_profit_per_hour.is_none());
+    }
+
+    #[test]
+    fn test_buy_in_buckets_boundaries_are_half_open() {
+        let mut sessions = vec![session(60, 10), session(60, 20), session(120, -30)];
+        sessions[0].buy_in_amount = BigDecimal::from(99);
+        sessions[0].cash_out_amount = BigDecimal::from(109);
+        sessions[1].buy_in_amount = BigDecimal::from(100);
+        sessions[1].cash_out_amount = BigDecimal::from(120);
+        sessions[2].buy_in_amount = BigDecimal::from(199);
+        sessions[2].cash_out_amount = BigDecimal::from(169);
+
+        let buckets = BuyInBucket::group_sessions(&sessions, &BigDecimal::from(100));
+
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].bucket_min, BigDecimal::from(0));
+        assert_eq!(buckets[0].bucket_max, BigDecimal::from(100));
+        assert_eq!(buckets[0].session_count, 1);
+        assert_eq!(buckets[1].bucket_min, BigDecimal::from(100));
+        assert_eq!(buckets[1].session_count, 2);
+        // -10 over 3 hours
+        assert_eq!(buckets[1].total_profit, BigDecimal::from(-10));
+        assert!((buckets[1].profit_per_hour.unwrap() + 10.0 / 3.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_kelly_fraction_matches_formula() {
+        assert!((kelly_fraction(25.0, 6250.0, 100.0) - 0.4).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_kelly_fraction_edge_cases() {
+        assert_eq!(kelly_fraction(-5.0, 100.0, 100.0), 0.0);
+        assert_eq!(kelly_fraction(0.0, 100.0, 100.0), 0.0);
+        assert_eq!(kelly_fraction(5.0, 0.0, 100.0), 1.0);
+        assert_eq!(kelly_fraction(1000.0, 1.0, 100.0), 1.0);
+    }
+
+    #[test]
+    fn test_running_stats_empty() {
+        let stats = RunningStats::default();
+        assert_eq!(stats.count(), 0);
+        assert!(stats.mean().is_none());
+        assert!(stats.variance().is_none());
+        assert!(stats.std_dev().is_none());
+    }
+
+    #[test]
+    fn test_running_stats_single_value_has_no_variance() {
+        let stats: RunningStats = [50.0].into_iter().collect();
+        assert_eq!(stats.mean(), Some(50.0));
+        assert!(stats.variance().is_none());
+    }
+
+    #[test]
+    fn test_running_stats_matches_hand_computation() {
+        // Profits 100, -50, 25, 75, -150: mean 0, squared deviations sum to
+        // 10000 + 2500 + 625 + 5625 + 22500 = 41250, sample variance 41250 / 4.
+        let stats: RunningStats = [100.0, -50.0, 25.0, 75.0, -150.0].into_iter().collect();
+        assert!(stats.mean().unwrap().abs() < TOLERANCE);
+        assert!((stats.variance().unwrap() - 10312.5).abs() < TOLERANCE);
+        assert!((stats.std_dev().unwrap() - 10312.5_f64.sqrt()).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_running_stats_stable_with_large_offset() {
+        // The naive sum-of-squares formula loses all precision here
+        let stats: RunningStats = [1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0]
+            .into_iter()
+            .collect();
+        assert!((stats.variance().unwrap() - 30.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_risk_of_ruin_known_values() {
+        // mean 10, variance 10000, bankroll 1000: exp(-2 * 1000 * 10 / 10000) = exp(-2)
+        let risk = risk_of_ruin(1000.0, 10.0, 10000.0);
+        assert!((risk - (-2.0_f64).exp()).abs() < TOLERANCE);
+        assert!((risk - 0.1353352832366127).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_risk_of_ruin_decreases_with_bankroll() {
+        let small = risk_of_ruin(500.0, 10.0, 10000.0);
+        let large = risk_of_ruin(5000.0, 10.0, 10000.0);
+        assert!(large < small);
+    }
+
+    #[test]
+    fn test_risk_of_ruin_losing_player_is_certain() {
+        assert_eq!(risk_of_ruin(1000.0, 0.0, 10000.0), 1.0);
+        assert_eq!(risk_of_ruin(1000.0, -5.0, 10000.0), 1.0);
+    }
+
+    #[test]
+    fn test_risk_of_ruin_zero_variance_winner_is_zero() {
+        assert_eq!(risk_of_ruin(1000.0, 10.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_pearson_correlation_perfect_positive() {
+        let pairs = [(60.0, 10.0), (120.0, 20.0), (180.0, 30.0), (240.0, 40.0)];
+        assert!((pearson_correlation(&pairs).unwrap() - 1.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_pearson_correlation_perfect_negative() {
+        let pairs = [(60.0, 30.0), (120.0, 20.0), (180.0, 10.0)];
+        assert!((pearson_correlation(&pairs).unwrap() + 1.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_pearson_correlation_matches_hand_computation() {
+        // x mean 2, y mean 3; sum dx*dy = 2, sum dx^2 = 2, sum dy^2 = 8: r = 2 / 4
+        let pairs = [(1.0, 1.0), (2.0, 5.0), (3.0, 3.0)];
+        assert!((pearson_correlation(&pairs).unwrap() - 0.5).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_pearson_correlation_requires_three_points() {
+        assert!(pearson_correlation(&[(1.0, 2.0), (2.0, 4.0)]).is_none());
+    }
+
+    #[test]
+    fn test_pearson_correlation_constant_series_is_none() {
+        assert!(pearson_correlation(&[(60.0, 10.0), (60.0, 20.0), (60.0, 30.0)]).is_none());
+    }
+}
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use diesel::{AsChangeset, Insertable, Queryable};
+use uuid::Uuid;
+
+use crate::schema::user_stats_cache;
+
+/// A user's all-time [`SessionStats`](crate::models::SessionStats) as of the
+/// last refresh, stored as JSON
+#[derive(Debug, Clone, Queryable, Insertable, AsChangeset)]
+#[diesel(table_name = user_stats_cache)]
+pub struct UserStatsCache {
+    pub user_id: Uuid,
+    pub stats: String,
+    pub session_count: i64,
+    pub sessions_updated_at: Option<DateTime<Utc>>,
+    pub profit_convention: String,
+    pub refreshed_at: DateTime<Utc>,
+}
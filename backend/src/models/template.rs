@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::templates;
+
+/// A reusable notes skeleton, e.g. "Villain read / Key hand / Takeaway"
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct Template {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = templates)]
+pub struct NewTemplate {
+    pub user_id: Uuid,
+    pub name: String,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTemplateRequest {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+    #[validate(length(min = 1, message = "Body must not be empty"))]
+    pub body: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_template_request_valid() {
+        let req = CreateTemplateRequest {
+            name: "Standard".to_string(),
+            body: "Villain read:\nKey hand:\nTakeaway:".to_string(),
+        };
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_create_template_request_empty_fields() {
+        let req = CreateTemplateRequest {
+            name: String::new(),
+            body: String::new(),
+        };
+        let errors = req.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("name"));
+        assert!(errors.field_errors().contains_key("body"));
+    }
+}
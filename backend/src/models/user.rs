@@ -1,9 +1,11 @@
-use chrono::NaiveDateTime;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use diesel::{Insertable, Queryable};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::models::{GameType, ProfitConvention};
 use crate::schema::users;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
@@ -14,9 +16,19 @@ pub struct User {
     #[serde(skip_serializing, default)]
     pub password_hash: String,
     pub cookie_consent: bool,
-    pub cookie_consent_date: Option<NaiveDateTime>,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
+    pub cookie_consent_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub is_admin: bool,
+    pub must_change_password: bool,
+    /// Used by `POST /api/sessions` when no buy-in is given
+    pub default_buy_in: Option<BigDecimal>,
+    /// Name of the user's `ProfitConvention`; `None` uses the default
+    pub profit_convention: Option<String>,
+    /// Used by `POST /api/sessions` when no location is given
+    pub default_location: Option<String>,
+    /// Name of the `GameType` used by `POST /api/sessions` when none is given
+    pub default_game_type: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Validate, Insertable)]
@@ -65,6 +77,33 @@ pub struct UpdateCookieConsent {
     pub cookie_consent: bool,
 }
 
+/// Updates the user's session defaults. Omitted fields are left as they are;
+/// `null` clears one (`Some(None)`).
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct UpdateProfileRequest {
+    #[validate(range(min = 0.0, message = "Default buy-in must not be negative"))]
+    #[serde(default, deserialize_with = "present")]
+    pub default_buy_in: Option<Option<f64>>,
+    /// How profit is counted in stats and exports; `null` restores the default
+    #[serde(default, deserialize_with = "present")]
+    pub profit_convention: Option<Option<ProfitConvention>>,
+    #[validate(length(max = 100, message = "Default location must be at most 100 characters"))]
+    #[serde(default, deserialize_with = "present")]
+    pub default_location: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub default_game_type: Option<Option<GameType>>,
+}
+
+/// Marks a field as present, even when it is `null`, so it can be told apart
+/// from one that was omitted
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct ChangePasswordRequest {
     pub old_password: String,
@@ -72,6 +111,38 @@ pub struct ChangePasswordRequest {
     pub new_password: String,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct AdminCreateUserRequest {
+    #[validate(email(message = "Invalid email address"))]
+    pub email: String,
+    #[validate(length(
+        min = 3,
+        max = 100,
+        message = "Username must be between 3 and 100 characters"
+    ))]
+    pub username: String,
+    /// Temporary password; one is generated when omitted
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminCreateUserResponse {
+    pub user: User,
+    /// Only present when the password was generated; it is not retrievable later
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temporary_password: Option<String>,
+}
+
+/// A short-lived token acting as another user, for support
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImpersonationResponse {
+    pub token: String,
+    pub user: User,
+    pub impersonated_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 #[allow(dead_code)]
 pub struct ResetPasswordRequest {
@@ -221,6 +292,30 @@ mod tests {
         assert!(errors.field_errors().contains_key("new_password"));
     }
 
+    // AdminCreateUserRequest validation tests
+    #[test]
+    fn test_admin_create_user_request_without_password_valid() {
+        let req = AdminCreateUserRequest {
+            email: "test@example.com".to_string(),
+            username: "validuser".to_string(),
+            password: None,
+        };
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_admin_create_user_request_short_password() {
+        let req = AdminCreateUserRequest {
+            email: "test@example.com".to_string(),
+            username: "validuser".to_string(),
+            password: Some("short".to_string()),
+        };
+        let result = req.validate();
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.field_errors().contains_key("password"));
+    }
+
     // NewUser validation tests
     #[test]
     fn test_new_user_valid() {
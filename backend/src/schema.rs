@@ -1,5 +1,34 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 64]
+        key_hash -> Varchar,
+        #[max_length = 100]
+        label -> Varchar,
+        read_only -> Bool,
+        created_at -> Timestamptz,
+        last_used_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    audit_log (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 50]
+        action -> Varchar,
+        created_at -> Timestamptz,
+        #[max_length = 45]
+        ip -> Nullable<Varchar>,
+        #[max_length = 500]
+        user_agent -> Nullable<Varchar>,
+        target_user_id -> Nullable<Uuid>,
+    }
+}
+
 diesel::table! {
     poker_sessions (id) {
         id -> Uuid,
@@ -10,8 +39,51 @@ diesel::table! {
         rebuy_amount -> Numeric,
         cash_out_amount -> Numeric,
         notes -> Nullable<Text>,
-        created_at -> Timestamp,
-        updated_at -> Timestamp,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        #[max_length = 50]
+        category -> Nullable<Varchar>,
+        start_time -> Nullable<Time>,
+        confirmed -> Bool,
+        #[max_length = 20]
+        game_type -> Varchar,
+        #[max_length = 20]
+        stakes -> Nullable<Varchar>,
+        #[max_length = 100]
+        location -> Nullable<Varchar>,
+        latitude -> Nullable<Float8>,
+        longitude -> Nullable<Float8>,
+    }
+}
+
+diesel::table! {
+    revoked_tokens (jti) {
+        jti -> Uuid,
+        user_id -> Uuid,
+        expires_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    templates (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        body -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    user_stats_cache (user_id) {
+        user_id -> Uuid,
+        stats -> Text,
+        session_count -> Int8,
+        sessions_updated_at -> Nullable<Timestamptz>,
+        #[max_length = 20]
+        profit_convention -> Varchar,
+        refreshed_at -> Timestamptz,
     }
 }
 
@@ -22,12 +94,33 @@ diesel::table! {
         username -> Varchar,
         password_hash -> Varchar,
         cookie_consent -> Bool,
-        cookie_consent_date -> Nullable<Timestamp>,
-        created_at -> Timestamp,
-        updated_at -> Timestamp,
+        cookie_consent_date -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        is_admin -> Bool,
+        must_change_password -> Bool,
+        default_buy_in -> Nullable<Numeric>,
+        #[max_length = 20]
+        profit_convention -> Nullable<Varchar>,
+        #[max_length = 100]
+        default_location -> Nullable<Varchar>,
+        #[max_length = 20]
+        default_game_type -> Nullable<Varchar>,
     }
 }
 
+diesel::joinable!(api_keys -> users (user_id));
 diesel::joinable!(poker_sessions -> users (user_id));
+diesel::joinable!(revoked_tokens -> users (user_id));
+diesel::joinable!(templates -> users (user_id));
+diesel::joinable!(user_stats_cache -> users (user_id));
 
-diesel::allow_tables_to_appear_in_same_query!(poker_sessions, users,);
+diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    audit_log,
+    poker_sessions,
+    revoked_tokens,
+    templates,
+    user_stats_cache,
+    users,
+);
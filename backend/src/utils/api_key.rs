@@ -0,0 +1,47 @@
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Marks API keys so they are recognizable in config files and secret scanners
+const API_KEY_PREFIX: &str = "ptk_";
+
+/// Generate a new API key with 244 random bits from two v4 UUIDs
+pub fn generate_api_key() -> String {
+    format!(
+        "{}{}{}",
+        API_KEY_PREFIX,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+/// Hex SHA-256 of an API key, the only form in which keys are stored.
+/// Keys are long and random, so an unsalted fast hash is enough to look them
+/// up without keeping the key itself.
+pub fn hash_api_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_api_key_is_prefixed_and_unique() {
+        let key = generate_api_key();
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.len(), API_KEY_PREFIX.len() + 64);
+        assert_ne!(key, generate_api_key());
+    }
+
+    #[test]
+    fn test_hash_api_key_is_stable_hex_sha256() {
+        assert_eq!(
+            hash_api_key("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(hash_api_key("abc"), hash_api_key("abd"));
+    }
+}
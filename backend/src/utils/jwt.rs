@@ -1,18 +1,71 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use uuid::Uuid;
 
+use crate::models::RevokedToken;
+use crate::schema::{revoked_tokens, users};
+use crate::utils::DbProvider;
+
+/// Impersonation tokens are for short support sessions only
+pub const IMPERSONATION_TOKEN_MINUTES: i64 = 15;
+
+/// Pause between purge batches, so other queries on `revoked_tokens` get a turn
+const PURGE_BATCH_PAUSE: std::time::Duration = std::time::Duration::from_millis(10);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // user_id
     pub exp: usize,  // expiration time
     pub iat: usize,  // issued at
+    /// Admin user_id when the token was minted via impersonation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<String>,
+    /// Unique token id, checked against `revoked_tokens` on each request.
+    /// Absent on tokens issued before logout existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+}
+
+/// Mint a login token for `user_id`, valid for `expiry_hours`
+pub fn create_jwt(
+    user_id: Uuid,
+    jwt_secret: &str,
+    expiry_hours: u32,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    encode_claims(
+        user_id,
+        Duration::hours(i64::from(expiry_hours)),
+        None,
+        jwt_secret,
+    )
+}
+
+/// Mint a short-lived token acting as `user_id`, marked with the admin who
+/// requested it
+pub fn create_impersonation_jwt(
+    user_id: Uuid,
+    admin_id: Uuid,
+    jwt_secret: &str,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    encode_claims(
+        user_id,
+        Duration::minutes(IMPERSONATION_TOKEN_MINUTES),
+        Some(admin_id),
+        jwt_secret,
+    )
 }
 
-pub fn create_jwt(user_id: Uuid, jwt_secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
+fn encode_claims(
+    user_id: Uuid,
+    valid_for: Duration,
+    impersonated_by: Option<Uuid>,
+    jwt_secret: &str,
+) -> Result<String, jsonwebtoken::errors::Error> {
     let expiration = Utc::now()
-        .checked_add_signed(Duration::days(7))
+        .checked_add_signed(valid_for)
         .expect("valid timestamp")
         .timestamp();
 
@@ -20,6 +73,8 @@ pub fn create_jwt(user_id: Uuid, jwt_secret: &str) -> Result<String, jsonwebtoke
         sub: user_id.to_string(),
         exp: expiration as usize,
         iat: Utc::now().timestamp() as usize,
+        impersonated_by: impersonated_by.map(|id| id.to_string()),
+        jti: Some(Uuid::new_v4().to_string()),
     };
 
     encode(
@@ -29,26 +84,220 @@ pub fn create_jwt(user_id: Uuid, jwt_secret: &str) -> Result<String, jsonwebtoke
     )
 }
 
-pub fn decode_jwt(token: &str, jwt_secret: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
+/// Decode and validate a token, tolerating `leeway_secs` of clock skew on
+/// time-based claims
+pub fn decode_jwt(
+    token: &str,
+    jwt_secret: &str,
+    leeway_secs: u64,
+) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let mut validation = Validation::default();
+    validation.leeway = leeway_secs;
+
     let token_data = decode::<Claims>(
         token,
         &DecodingKey::from_secret(jwt_secret.as_bytes()),
-        &Validation::default(),
+        &validation,
     )?;
 
     Ok(token_data.claims)
 }
 
+#[derive(Debug, Error)]
+pub enum RefreshError {
+    #[error("Invalid or expired JWT token")]
+    InvalidToken,
+    #[error("Impersonation tokens can't be refreshed")]
+    Impersonation,
+    #[error("User no longer exists")]
+    UnknownUser,
+    #[error("Token has been revoked")]
+    Revoked,
+    #[error("Token has no jti claim; log in again")]
+    MissingJti,
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+    #[error(transparent)]
+    Revocation(#[from] RevocationError),
+    #[error("Token generation failed: {0}")]
+    Encoding(#[from] jsonwebtoken::errors::Error),
+}
+
+/// Swap a still-valid token for one valid for another `expiry_hours`, as long as its
+/// user still exists. Impersonation tokens are refused so a short support
+/// session can't be stretched into a regular one. The old token is revoked,
+/// so a logged-out token can't be refreshed and one refresh can't fork a
+/// token into two live ones; tokens without a `jti` can't be revoked, so they
+/// can't be refreshed either.
+pub fn refresh_jwt(
+    db_provider: &dyn DbProvider,
+    token: &str,
+    jwt_secret: &str,
+    leeway_secs: u64,
+    expiry_hours: u32,
+) -> Result<String, RefreshError> {
+    let claims =
+        decode_jwt(token, jwt_secret, leeway_secs).map_err(|_| RefreshError::InvalidToken)?;
+    if claims.impersonated_by.is_some() {
+        return Err(RefreshError::Impersonation);
+    }
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| RefreshError::InvalidToken)?;
+    let jti = claims.jti.as_deref().ok_or(RefreshError::MissingJti)?;
+    let jti = Uuid::parse_str(jti).map_err(|_| RefreshError::InvalidToken)?;
+    if is_jwt_revoked(db_provider, jti, leeway_secs)? {
+        return Err(RefreshError::Revoked);
+    }
+
+    let exists = {
+        let mut conn = db_provider
+            .get_connection()
+            .map_err(|_| RefreshError::DatabaseConnection)?;
+        diesel::select(diesel::dsl::exists(users::table.find(user_id)))
+            .get_result::<bool>(&mut conn)?
+    };
+    if !exists {
+        return Err(RefreshError::UnknownUser);
+    }
+
+    // A concurrent refresh or logout of the same token got there first
+    if !revoke_jwt(db_provider, &claims)? {
+        return Err(RefreshError::Revoked);
+    }
+
+    Ok(create_jwt(user_id, jwt_secret, expiry_hours)?)
+}
+
+#[derive(Debug, Error)]
+pub enum RevocationError {
+    #[error("Token has no jti claim")]
+    MissingJti,
+    #[error("Invalid token claims")]
+    InvalidClaims,
+    #[error("Database connection error")]
+    DatabaseConnection,
+    #[error("Database error: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+/// Record a token as logged out until it would have expired anyway.
+/// Revoking the same token twice is a no-op; returns whether this call was
+/// the one that revoked it.
+pub fn revoke_jwt(db_provider: &dyn DbProvider, claims: &Claims) -> Result<bool, RevocationError> {
+    let jti = claims.jti.as_deref().ok_or(RevocationError::MissingJti)?;
+    let jti = Uuid::parse_str(jti).map_err(|_| RevocationError::InvalidClaims)?;
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| RevocationError::InvalidClaims)?;
+    let expires_at = DateTime::<Utc>::from_timestamp(claims.exp as i64, 0)
+        .ok_or(RevocationError::InvalidClaims)?;
+
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| RevocationError::DatabaseConnection)?;
+    let inserted = diesel::insert_into(revoked_tokens::table)
+        .values(&RevokedToken {
+            jti,
+            user_id,
+            expires_at,
+        })
+        .on_conflict_do_nothing()
+        .execute(&mut conn)?;
+
+    Ok(inserted == 1)
+}
+
+/// Tokens that expired after this moment may still be accepted, given
+/// `decode_jwt`'s leeway, so their revocations have to be kept and honoured
+fn revocation_cutoff(leeway_secs: u64) -> DateTime<Utc> {
+    i64::try_from(leeway_secs)
+        .ok()
+        .and_then(Duration::try_seconds)
+        .and_then(|leeway| Utc::now().checked_sub_signed(leeway))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// Whether the token with this `jti` was logged out and could still be
+/// accepted, i.e. hasn't expired by more than `leeway_secs`
+pub fn is_jwt_revoked(
+    db_provider: &dyn DbProvider,
+    jti: Uuid,
+    leeway_secs: u64,
+) -> Result<bool, RevocationError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| RevocationError::DatabaseConnection)?;
+    let revoked = diesel::select(diesel::dsl::exists(
+        revoked_tokens::table
+            .find(jti)
+            .filter(revoked_tokens::expires_at.gt(revocation_cutoff(leeway_secs))),
+    ))
+    .get_result::<bool>(&mut conn)?;
+
+    Ok(revoked)
+}
+
+/// Delete revocations for tokens that expired more than `leeway_secs` ago,
+/// returning how many were removed. Rows go at most `batch_size` per
+/// statement, pausing between batches, so a large backlog never holds a
+/// long lock on the table.
+pub fn purge_expired_revocations(
+    db_provider: &dyn DbProvider,
+    leeway_secs: u64,
+    batch_size: u32,
+) -> Result<usize, RevocationError> {
+    let mut conn = db_provider
+        .get_connection()
+        .map_err(|_| RevocationError::DatabaseConnection)?;
+    let cutoff = revocation_cutoff(leeway_secs);
+    let batch_size = batch_size.max(1) as usize;
+
+    let mut purged = 0;
+    loop {
+        let batch = revoked_tokens::table
+            .filter(revoked_tokens::expires_at.le(cutoff))
+            .select(revoked_tokens::jti)
+            .limit(batch_size as i64);
+        let deleted =
+            diesel::delete(revoked_tokens::table.filter(revoked_tokens::jti.eq_any(batch)))
+                .execute(&mut conn)?;
+        purged += deleted;
+        if deleted < batch_size {
+            return Ok(purged);
+        }
+
+        tracing::info!(purged, "purging expired token revocations");
+        std::thread::sleep(PURGE_BATCH_PAUSE);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     const TEST_SECRET: &str = "test_secret_key_for_unit_tests";
+    const TEST_EXPIRY_HOURS: u32 = 168;
+    const TEST_LEEWAY: u64 = 60;
+
+    fn token_expiring_at(exp: i64) -> String {
+        let claims = Claims {
+            sub: Uuid::new_v4().to_string(),
+            exp: exp as usize,
+            iat: (exp - 3600) as usize,
+            impersonated_by: None,
+            jti: None,
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(TEST_SECRET.as_bytes()),
+        )
+        .expect("should create token")
+    }
 
     #[test]
     fn test_create_jwt_returns_token() {
         let user_id = Uuid::new_v4();
-        let token = create_jwt(user_id, TEST_SECRET);
+        let token = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS);
         assert!(token.is_ok());
         assert!(!token.unwrap().is_empty());
     }
@@ -56,21 +305,58 @@ mod tests {
     #[test]
     fn test_create_and_decode_jwt_roundtrip() {
         let user_id = Uuid::new_v4();
-        let token = create_jwt(user_id, TEST_SECRET).expect("should create token");
-        let claims = decode_jwt(&token, TEST_SECRET).expect("should decode token");
+        let token =
+            create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).expect("should create token");
+        let claims = decode_jwt(&token, TEST_SECRET, TEST_LEEWAY).expect("should decode token");
+        assert_eq!(claims.sub, user_id.to_string());
+    }
+
+    #[test]
+    fn test_regular_jwt_has_no_impersonator() {
+        let token = create_jwt(Uuid::new_v4(), TEST_SECRET, TEST_EXPIRY_HOURS)
+            .expect("should create token");
+        let claims = decode_jwt(&token, TEST_SECRET, TEST_LEEWAY).expect("should decode token");
+        assert!(claims.impersonated_by.is_none());
+    }
+
+    #[test]
+    fn test_each_jwt_gets_a_unique_jti() {
+        let user_id = Uuid::new_v4();
+        let first = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
+        let second = create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).unwrap();
+        let first = decode_jwt(&first, TEST_SECRET, TEST_LEEWAY).unwrap();
+        let second = decode_jwt(&second, TEST_SECRET, TEST_LEEWAY).unwrap();
+
+        let jti = first.jti.expect("jti should be set");
+        assert!(Uuid::parse_str(&jti).is_ok());
+        assert_ne!(Some(jti), second.jti);
+    }
+
+    #[test]
+    fn test_impersonation_jwt_is_marked_and_short_lived() {
+        let user_id = Uuid::new_v4();
+        let admin_id = Uuid::new_v4();
+        let token =
+            create_impersonation_jwt(user_id, admin_id, TEST_SECRET).expect("should create token");
+        let claims = decode_jwt(&token, TEST_SECRET, TEST_LEEWAY).expect("should decode token");
+
         assert_eq!(claims.sub, user_id.to_string());
+        assert_eq!(claims.impersonated_by, Some(admin_id.to_string()));
+        let now = Utc::now().timestamp() as usize;
+        assert!(claims.exp <= now + (IMPERSONATION_TOKEN_MINUTES as usize * 60) + 1);
     }
 
     #[test]
     fn test_decode_jwt_invalid_token() {
-        let result = decode_jwt("invalid.token.here", TEST_SECRET);
+        let result = decode_jwt("invalid.token.here", TEST_SECRET, TEST_LEEWAY);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_decode_jwt_wrong_secret() {
         let user_id = Uuid::new_v4();
-        let token = create_jwt(user_id, TEST_SECRET).expect("should create token");
+        let token =
+            create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).expect("should create token");
 
         // Tamper with the token signature
         let mut parts: Vec<&str> = token.split('.').collect();
@@ -79,15 +365,16 @@ mod tests {
         }
         let tampered_token = parts.join(".");
 
-        let result = decode_jwt(&tampered_token, TEST_SECRET);
+        let result = decode_jwt(&tampered_token, TEST_SECRET, TEST_LEEWAY);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_claims_expiration_is_in_future() {
         let user_id = Uuid::new_v4();
-        let token = create_jwt(user_id, TEST_SECRET).expect("should create token");
-        let claims = decode_jwt(&token, TEST_SECRET).expect("should decode token");
+        let token =
+            create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).expect("should create token");
+        let claims = decode_jwt(&token, TEST_SECRET, TEST_LEEWAY).expect("should decode token");
 
         let now = Utc::now().timestamp() as usize;
         assert!(claims.exp > now);
@@ -96,15 +383,47 @@ mod tests {
         assert!(claims.exp <= seven_days_from_now + 60); // 60 second margin
     }
 
+    #[test]
+    fn test_create_jwt_uses_given_expiry() {
+        let token = create_jwt(Uuid::new_v4(), TEST_SECRET, 1).expect("should create token");
+        let claims = decode_jwt(&token, TEST_SECRET, TEST_LEEWAY).expect("should decode token");
+
+        let lifetime = claims.exp - claims.iat;
+        assert!(
+            (3599..=3601).contains(&lifetime),
+            "lifetime was {}",
+            lifetime
+        );
+    }
+
     #[test]
     fn test_claims_issued_at_is_recent() {
         let user_id = Uuid::new_v4();
         let before = Utc::now().timestamp() as usize;
-        let token = create_jwt(user_id, TEST_SECRET).expect("should create token");
+        let token =
+            create_jwt(user_id, TEST_SECRET, TEST_EXPIRY_HOURS).expect("should create token");
         let after = Utc::now().timestamp() as usize;
-        let claims = decode_jwt(&token, TEST_SECRET).expect("should decode token");
+        let claims = decode_jwt(&token, TEST_SECRET, TEST_LEEWAY).expect("should decode token");
 
         assert!(claims.iat >= before);
         assert!(claims.iat <= after + 1); // 1 second margin
     }
+
+    #[test]
+    fn test_decode_jwt_recently_expired_within_leeway_accepted() {
+        let token = token_expiring_at(Utc::now().timestamp() - 5);
+        assert!(decode_jwt(&token, TEST_SECRET, TEST_LEEWAY).is_ok());
+    }
+
+    #[test]
+    fn test_decode_jwt_expired_past_leeway_rejected() {
+        let token = token_expiring_at(Utc::now().timestamp() - 120);
+        assert!(decode_jwt(&token, TEST_SECRET, TEST_LEEWAY).is_err());
+    }
+
+    #[test]
+    fn test_decode_jwt_zero_leeway_rejects_recently_expired() {
+        let token = token_expiring_at(Utc::now().timestamp() - 5);
+        assert!(decode_jwt(&token, TEST_SECRET, 0).is_err());
+    }
 }
@@ -1,7 +1,15 @@
+pub mod api_key;
 pub mod config;
 pub mod db;
 pub mod jwt;
+pub mod password;
+pub mod query_timing;
+pub mod redact;
 
+pub use api_key::*;
 pub use config::*;
 pub use db::*;
 pub use jwt::*;
+pub use password::*;
+pub use query_timing::*;
+pub use redact::*;
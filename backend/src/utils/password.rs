@@ -0,0 +1,68 @@
+use thiserror::Error;
+use zxcvbn::zxcvbn;
+
+/// A password that scored below the configured minimum, with zxcvbn's advice
+#[derive(Debug, Error)]
+#[error("Password is too weak")]
+pub struct WeakPassword {
+    pub score: u8,
+    pub warning: Option<String>,
+    pub suggestions: Vec<String>,
+}
+
+/// Reject passwords whose zxcvbn score (0-4) is below `min_score`.
+/// `user_inputs` (email, username) count against passwords built from them.
+/// A `min_score` of 0 accepts everything.
+pub fn check_password_strength(
+    password: &str,
+    min_score: u8,
+    user_inputs: &[&str],
+) -> Result<(), WeakPassword> {
+    if min_score == 0 {
+        return Ok(());
+    }
+
+    let entropy = zxcvbn(password, user_inputs);
+    let score = u8::from(entropy.score());
+    if score >= min_score {
+        return Ok(());
+    }
+
+    let feedback = entropy.feedback();
+    Err(WeakPassword {
+        score,
+        warning: feedback
+            .and_then(|f| f.warning())
+            .map(|warning| warning.to_string()),
+        suggestions: feedback
+            .map(|f| f.suggestions().iter().map(ToString::to_string).collect())
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_score_zero_accepts_anything() {
+        assert!(check_password_strength("password", 0, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_common_password_rejected_with_feedback() {
+        let weak = check_password_strength("password123", 3, &[]).unwrap_err();
+        assert!(weak.score < 3);
+        assert!(weak.warning.is_some() || !weak.suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_password_from_user_inputs_rejected() {
+        assert!(check_password_strength("alice1990", 3, &["alice@example.com", "alice"]).is_err());
+    }
+
+    #[test]
+    fn test_strong_password_accepted() {
+        assert!(check_password_strength("Tr0mbone-Velvet-Quarry-82!", 4, &[]).is_ok());
+    }
+}
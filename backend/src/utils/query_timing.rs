@@ -0,0 +1,97 @@
+use diesel::Connection;
+use diesel::connection::{Instrumentation, InstrumentationEvent};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::utils::{DbConnection, DbProvider};
+
+/// Query count and cumulative query time for a single request
+#[derive(Debug, Default)]
+pub struct DbQueryStats {
+    queries: AtomicU64,
+    elapsed_micros: AtomicU64,
+}
+
+impl DbQueryStats {
+    pub fn record(&self, elapsed: Duration) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        self.elapsed_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn queries(&self) -> u64 {
+        self.queries.load(Ordering::Relaxed)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_micros(self.elapsed_micros.load(Ordering::Relaxed))
+    }
+}
+
+tokio::task_local! {
+    /// Stats for the request currently being handled on this task
+    pub static REQUEST_DB_STATS: Arc<DbQueryStats>;
+}
+
+/// Diesel instrumentation that times each query into the request's stats
+struct QueryRecorder {
+    stats: Option<Arc<DbQueryStats>>,
+    started: Option<Instant>,
+}
+
+impl Instrumentation for QueryRecorder {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        match event {
+            InstrumentationEvent::StartQuery { .. } => self.started = Some(Instant::now()),
+            InstrumentationEvent::FinishQuery { .. } => {
+                if let (Some(stats), Some(started)) = (&self.stats, self.started.take()) {
+                    stats.record(started.elapsed());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Development aid: wraps a provider so every checked-out connection reports
+/// its queries to the current request's `REQUEST_DB_STATS`.
+pub struct QueryTimingDbProvider {
+    inner: Arc<dyn DbProvider>,
+}
+
+impl QueryTimingDbProvider {
+    pub fn new(inner: Arc<dyn DbProvider>) -> Self {
+        QueryTimingDbProvider { inner }
+    }
+}
+
+impl DbProvider for QueryTimingDbProvider {
+    fn get_connection(&self) -> Result<DbConnection, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.inner.get_connection()?;
+
+        // Pooled connections are reused, so always replace the recorder left
+        // behind by a previous request
+        conn.set_instrumentation(QueryRecorder {
+            stats: REQUEST_DB_STATS.try_with(Arc::clone).ok(),
+            started: None,
+        });
+
+        Ok(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_db_query_stats_accumulates() {
+        let stats = DbQueryStats::default();
+        stats.record(Duration::from_millis(3));
+        stats.record(Duration::from_millis(4));
+
+        assert_eq!(stats.queries(), 2);
+        assert_eq!(stats.elapsed(), Duration::from_millis(7));
+    }
+}
@@ -0,0 +1,44 @@
+/// Redact the local part of an email address for diagnostic logging, keeping
+/// only its first character: `test@example.com` becomes `t***@example.com`.
+/// Raw email addresses must never be written to logs.
+pub fn redact_email(email: &str) -> String {
+    match email.rsplit_once('@') {
+        Some((local, domain)) => {
+            let first: String = local.chars().take(1).collect();
+            format!("{}***@{}", first, domain)
+        }
+        None => "***".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_email_keeps_first_char_and_domain() {
+        assert_eq!(redact_email("test@example.com"), "t***@example.com");
+        assert_eq!(redact_email("a@b.org"), "a***@b.org");
+    }
+
+    #[test]
+    fn test_redact_email_hides_local_part_length() {
+        assert_eq!(
+            redact_email("averyveryverylongname@example.com"),
+            "a***@example.com"
+        );
+    }
+
+    #[test]
+    fn test_redact_email_without_at_sign() {
+        assert_eq!(redact_email("not-an-email"), "***");
+        assert_eq!(redact_email(""), "***");
+    }
+
+    #[test]
+    fn test_redact_email_uses_last_at_sign_and_handles_unicode() {
+        assert_eq!(redact_email("\"a@b\"@example.com"), "\"***@example.com");
+        assert_eq!(redact_email("élodie@example.fr"), "é***@example.fr");
+        assert_eq!(redact_email("@example.com"), "***@example.com");
+    }
+}
@@ -1,8 +1,13 @@
 mod common;
 
+use chrono::Utc;
 use common::{DirectConnectionTestDb, test_config};
-use poker_tracker::handlers::auth::{LoginError, RegisterError, do_login, do_register};
+use poker_tracker::handlers::auth::{
+    LoginError, RegisterError, do_login, do_register_temporary_account, do_register_with_config,
+};
+use poker_tracker::utils::{Claims, is_jwt_revoked, purge_expired_revocations, revoke_jwt};
 use rstest::rstest;
+use uuid::Uuid;
 
 use crate::common::fixtures::test_db;
 
@@ -11,9 +16,9 @@ use crate::common::fixtures::test_db;
 async fn test_register_user_success(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
-    let user = do_register(
+    let user = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "test@example.com".to_string(),
         "testuser".to_string(),
         "password123".to_string(),
@@ -27,15 +32,38 @@ async fn test_register_user_success(#[future] test_db: DirectConnectionTestDb) {
     assert!(!user.password_hash.is_empty());
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_register_uses_configured_bcrypt_cost(#[future] test_db: DirectConnectionTestDb) {
+    let db = test_db.await;
+
+    let mut config = test_config();
+    config.bcrypt_cost = 4;
+    let user = do_register_with_config(
+        &db,
+        &config,
+        "test@example.com".to_string(),
+        "testuser".to_string(),
+        "password123".to_string(),
+    )
+    .expect("Registration should succeed");
+
+    assert!(
+        user.password_hash.starts_with("$2b$04$"),
+        "hash should use cost 4: {}",
+        user.password_hash
+    );
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_register_duplicate_email(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
     // First registration should succeed
-    do_register(
+    do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "duplicate@example.com".to_string(),
         "user1".to_string(),
         "password123".to_string(),
@@ -43,9 +71,9 @@ async fn test_register_duplicate_email(#[future] test_db: DirectConnectionTestDb
     .expect("First registration should succeed");
 
     // Second registration with same email should fail
-    let result = do_register(
+    let result = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "duplicate@example.com".to_string(),
         "user2".to_string(),
         "password456".to_string(),
@@ -60,9 +88,9 @@ async fn test_register_duplicate_username(#[future] test_db: DirectConnectionTes
     let db = test_db.await;
 
     // First registration should succeed
-    do_register(
+    do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "user1@example.com".to_string(),
         "duplicateuser".to_string(),
         "password123".to_string(),
@@ -70,9 +98,9 @@ async fn test_register_duplicate_username(#[future] test_db: DirectConnectionTes
     .expect("First registration should succeed");
 
     // Second registration with same username should fail
-    let result = do_register(
+    let result = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "user2@example.com".to_string(),
         "duplicateuser".to_string(),
         "password456".to_string(),
@@ -81,14 +109,51 @@ async fn test_register_duplicate_username(#[future] test_db: DirectConnectionTes
     assert!(matches!(result, Err(RegisterError::DuplicateUsername)));
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_register_unknown_unique_constraint_is_duplicate_account(
+    #[future] test_db: DirectConnectionTestDb,
+) {
+    use diesel::RunQueryDsl;
+    use poker_tracker::utils::DbProvider;
+
+    let db = test_db.await;
+
+    // An extra constraint whose name mentions neither email nor username
+    // semantics the mapping knows about
+    let mut conn = db.get_connection().expect("Failed to get db connection");
+    diesel::sql_query("CREATE UNIQUE INDEX users_single_consent_idx ON users (cookie_consent)")
+        .execute(&mut conn)
+        .expect("Failed to create index");
+
+    do_register_with_config(
+        &db,
+        &test_config(),
+        "first@example.com".to_string(),
+        "first".to_string(),
+        "password123".to_string(),
+    )
+    .expect("First registration should succeed");
+
+    let result = do_register_with_config(
+        &db,
+        &test_config(),
+        "second@example.com".to_string(),
+        "second".to_string(),
+        "password123".to_string(),
+    );
+
+    assert!(matches!(result, Err(RegisterError::DuplicateAccount)));
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_register_returns_valid_user_id(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
-    let user = do_register(
+    let user = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "test@example.com".to_string(),
         "testuser".to_string(),
         "password123".to_string(),
@@ -104,9 +169,9 @@ async fn test_register_returns_valid_user_id(#[future] test_db: DirectConnection
 async fn test_register_sets_default_cookie_consent(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
-    let user = do_register(
+    let user = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "test@example.com".to_string(),
         "testuser".to_string(),
         "password123".to_string(),
@@ -124,9 +189,9 @@ async fn test_login_success(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
     // First register a user
-    let registered_user = do_register(
+    let registered_user = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "login@example.com".to_string(),
         "loginuser".to_string(),
         "correctpassword".to_string(),
@@ -152,9 +217,9 @@ async fn test_login_wrong_password(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
     // First register a user
-    do_register(
+    do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "login@example.com".to_string(),
         "loginuser".to_string(),
         "correctpassword".to_string(),
@@ -196,9 +261,9 @@ async fn test_login_after_registration_flow(#[future] test_db: DirectConnectionT
     let password = "securepassword123".to_string();
 
     // Register
-    let registered = do_register(
+    let registered = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         email.clone(),
         "flowuser".to_string(),
         password.clone(),
@@ -214,28 +279,38 @@ async fn test_login_after_registration_flow(#[future] test_db: DirectConnectionT
 
 #[rstest]
 #[tokio::test]
-async fn test_login_case_sensitive_email(#[future] test_db: DirectConnectionTestDb) {
+async fn test_login_email_is_case_insensitive(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
-    // Register with lowercase email
-    do_register(
+    // Register with a mixed-case email; it is stored lowercased
+    let registered = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
-        "test@example.com".to_string(),
+        &test_config(),
+        "Test@Example.com".to_string(),
         "testuser".to_string(),
         "password123".to_string(),
     )
     .expect("Registration should succeed");
+    assert_eq!(registered.email, "test@example.com");
 
-    // Try login with different case - should fail (emails are case-sensitive in this impl)
-    let result = do_login(
+    // Login with any casing finds the same user
+    let logged_in = do_login(
         &db,
         "TEST@EXAMPLE.COM".to_string(),
         "password123".to_string(),
-    );
+    )
+    .expect("Login should match the email case-insensitively");
+    assert_eq!(logged_in.id, registered.id);
 
-    // This tests the current behavior - email lookup is case-sensitive
-    assert!(matches!(result, Err(LoginError::InvalidCredentials)));
+    // A second account differing only in case is a duplicate
+    let result = do_register_with_config(
+        &db,
+        &test_config(),
+        "test@EXAMPLE.com".to_string(),
+        "otheruser".to_string(),
+        "password123".to_string(),
+    );
+    assert!(matches!(result, Err(RegisterError::DuplicateEmail)));
 }
 
 #[rstest]
@@ -246,9 +321,9 @@ async fn test_login_password_not_stored_plaintext(#[future] test_db: DirectConne
     let password = "mySecretPassword123";
 
     // Register
-    do_register(
+    do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "test@example.com".to_string(),
         "testuser".to_string(),
         password.to_string(),
@@ -275,19 +350,19 @@ async fn test_login_password_not_stored_plaintext(#[future] test_db: DirectConne
 async fn test_register_empty_email(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
-    // Empty email should still work at the do_register level (validation happens in handler)
+    // Empty email should still work at the do_register_with_config level (validation happens in handler)
     // but the database constraint should reject it or bcrypt should work
     // This tests that we can create users with various inputs
-    let result = do_register(
+    let result = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "".to_string(),
         "testuser".to_string(),
         "password123".to_string(),
     );
 
     // Empty email is technically allowed at the business logic level
-    // (validation happens at the handler level before calling do_register)
+    // (validation happens at the handler level before calling do_register_with_config)
     // The database may or may not reject it based on constraints
     // This test documents the current behavior
     assert!(result.is_ok() || result.is_err());
@@ -298,9 +373,9 @@ async fn test_register_empty_email(#[future] test_db: DirectConnectionTestDb) {
 async fn test_register_empty_username(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
-    let result = do_register(
+    let result = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "test@example.com".to_string(),
         "".to_string(),
         "password123".to_string(),
@@ -316,15 +391,15 @@ async fn test_register_empty_password(#[future] test_db: DirectConnectionTestDb)
     let db = test_db.await;
 
     // Empty password should still hash successfully with bcrypt
-    let result = do_register(
+    let result = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "test@example.com".to_string(),
         "testuser".to_string(),
         "".to_string(),
     );
 
-    // bcrypt can hash empty strings, so this should succeed at the do_register level
+    // bcrypt can hash empty strings, so this should succeed at the do_register_with_config level
     assert!(result.is_ok());
 }
 
@@ -345,9 +420,9 @@ async fn test_login_empty_password(#[future] test_db: DirectConnectionTestDb) {
     let db = test_db.await;
 
     // First register a user with a real password
-    do_register(
+    do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "test@example.com".to_string(),
         "testuser".to_string(),
         "realpassword123".to_string(),
@@ -366,18 +441,18 @@ async fn test_multiple_users_independent_login(#[future] test_db: DirectConnecti
     let db = test_db.await;
 
     // Register multiple users
-    let user1 = do_register(
+    let user1 = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "user1@example.com".to_string(),
         "user1".to_string(),
         "password1".to_string(),
     )
     .expect("User 1 registration should succeed");
 
-    let user2 = do_register(
+    let user2 = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "user2@example.com".to_string(),
         "user2".to_string(),
         "password2".to_string(),
@@ -417,18 +492,18 @@ async fn test_register_same_email_different_username_fails(
 ) {
     let db = test_db.await;
 
-    do_register(
+    do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "shared@example.com".to_string(),
         "user1".to_string(),
         "password1".to_string(),
     )
     .expect("First registration should succeed");
 
-    let result = do_register(
+    let result = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "shared@example.com".to_string(),
         "user2".to_string(),
         "password2".to_string(),
@@ -444,18 +519,18 @@ async fn test_register_same_username_different_email_fails(
 ) {
     let db = test_db.await;
 
-    do_register(
+    do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "user1@example.com".to_string(),
         "shareduser".to_string(),
         "password1".to_string(),
     )
     .expect("First registration should succeed");
 
-    let result = do_register(
+    let result = do_register_with_config(
         &db,
-        test_config().bcrypt_cost,
+        &test_config(),
         "user2@example.com".to_string(),
         "shareduser".to_string(),
         "password2".to_string(),
@@ -463,3 +538,130 @@ async fn test_register_same_username_different_email_fails(
 
     assert!(matches!(result, Err(RegisterError::DuplicateUsername)));
 }
+
+#[rstest]
+#[tokio::test]
+async fn test_register_lowercase_usernames_rejects_existing_mixed_case(
+    #[future] test_db: DirectConnectionTestDb,
+) {
+    let db = test_db.await;
+
+    // Registered before the flag was turned on
+    do_register_with_config(
+        &db,
+        &test_config(),
+        "user1@example.com".to_string(),
+        "SharedUser".to_string(),
+        "password1".to_string(),
+    )
+    .expect("First registration should succeed");
+
+    let mut config = test_config();
+    config.lowercase_usernames = true;
+    let result = do_register_with_config(
+        &db,
+        &config,
+        "user2@example.com".to_string(),
+        "shareduser".to_string(),
+        "password2".to_string(),
+    );
+
+    assert!(matches!(result, Err(RegisterError::DuplicateUsername)));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_temporary_account_is_created_flagged(#[future] test_db: DirectConnectionTestDb) {
+    let db = test_db.await;
+
+    let user = do_register_temporary_account(
+        &db,
+        &test_config(),
+        "temp@example.com".to_string(),
+        "tempuser".to_string(),
+        "temporary123".to_string(),
+    )
+    .expect("Registration should succeed");
+    assert!(user.must_change_password);
+
+    let user = do_register_with_config(
+        &db,
+        &test_config(),
+        "regular@example.com".to_string(),
+        "regularuser".to_string(),
+        "password123".to_string(),
+    )
+    .expect("Registration should succeed");
+    assert!(!user.must_change_password);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_revocation_outlives_expiry_by_leeway(#[future] test_db: DirectConnectionTestDb) {
+    let db = test_db.await;
+    let user = do_register_with_config(
+        &db,
+        &test_config(),
+        "test@example.com".to_string(),
+        "testuser".to_string(),
+        "password123".to_string(),
+    )
+    .expect("Registration should succeed");
+
+    // Expired 30 seconds ago, so still accepted with a 60 second leeway
+    let now = Utc::now().timestamp();
+    let jti = Uuid::new_v4();
+    let claims = Claims {
+        sub: user.id.to_string(),
+        exp: (now - 30) as usize,
+        iat: (now - 3600) as usize,
+        impersonated_by: None,
+        jti: Some(jti.to_string()),
+    };
+    revoke_jwt(&db, &claims).expect("should revoke");
+
+    assert!(is_jwt_revoked(&db, jti, 60).unwrap());
+    assert_eq!(purge_expired_revocations(&db, 60, 1000).unwrap(), 0);
+    assert!(is_jwt_revoked(&db, jti, 60).unwrap());
+
+    // Without leeway the token is plainly expired and the row can go
+    assert!(!is_jwt_revoked(&db, jti, 0).unwrap());
+    assert_eq!(purge_expired_revocations(&db, 0, 1000).unwrap(), 1);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_purge_expired_revocations_in_batches(#[future] test_db: DirectConnectionTestDb) {
+    let db = test_db.await;
+    let user = do_register_with_config(
+        &db,
+        &test_config(),
+        "test@example.com".to_string(),
+        "testuser".to_string(),
+        "password123".to_string(),
+    )
+    .expect("Registration should succeed");
+
+    let now = Utc::now().timestamp();
+    let revoke = |exp: i64| {
+        let jti = Uuid::new_v4();
+        let claims = Claims {
+            sub: user.id.to_string(),
+            exp: exp as usize,
+            iat: (exp - 3600) as usize,
+            impersonated_by: None,
+            jti: Some(jti.to_string()),
+        };
+        revoke_jwt(&db, &claims).expect("should revoke");
+        jti
+    };
+    for _ in 0..5 {
+        revoke(now - 3600);
+    }
+    let live = revoke(now + 3600);
+
+    // A single statement removes at most 2 rows, so all 5 means it kept going
+    assert_eq!(purge_expired_revocations(&db, 0, 2).unwrap(), 5);
+    assert_eq!(purge_expired_revocations(&db, 0, 2).unwrap(), 0);
+    assert!(is_jwt_revoked(&db, live, 0).unwrap());
+}
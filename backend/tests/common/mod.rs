@@ -7,7 +7,9 @@ use diesel::r2d2::{ConnectionManager, Pool};
 use poker_tracker::models::user::{NewUser, User};
 use poker_tracker::models::{CreatePokerSessionRequest, PokerSession};
 use poker_tracker::schema::{poker_sessions, users};
-use poker_tracker::utils::{DbConnection, DbPool, DbProvider, PokerTrackerConfig};
+use poker_tracker::utils::{
+    DbConnection, DbPool, DbProvider, ExportFormat, PokerTrackerConfig, SortOrder,
+};
 use testcontainers::ContainerAsync;
 use testcontainers::runners::AsyncRunner;
 use testcontainers_modules::postgres::Postgres;
@@ -24,6 +26,11 @@ struct TestContainer {
 impl TestContainer {
     /// Starts a new Postgres container and runs migrations.
     async fn new() -> Self {
+        Self::start(true).await
+    }
+
+    /// Starts a new Postgres container, optionally leaving the schema empty.
+    async fn start(apply_migrations: bool) -> Self {
         let container = Postgres::default().start().await.unwrap();
 
         let host = container.get_host().await.unwrap();
@@ -33,7 +40,9 @@ impl TestContainer {
             host, host_port
         );
 
-        Self::run_migrations(&database_url).expect("Failed to run migrations on test DB");
+        if apply_migrations {
+            Self::run_migrations(&database_url).expect("Failed to run migrations on test DB");
+        }
 
         Self {
             database_url,
@@ -96,7 +105,15 @@ pub struct PooledConnectionTestDb {
 
 impl PooledConnectionTestDb {
     pub async fn new() -> Self {
-        let container = TestContainer::new().await;
+        Self::from_container(TestContainer::new().await)
+    }
+
+    /// A database with no migrations applied, for schema drift tests
+    pub async fn without_migrations() -> Self {
+        Self::from_container(TestContainer::start(false).await)
+    }
+
+    fn from_container(container: TestContainer) -> Self {
         let manager = ConnectionManager::new(&container.database_url);
         let pool = Pool::builder()
             .build(manager)
@@ -123,7 +140,38 @@ pub fn test_config() -> PokerTrackerConfig {
         db_max_connections: 10,
         db_min_idle: 1,
         jwt_secret: "test_secret".to_string(),
+        jwt_secret_env_only: false,
+        allow_weak_jwt_secret: false,
+        jwt_expiry_hours: 168,
+        purge_batch_size: 1000,
+        jwt_leeway_secs: 60,
         bcrypt_cost: 4, // Fast for tests
+        min_password_score: 0,
+        registration_enabled: true,
+        lowercase_usernames: false,
+        blocked_email_domains: vec![],
+        require_buy_in_for_rebuy: true,
+        large_loss_confirm_threshold: None,
+        break_even_epsilon: 0.0,
+        default_list_order: SortOrder::Desc,
+        default_export_order: SortOrder::Asc,
+        default_time_range: "all".to_string(),
+        enabled_export_formats: ExportFormat::ALL.to_vec(),
+        max_list_page_size: 100,
+        max_export_rows: None,
+        max_total_notes_bytes: None,
+        empty_filter_not_found: false,
+        min_duration_for_stats_minutes: 0,
+        day_cutoff_hour: 0,
+        stats_cache_max_age_secs: 0,
+        slow_request_threshold_ms: 1000,
+        maintenance_mode: false,
+        trust_proxy: false,
+        require_https: false,
+        strict_json: false,
+        camel_case_json: false,
+        debug_timing: false,
+        debug_log_bodies: false,
     }
 }
 
@@ -184,10 +232,11 @@ pub fn default_session_request() -> CreatePokerSessionRequest {
     CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: Some(50.0),
-        cash_out_amount: 200.0,
+        cash_out_amount: Some(200.0),
         notes: Some("Test session".to_string()),
+        ..Default::default()
     }
 }
 
@@ -0,0 +1,774 @@
+mod common;
+mod http_common;
+
+use axum::http::StatusCode;
+use common::test_config;
+use http_common::{
+    HttpTestContext, create_session, default_session_json, http_ctx, make_admin,
+    register_and_get_token,
+};
+use poker_tracker::models::user::{
+    AdminCreateUserResponse, AuthResponse, ImpersonationResponse, User,
+};
+use poker_tracker::models::{AdminExportRow, AuditEntry, CreatedApiKey};
+use rstest::rstest;
+use serde_json::json;
+use std::io;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+// =============================================================================
+// Admin User Management
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_admin_can_create_user_who_can_login(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+
+    let response = ctx
+        .server
+        .post("/api/admin/users")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "email": "newuser@example.com",
+            "username": "newuser"
+        }))
+        .await;
+
+    response.assert_status(StatusCode::CREATED);
+    let created: AdminCreateUserResponse = response.json();
+    assert_eq!(created.user.email, "newuser@example.com");
+    let temporary_password = created
+        .temporary_password
+        .expect("Generated password should be returned");
+
+    let login = ctx
+        .server
+        .post("/api/auth/login")
+        .json(&json!({
+            "email": "newuser@example.com",
+            "password": temporary_password
+        }))
+        .await;
+
+    login.assert_status_ok();
+    let auth: AuthResponse = login.json();
+    assert_eq!(auth.user.id, created.user.id);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_admin_create_user_with_provided_password(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+
+    let response = ctx
+        .server
+        .post("/api/admin/users")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "email": "newuser@example.com",
+            "username": "newuser",
+            "password": "temporary123"
+        }))
+        .await;
+
+    response.assert_status(StatusCode::CREATED);
+    let body: serde_json::Value = response.json();
+    assert!(body.get("temporary_password").is_none());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_non_admin_create_user_returns_403(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+
+    let response = ctx
+        .server
+        .post("/api/admin/users")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "email": "newuser@example.com",
+            "username": "newuser"
+        }))
+        .await;
+
+    response.assert_status(StatusCode::FORBIDDEN);
+}
+
+// =============================================================================
+// Forced Password Change
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_admin_created_user_must_change_password(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+
+    let created: AdminCreateUserResponse = ctx
+        .server
+        .post("/api/admin/users")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "email": "newuser@example.com",
+            "username": "newuser",
+            "password": "temporary123"
+        }))
+        .await
+        .json();
+    assert!(created.user.must_change_password);
+
+    let auth: AuthResponse = ctx
+        .server
+        .post("/api/auth/login")
+        .json(&json!({
+            "email": "newuser@example.com",
+            "password": "temporary123"
+        }))
+        .await
+        .json();
+    let auth_header = format!("Bearer {}", auth.token);
+
+    // Blocked from regular routes until the password is changed
+    ctx.server
+        .get("/api/sessions")
+        .add_header("Authorization", &auth_header)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // Profile remains accessible
+    ctx.server
+        .get("/api/auth/me")
+        .add_header("Authorization", &auth_header)
+        .await
+        .assert_status_ok();
+
+    ctx.server
+        .post("/api/auth/change-password")
+        .add_header("Authorization", &auth_header)
+        .json(&json!({
+            "old_password": "temporary123",
+            "new_password": "mynewpassword"
+        }))
+        .await
+        .assert_status_ok();
+
+    ctx.server
+        .get("/api/sessions")
+        .add_header("Authorization", &auth_header)
+        .await
+        .assert_status_ok();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_password_change_check_fails_closed(#[future] http_ctx: HttpTestContext) {
+    use diesel::RunQueryDsl;
+    use poker_tracker::utils::DbProvider;
+
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+
+    // Make the must_change_password lookup fail
+    let mut conn = ctx.db_provider.get_connection().unwrap();
+    diesel::sql_query("ALTER TABLE users RENAME COLUMN must_change_password TO unreadable")
+        .execute(&mut conn)
+        .unwrap();
+
+    ctx.server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+// =============================================================================
+// Admin Export
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_non_admin_export_returns_403(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/admin/export")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_admin_export_includes_all_users_sessions(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    let alice_token = register_and_get_token(&ctx, "alice@example.com").await;
+    let bob_token = register_and_get_token(&ctx, "bob@example.com").await;
+    create_session(&ctx, &alice_token, default_session_json()).await;
+    create_session(&ctx, &alice_token, default_session_json()).await;
+    create_session(&ctx, &bob_token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .get("/api/admin/export")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .await;
+
+    response.assert_status_ok();
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/x-ndjson"
+    );
+
+    let rows: Vec<AdminExportRow> = response
+        .text()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Each line should be a JSON row"))
+        .collect();
+    assert_eq!(rows.len(), 3);
+
+    let mut usernames: Vec<&str> = rows.iter().map(|row| row.username.as_str()).collect();
+    usernames.sort();
+    assert_eq!(usernames, vec!["alice", "alice", "bob"]);
+
+    // Rows for the same user carry that user's id
+    let alice_ids: Vec<_> = rows
+        .iter()
+        .filter(|row| row.username == "alice")
+        .map(|row| row.session.session.user_id)
+        .collect();
+    assert_eq!(alice_ids[0], alice_ids[1]);
+}
+
+// =============================================================================
+// Maintenance Mode
+// =============================================================================
+
+async fn set_maintenance(ctx: &HttpTestContext, token: &str, enabled: bool) -> StatusCode {
+    ctx.server
+        .put("/api/admin/maintenance")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "enabled": enabled }))
+        .await
+        .status_code()
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_maintenance_mode_toggle(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+
+    assert_eq!(
+        set_maintenance(&ctx, &admin_token, true).await,
+        StatusCode::OK
+    );
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .await;
+    response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().contains_key("retry-after"));
+
+    ctx.server.get("/api/health/live").await.assert_status_ok();
+
+    assert_eq!(
+        set_maintenance(&ctx, &admin_token, false).await,
+        StatusCode::OK
+    );
+
+    ctx.server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .await
+        .assert_status_ok();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_maintenance_mode_from_config() {
+    let mut config = test_config();
+    config.maintenance_mode = true;
+    let ctx = HttpTestContext::with_config(config).await;
+
+    ctx.server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "someone@example.com",
+            "username": "someone",
+            "password": "password123"
+        }))
+        .await
+        .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+
+    // Login stays reachable; this user just doesn't exist
+    ctx.server
+        .post("/api/auth/login")
+        .json(&json!({
+            "email": "someone@example.com",
+            "password": "password123"
+        }))
+        .await
+        .assert_status_unauthorized();
+
+    ctx.server.get("/api/health/live").await.assert_status_ok();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_admin_can_log_in_to_end_maintenance(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    assert_eq!(
+        set_maintenance(&ctx, &admin_token, true).await,
+        StatusCode::OK
+    );
+
+    // A fresh login, as after the old token expired
+    let auth: AuthResponse = ctx
+        .server
+        .post("/api/auth/login")
+        .json(&json!({
+            "email": "admin@example.com",
+            "password": "password123"
+        }))
+        .await
+        .json();
+
+    assert_eq!(
+        set_maintenance(&ctx, &auth.token, false).await,
+        StatusCode::OK
+    );
+    ctx.server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", auth.token))
+        .await
+        .assert_status_ok();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_non_admin_cannot_toggle_maintenance(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+
+    assert_eq!(
+        set_maintenance(&ctx, &token, true).await,
+        StatusCode::FORBIDDEN
+    );
+
+    ctx.server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status_ok();
+}
+
+// =============================================================================
+// Impersonation
+// =============================================================================
+
+/// Writer that collects formatted log output for assertions
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+async fn user_id_for_token(ctx: &HttpTestContext, token: &str) -> Uuid {
+    let response = ctx
+        .server
+        .get("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    let user: User = response.json();
+    user.id
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_impersonate_requires_admin(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+    let target_token = register_and_get_token(&ctx, "target@example.com").await;
+    let target_id = user_id_for_token(&ctx, &target_token).await;
+
+    let response = ctx
+        .server
+        .post(&format!("/api/admin/impersonate/{}", target_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_impersonation_token_acts_as_user(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    let admin_id = user_id_for_token(&ctx, &admin_token).await;
+    let target_token = register_and_get_token(&ctx, "target@example.com").await;
+    let target_id = user_id_for_token(&ctx, &target_token).await;
+    create_session(&ctx, &target_token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .post(&format!("/api/admin/impersonate/{}", target_id))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .await;
+
+    response.assert_status_ok();
+    let impersonation: ImpersonationResponse = response.json();
+    assert_eq!(impersonation.user.id, target_id);
+    assert_eq!(impersonation.impersonated_by, admin_id);
+
+    assert_eq!(
+        user_id_for_token(&ctx, &impersonation.token).await,
+        target_id
+    );
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", impersonation.token))
+        .await;
+    let sessions: Vec<serde_json::Value> = response.json();
+    assert_eq!(sessions.len(), 1);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_impersonation_token_cannot_gain_lasting_access(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    // An admin target, so admin routes would otherwise be open to the token
+    let target_token = register_and_get_token(&ctx, "target@example.com").await;
+    make_admin(&ctx, "target@example.com");
+    let target_id = user_id_for_token(&ctx, &target_token).await;
+
+    let impersonation: ImpersonationResponse = ctx
+        .server
+        .post(&format!("/api/admin/impersonate/{}", target_id))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .await
+        .json();
+    let auth = format!("Bearer {}", impersonation.token);
+
+    let response = ctx
+        .server
+        .post("/api/auth/api-keys")
+        .add_header("Authorization", auth.clone())
+        .json(&json!({"label": "backdoor"}))
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let response = ctx
+        .server
+        .post("/api/auth/change-password")
+        .add_header("Authorization", auth.clone())
+        .json(&json!({
+            "old_password": "password123",
+            "new_password": "newpassword123"
+        }))
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let response = ctx
+        .server
+        .get("/api/admin/audit")
+        .add_header("Authorization", auth.clone())
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    // The target's own token still reaches the same routes
+    let response = ctx
+        .server
+        .get("/api/admin/audit")
+        .add_header("Authorization", format!("Bearer {}", target_token))
+        .await;
+    response.assert_status_ok();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_impersonation_is_logged(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    let admin_id = user_id_for_token(&ctx, &admin_token).await;
+    let target_token = register_and_get_token(&ctx, "target@example.com").await;
+    let target_id = user_id_for_token(&ctx, &target_token).await;
+
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    ctx.server
+        .post(&format!("/api/admin/impersonate/{}", target_id))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("User-Agent", "support-console/1.0")
+        .await
+        .assert_status_ok();
+
+    let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    assert!(output.contains("admin impersonation token issued"));
+    assert!(output.contains(&admin_id.to_string()));
+    assert!(output.contains(&target_id.to_string()));
+
+    let entries = wait_for_audit_entries(&ctx, &admin_token, admin_id, "impersonate").await;
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.user_id, admin_id);
+    assert_eq!(entry.target_user_id, Some(target_id));
+    assert_eq!(entry.user_agent.as_deref(), Some("support-console/1.0"));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_impersonate_unknown_user_returns_404(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+
+    let response = ctx
+        .server
+        .post(&format!("/api/admin/impersonate/{}", Uuid::new_v4()))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_admin_api_key_cannot_use_admin_routes(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    let target_token = register_and_get_token(&ctx, "target@example.com").await;
+    let target_id = user_id_for_token(&ctx, &target_token).await;
+    let created: CreatedApiKey = ctx
+        .server
+        .post("/api/auth/api-keys")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({"label": "scripts"}))
+        .await
+        .json();
+
+    let response = ctx
+        .server
+        .post(&format!("/api/admin/impersonate/{}", target_id))
+        .add_header("X-API-Key", created.key.clone())
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    let body: serde_json::Value = response.json();
+    assert!(body.get("token").is_none());
+
+    let response = ctx
+        .server
+        .get("/api/admin/audit")
+        .add_header("X-API-Key", created.key.clone())
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    // The same key still works outside the admin routes
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("X-API-Key", created.key)
+        .await;
+    response.assert_status_ok();
+}
+
+// =============================================================================
+// Audit Log
+// =============================================================================
+
+/// Audit entries are written in the background, so poll until one shows up
+async fn wait_for_audit_entries(
+    ctx: &HttpTestContext,
+    admin_token: &str,
+    user_id: Uuid,
+    action: &str,
+) -> Vec<AuditEntry> {
+    for _ in 0..50 {
+        let response = ctx
+            .server
+            .get("/api/admin/audit")
+            .add_query_param("user_id", user_id)
+            .add_query_param("action", action)
+            .add_header("Authorization", format!("Bearer {}", admin_token))
+            .await;
+        response.assert_status_ok();
+        let entries: Vec<AuditEntry> = response.json();
+        if !entries.is_empty() {
+            return entries;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    panic!("no {} audit entry for {}", action, user_id);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_password_change_is_audited() {
+    let mut config = test_config();
+    config.trust_proxy = true;
+    let ctx = HttpTestContext::with_config(config).await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+    let user_id = user_id_for_token(&ctx, &token).await;
+
+    ctx.server
+        .post("/api/auth/change-password")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .add_header("User-Agent", "audit-test/1.0")
+        .add_header("X-Forwarded-For", "203.0.113.7")
+        .json(&json!({
+            "old_password": "password123",
+            "new_password": "newpassword456"
+        }))
+        .await
+        .assert_status_ok();
+
+    let entries = wait_for_audit_entries(&ctx, &admin_token, user_id, "password_change").await;
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.user_id, user_id);
+    assert_eq!(entry.ip.as_deref(), Some("203.0.113.7"));
+    assert_eq!(entry.user_agent.as_deref(), Some("audit-test/1.0"));
+
+    let raw = serde_json::to_string(&entries).unwrap();
+    assert!(!raw.contains("password123"));
+    assert!(!raw.contains("newpassword456"));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_login_is_audited(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+    let user_id = user_id_for_token(&ctx, &token).await;
+
+    ctx.server
+        .post("/api/auth/login")
+        .json(&json!({
+            "email": "user@example.com",
+            "password": "password123"
+        }))
+        .await
+        .assert_status_ok();
+
+    let entries = wait_for_audit_entries(&ctx, &admin_token, user_id, "login").await;
+    assert_eq!(entries[0].action, "login");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_audit_ignores_forwarded_for_without_trust_proxy(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+    let user_id = user_id_for_token(&ctx, &token).await;
+
+    ctx.server
+        .post("/api/auth/login")
+        .add_header("X-Forwarded-For", "203.0.113.7")
+        .json(&json!({
+            "email": "user@example.com",
+            "password": "password123"
+        }))
+        .await
+        .assert_status_ok();
+
+    let entries = wait_for_audit_entries(&ctx, &admin_token, user_id, "login").await;
+    assert_ne!(entries[0].ip.as_deref(), Some("203.0.113.7"));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_logout_is_audited(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+    let user_id = user_id_for_token(&ctx, &token).await;
+
+    ctx.server
+        .post("/api/auth/logout")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let entries = wait_for_audit_entries(&ctx, &admin_token, user_id, "logout").await;
+    assert_eq!(entries.len(), 1);
+    assert!(!serde_json::to_string(&entries).unwrap().contains(&token));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_non_admin_cannot_read_audit_log(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "user@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/admin/audit")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_audit_log_rejects_out_of_range_limit(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let admin_token = register_and_get_token(&ctx, "admin@example.com").await;
+    make_admin(&ctx, "admin@example.com");
+
+    let response = ctx
+        .server
+        .get("/api/admin/audit")
+        .add_query_param("limit", 0)
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
@@ -2,10 +2,17 @@ mod common;
 mod http_common;
 
 use axum::body::Bytes;
-use http_common::{HttpTestContext, http_ctx};
+use common::test_config;
+use http_common::{
+    HttpTestContext, create_session, default_session_json, http_ctx, register_and_get_token,
+};
+use poker_tracker::models::api_key::{ApiKey, CreatedApiKey};
+use poker_tracker::models::poker_session::SessionWithProfit;
 use poker_tracker::models::user::{AuthResponse, User};
+use poker_tracker::utils::{create_jwt, decode_jwt};
 use rstest::rstest;
 use serde_json::json;
+use uuid::Uuid;
 
 // =============================================================================
 // Phase 2: Health Check & Basic Routing Tests
@@ -22,6 +29,125 @@ async fn test_health_endpoint_returns_ok(#[future] http_ctx: HttpTestContext) {
     assert_eq!(body["status"], "Ok");
 }
 
+#[tokio::test]
+async fn test_health_reports_missing_tables_without_migrations() {
+    let ctx = HttpTestContext::without_migrations().await;
+    let response = ctx.server.get("/api/health").await;
+    response.assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["missing_tables"], json!(["users", "poker_sessions"]));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_cors_exposes_content_disposition(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let response = ctx
+        .server
+        .get("/api/health")
+        .add_header("Origin", "http://localhost:5173")
+        .await;
+    response.assert_status_ok();
+
+    let exposed = response.header("access-control-expose-headers");
+    let exposed = exposed.to_str().unwrap().to_ascii_lowercase();
+    assert!(
+        exposed
+            .split(',')
+            .any(|h| h.trim() == "content-disposition"),
+        "Expected content-disposition in exposed headers, got: {}",
+        exposed
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_version_endpoint_returns_build_info(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    // No Authorization header: version is public
+    let response = ctx.server.get("/api/version").await;
+    response.assert_status_ok();
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["git_commit"].is_string());
+    assert!(body["build_timestamp"].is_string());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_version_reports_applied_migrations(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let mut migration_dirs: Vec<String> =
+        std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+    migration_dirs.sort();
+
+    let response = ctx.server.get("/api/version").await;
+    response.assert_status_ok();
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["schema"]["applied_migrations"], migration_dirs.len());
+    assert_eq!(
+        body["schema"]["latest_migration"],
+        migration_dirs.last().unwrap().as_str()
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_pretty_query_param_formats_json(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+
+    let compact = ctx.server.get("/api/version").await;
+    compact.assert_status_ok();
+    assert!(!compact.text().contains('\n'));
+
+    let pretty = ctx
+        .server
+        .get("/api/version")
+        .add_query_param("pretty", "true")
+        .await;
+    pretty.assert_status_ok();
+    assert!(pretty.text().contains('\n'));
+
+    // Formatting only changes whitespace, not content
+    let compact_body: serde_json::Value = compact.json();
+    let pretty_body: serde_json::Value = pretty.json();
+    assert_eq!(compact_body, pretty_body);
+}
+
+#[tokio::test]
+async fn test_require_https_rejects_plaintext_forwarded_requests() {
+    let mut config = test_config();
+    config.trust_proxy = true;
+    config.require_https = true;
+    let ctx = HttpTestContext::with_config(config).await;
+
+    let response = ctx
+        .server
+        .get("/api/version")
+        .add_header("X-Forwarded-Proto", "http")
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "HTTPS is required");
+
+    let response = ctx
+        .server
+        .get("/api/version")
+        .add_header("X-Forwarded-Proto", "https")
+        .await;
+    response.assert_status_ok();
+
+    // Health checks bypass the proxy, so they carry no header
+    let response = ctx.server.get("/api/health/live").await;
+    response.assert_status_ok();
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_unknown_route_without_auth_returns_401(#[future] http_ctx: HttpTestContext) {
@@ -64,6 +190,147 @@ async fn test_register_with_valid_data_returns_token(#[future] http_ctx: HttpTes
     assert_eq!(body.user.username, "testuser");
 }
 
+#[tokio::test]
+async fn test_register_disabled_returns_403() {
+    let mut config = test_config();
+    config.registration_enabled = false;
+    let ctx = HttpTestContext::with_config(config).await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "test@example.com",
+            "username": "testuser",
+            "password": "password123"
+        }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::FORBIDDEN);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "Registration disabled");
+}
+
+#[tokio::test]
+async fn test_register_enabled_returns_201() {
+    let mut config = test_config();
+    config.registration_enabled = true;
+    let ctx = HttpTestContext::with_config(config).await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "test@example.com",
+            "username": "testuser",
+            "password": "password123"
+        }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::CREATED);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_register_keeps_username_case_by_default(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "dave1@example.com",
+            "username": "Dave",
+            "password": "password123"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let body: AuthResponse = response.json();
+    assert_eq!(body.user.username, "Dave");
+
+    // The LOWER(username) unique index rejects case-only duplicates even with
+    // lowercase_usernames off
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "dave2@example.com",
+            "username": "dave",
+            "password": "password123"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "This username is already taken");
+}
+
+#[tokio::test]
+async fn test_register_lowercase_usernames_rejects_case_collision() {
+    let mut config = test_config();
+    config.lowercase_usernames = true;
+    let ctx = HttpTestContext::with_config(config).await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "dave1@example.com",
+            "username": "Dave",
+            "password": "password123"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let body: AuthResponse = response.json();
+    assert_eq!(body.user.username, "dave");
+
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "dave2@example.com",
+            "username": "DAVE",
+            "password": "password123"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "This username is already taken");
+}
+
+#[tokio::test]
+async fn test_register_blocked_email_domain_returns_400() {
+    let mut config = test_config();
+    config.blocked_email_domains = vec!["mailinator.com".to_string()];
+    let ctx = HttpTestContext::with_config(config).await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "throwaway@MAILINATOR.com",
+            "username": "throwaway",
+            "password": "password123"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert_eq!(
+        body["error"],
+        "Registration with this email domain is not allowed"
+    );
+
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "player@example.com",
+            "username": "player",
+            "password": "password123"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_register_invalid_email_returns_400(#[future] http_ctx: HttpTestContext) {
@@ -286,6 +553,81 @@ async fn test_missing_required_fields_returns_422(#[future] http_ctx: HttpTestCo
     response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
 }
 
+// =============================================================================
+// Password Strength
+// =============================================================================
+
+async fn strength_ctx() -> HttpTestContext {
+    let mut config = test_config();
+    config.min_password_score = 3;
+    HttpTestContext::with_config(config).await
+}
+
+#[tokio::test]
+async fn test_register_weak_password_rejected_with_suggestions() {
+    let ctx = strength_ctx().await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "test@example.com",
+            "username": "testuser",
+            "password": "password123"
+        }))
+        .await;
+
+    response.assert_status_bad_request();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "Password is too weak");
+    assert!(body["score"].as_u64().unwrap() < 3);
+    assert!(body["suggestions"].is_array());
+}
+
+#[tokio::test]
+async fn test_register_strong_password_accepted() {
+    let ctx = strength_ctx().await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "test@example.com",
+            "username": "testuser",
+            "password": "Tr0mbone-Velvet-Quarry-82!"
+        }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_change_password_weak_password_rejected() {
+    let ctx = strength_ctx().await;
+    let register_response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "test@example.com",
+            "username": "testuser",
+            "password": "Tr0mbone-Velvet-Quarry-82!"
+        }))
+        .await;
+    let auth: AuthResponse = register_response.json();
+
+    let response = ctx
+        .server
+        .post("/api/auth/change-password")
+        .add_header("Authorization", format!("Bearer {}", auth.token))
+        .json(&json!({
+            "old_password": "Tr0mbone-Velvet-Quarry-82!",
+            "new_password": "password123"
+        }))
+        .await;
+
+    response.assert_status_bad_request();
+}
+
 // =============================================================================
 // Phase 4: Protected Endpoints & Auth Middleware Tests
 // =============================================================================
@@ -398,6 +740,35 @@ async fn test_update_cookie_consent_with_valid_token(#[future] http_ctx: HttpTes
     assert!(user.cookie_consent);
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_withdrawing_cookie_consent_records_timestamp(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let set_consent = |consent: bool| {
+        ctx.server
+            .put("/api/auth/cookie-consent")
+            .add_header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ "cookie_consent": consent }))
+    };
+
+    let granted: User = set_consent(true).await.json();
+    let granted_at = granted.cookie_consent_date.expect("grant should be dated");
+
+    let withdrawn: User = set_consent(false).await.json();
+    assert!(!withdrawn.cookie_consent);
+    let withdrawn_at = withdrawn
+        .cookie_consent_date
+        .expect("withdrawal should be dated, not nulled");
+    assert!(withdrawn_at >= granted_at);
+
+    // Withdrawing again is idempotent and keeps the original withdrawal time
+    let repeated: User = set_consent(false).await.json();
+    assert!(!repeated.cookie_consent);
+    assert_eq!(repeated.cookie_consent_date, Some(withdrawn_at));
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_change_password_with_valid_credentials(#[future] http_ctx: HttpTestContext) {
@@ -548,3 +919,360 @@ async fn test_token_from_login_works_for_protected_endpoints(#[future] http_ctx:
     let user: User = response.json();
     assert_eq!(user.email, "test@example.com");
 }
+
+// =============================================================================
+// Token Refresh
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_refresh_token_issues_new_token_for_same_user(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    // Tokens are issued with second precision
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    let old_user: User = ctx
+        .server
+        .get("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json();
+
+    let response = ctx
+        .server
+        .post("/api/auth/refresh")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    let refreshed = body["token"].as_str().unwrap();
+    assert_ne!(refreshed, token);
+
+    let new_user: User = ctx
+        .server
+        .get("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", refreshed))
+        .await
+        .json();
+    assert_eq!(new_user.id, old_user.id);
+
+    // The refreshed token replaces the old one
+    ctx.server
+        .get("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status_unauthorized();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_refresh_logged_out_token_returns_401(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    ctx.server
+        .post("/api/auth/logout")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let response = ctx
+        .server
+        .post("/api/auth/refresh")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_unauthorized();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_refresh_with_api_key_and_revoked_token_is_forbidden(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let victim_token = register_and_get_token(&ctx, "victim@example.com").await;
+    ctx.server
+        .post("/api/auth/logout")
+        .add_header("Authorization", format!("Bearer {}", victim_token))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    let attacker_token = register_and_get_token(&ctx, "attacker@example.com").await;
+    let created = create_api_key(&ctx, &attacker_token, false).await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/refresh")
+        .add_header("X-API-Key", created.key)
+        .add_header("Authorization", format!("Bearer {}", victim_token))
+        .await;
+
+    response.assert_status_forbidden();
+    let body: serde_json::Value = response.json();
+    assert!(body.get("token").is_none());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_refresh_token_for_nonexistent_user_returns_401(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let config = test_config();
+    let token = create_jwt(Uuid::new_v4(), &config.jwt_secret, config.jwt_expiry_hours).unwrap();
+
+    let response = ctx
+        .server
+        .post("/api/auth/refresh")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_unauthorized();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_refresh_expired_token_returns_401(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let config = test_config();
+    let mut claims = decode_jwt(&token, &config.jwt_secret, 0).unwrap();
+    claims.exp = (chrono::Utc::now().timestamp() - 3600) as usize;
+    let expired = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+    )
+    .unwrap();
+
+    let response = ctx
+        .server
+        .post("/api/auth/refresh")
+        .add_header("Authorization", format!("Bearer {}", expired))
+        .await;
+
+    response.assert_status_unauthorized();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_refresh_token_without_jti_returns_401(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let config = test_config();
+    let mut claims = decode_jwt(&token, &config.jwt_secret, 0).unwrap();
+    // Without a jti the old token couldn't be revoked, so it must not be swapped
+    claims.jti = None;
+    let unrevocable = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+    )
+    .unwrap();
+
+    let response = ctx
+        .server
+        .post("/api/auth/refresh")
+        .add_header("Authorization", format!("Bearer {}", unrevocable))
+        .await;
+
+    response.assert_status_unauthorized();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "Token has no jti claim; log in again");
+}
+
+// =============================================================================
+// Logout
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_logout_revokes_token(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/logout")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let response = ctx
+        .server
+        .get("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_logout_leaves_other_tokens_valid(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let response = ctx
+        .server
+        .post("/api/auth/login")
+        .json(&json!({"email": "test@example.com", "password": "password123"}))
+        .await;
+    let body: serde_json::Value = response.json();
+    let other = body["token"].as_str().unwrap().to_string();
+
+    ctx.server
+        .post("/api/auth/logout")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let response = ctx
+        .server
+        .get("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", other))
+        .await;
+    response.assert_status_ok();
+}
+
+// =============================================================================
+// API Keys
+// =============================================================================
+
+async fn create_api_key(ctx: &HttpTestContext, token: &str, read_only: bool) -> CreatedApiKey {
+    let response = ctx
+        .server
+        .post("/api/auth/api-keys")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"label": "exports", "read_only": read_only}))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    response.json()
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_api_key_lists_sessions(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, default_session_json()).await;
+    let created = create_api_key(&ctx, &token, false).await;
+    assert!(created.api_key.last_used_at.is_none());
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("X-API-Key", created.key.clone())
+        .await;
+
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert_eq!(sessions.len(), 1);
+
+    // The listing never includes the key itself, but records its use
+    let response = ctx
+        .server
+        .get("/api/auth/api-keys")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    assert!(!response.text().contains(&created.key));
+    let api_keys: Vec<ApiKey> = response.json();
+    assert_eq!(api_keys.len(), 1);
+    assert_eq!(api_keys[0].label, "exports");
+    assert!(api_keys[0].last_used_at.is_some());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_revoked_api_key_is_rejected(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let created = create_api_key(&ctx, &token, false).await;
+
+    let response = ctx
+        .server
+        .delete(&format!("/api/auth/api-keys/{}", created.api_key.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("X-API-Key", created.key)
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_revoke_other_users_api_key_returns_404(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let other_token = register_and_get_token(&ctx, "other@example.com").await;
+    let created = create_api_key(&ctx, &token, false).await;
+
+    let response = ctx
+        .server
+        .delete(&format!("/api/auth/api-keys/{}", created.api_key.id))
+        .add_header("Authorization", format!("Bearer {}", other_token))
+        .await;
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("X-API-Key", created.key)
+        .await;
+    response.assert_status_ok();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_read_only_api_key_cannot_write(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let created = create_api_key(&ctx, &token, true).await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("X-API-Key", created.key.clone())
+        .json(&default_session_json())
+        .await;
+    response.assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("X-API-Key", created.key)
+        .await;
+    response.assert_status_ok();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_api_key_cannot_create_api_keys(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let created = create_api_key(&ctx, &token, false).await;
+
+    let response = ctx
+        .server
+        .post("/api/auth/api-keys")
+        .add_header("X-API-Key", created.key)
+        .json(&json!({"label": "another"}))
+        .await;
+    response.assert_status(axum::http::StatusCode::FORBIDDEN);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_unknown_api_key_returns_401(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("X-API-Key", "ptk_not-a-real-key")
+        .await;
+    response.assert_status_unauthorized();
+}
@@ -2,7 +2,9 @@
 
 use axum_test::TestServer;
 use poker_tracker::app::{AppState, create_app_router};
+use poker_tracker::models::poker_session::SessionWithProfit;
 use poker_tracker::models::user::AuthResponse;
+use poker_tracker::utils::PokerTrackerConfig;
 use rstest::fixture;
 use serde_json::json;
 use std::sync::Arc;
@@ -12,23 +14,39 @@ use crate::common::{PooledConnectionTestDb, test_config};
 /// Test context combining axum-test server with testcontainers database
 pub struct HttpTestContext {
     pub server: TestServer,
-    _db_provider: Arc<PooledConnectionTestDb>, // Keep TestDb alive for the container
+    pub db_provider: Arc<PooledConnectionTestDb>, // Keeps TestDb alive for the container
 }
 
 impl HttpTestContext {
     pub async fn new() -> Self {
-        let db_provider = Arc::new(PooledConnectionTestDb::new().await);
-        let config = test_config();
-        let app_state = Arc::new(AppState {
-            db_provider: db_provider.clone() as Arc<dyn poker_tracker::utils::DbProvider>,
+        Self::with_config(test_config()).await
+    }
+
+    /// Create a test context using a customized config
+    pub async fn with_config(config: PokerTrackerConfig) -> Self {
+        Self::with_db(PooledConnectionTestDb::new().await, config)
+    }
+
+    /// Create a test context against a database with no migrations applied
+    pub async fn without_migrations() -> Self {
+        Self::with_db(
+            PooledConnectionTestDb::without_migrations().await,
+            test_config(),
+        )
+    }
+
+    fn with_db(db: PooledConnectionTestDb, config: PokerTrackerConfig) -> Self {
+        let db_provider = Arc::new(db);
+        let app_state = Arc::new(AppState::new(
+            db_provider.clone() as Arc<dyn poker_tracker::utils::DbProvider>,
             config,
-        });
+        ));
         let router = create_app_router(app_state);
         let server = TestServer::new(router).expect("Failed to create test server");
 
         Self {
             server,
-            _db_provider: db_provider,
+            db_provider,
         }
     }
 }
@@ -64,3 +82,42 @@ pub fn default_session_json() -> serde_json::Value {
         "cash_out_amount": 150.0
     })
 }
+
+/// Create a session for the given token, asserting it was created
+pub async fn create_session(
+    ctx: &HttpTestContext,
+    token: &str,
+    body: serde_json::Value,
+) -> SessionWithProfit {
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    response.json()
+}
+
+/// Session request JSON on the given date, otherwise using defaults
+pub fn session_json_on(date: &str) -> serde_json::Value {
+    let mut body = default_session_json();
+    body["session_date"] = json!(date);
+    body
+}
+
+/// Grant the admin flag to the user with the given email
+pub fn make_admin(ctx: &HttpTestContext, email: &str) {
+    use diesel::prelude::*;
+    use poker_tracker::schema::users;
+    use poker_tracker::utils::DbProvider;
+
+    let mut conn = ctx
+        .db_provider
+        .get_connection()
+        .expect("Failed to get db connection");
+    diesel::update(users::table.filter(users::email.eq(email)))
+        .set(users::is_admin.eq(true))
+        .execute(&mut conn)
+        .expect("Failed to grant admin");
+}
@@ -2,9 +2,16 @@ mod common;
 mod http_common;
 
 use axum::http::StatusCode;
-use http_common::{HttpTestContext, default_session_json, http_ctx, register_and_get_token};
-use poker_tracker::models::poker_session::SessionWithProfit;
+use bigdecimal::BigDecimal;
+use common::test_config;
+use http_common::{
+    HttpTestContext, create_session, default_session_json, http_ctx, register_and_get_token,
+    session_json_on,
+};
+use poker_tracker::models::poker_session::{ProfitPreview, SessionWithProfit};
+use poker_tracker::models::template::Template;
 use poker_tracker::models::user::AuthResponse;
+use poker_tracker::utils::ExportFormat;
 use rstest::rstest;
 use serde_json::json;
 
@@ -32,10 +39,47 @@ async fn test_create_session_with_valid_data(#[future] http_ctx: HttpTestContext
 
     response.assert_status(StatusCode::CREATED);
     let session: SessionWithProfit = response.json();
-    assert_eq!(session.profit, 50.0);
+    assert_eq!(session.profit, BigDecimal::from(50));
     assert_eq!(session.session.duration_minutes, 120);
 }
 
+/// Timestamps must be RFC 3339 with an explicit UTC designator or offset
+fn assert_has_utc_offset(timestamp: &serde_json::Value) {
+    let timestamp = timestamp.as_str().expect("timestamp should be a string");
+    assert!(
+        timestamp.ends_with('Z') || timestamp.contains('+'),
+        "timestamp without offset: {timestamp}"
+    );
+    assert!(chrono::DateTime::parse_from_rfc3339(timestamp).is_ok());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_session_and_user_timestamps_carry_offset(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&default_session_json())
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let session: serde_json::Value = response.json();
+    assert_has_utc_offset(&session["created_at"]);
+    assert_has_utc_offset(&session["updated_at"]);
+
+    let me: serde_json::Value = ctx
+        .server
+        .get("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json();
+    assert_has_utc_offset(&me["created_at"]);
+    assert_has_utc_offset(&me["updated_at"]);
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_create_session_with_rebuy(#[future] http_ctx: HttpTestContext) {
@@ -58,7 +102,7 @@ async fn test_create_session_with_rebuy(#[future] http_ctx: HttpTestContext) {
     response.assert_status(StatusCode::CREATED);
     let session: SessionWithProfit = response.json();
     // profit = 200 - (100 + 50) = 50
-    assert_eq!(session.profit, 50.0);
+    assert_eq!(session.profit, BigDecimal::from(50));
 }
 
 #[rstest]
@@ -86,7 +130,7 @@ async fn test_create_session_with_notes(#[future] http_ctx: HttpTestContext) {
         session.session.notes,
         Some("Bad session, tilted on river".to_string())
     );
-    assert_eq!(session.profit, -20.0);
+    assert_eq!(session.profit, BigDecimal::from(-20));
 }
 
 #[rstest]
@@ -131,6 +175,88 @@ async fn test_create_session_zero_duration_returns_400(#[future] http_ctx: HttpT
     response.assert_status_bad_request();
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_create_session_oversized_duration_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["duration_minutes"] = json!(i64::from(i32::MAX) + 1);
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status_bad_request();
+    let error: serde_json::Value = response.json();
+    assert_eq!(error["error"], "duration out of range");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_update_session_oversized_duration_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let created = create_session(&ctx, &token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"duration_minutes": 99999999999999999999_f64}))
+        .await;
+
+    response.assert_status_bad_request();
+    let error: serde_json::Value = response.json();
+    assert_eq!(error["error"], "duration out of range");
+}
+
+#[rstest]
+#[case("stakes", json!("x".repeat(21)))]
+#[case("location", json!("x".repeat(101)))]
+#[case("latitude", json!(91.0))]
+#[tokio::test]
+async fn test_update_session_invalid_field_returns_400(
+    #[future] http_ctx: HttpTestContext,
+    #[case] field: &str,
+    #[case] value: serde_json::Value,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let created = create_session(&ctx, &token, default_session_json()).await;
+
+    for path in [
+        format!("/api/sessions/{}", created.session.id),
+        format!("/api/sessions/{}/preview-update", created.session.id),
+    ] {
+        let request = if path.ends_with("preview-update") {
+            ctx.server.post(&path)
+        } else {
+            ctx.server.put(&path)
+        };
+        let response = request
+            .add_header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ field: value }))
+            .await;
+
+        response.assert_status_bad_request();
+        let error: serde_json::Value = response.json();
+        assert_eq!(error["error"], "Validation failed");
+    }
+
+    // Nothing invalid was stored
+    let response = ctx
+        .server
+        .get(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    let fetched: serde_json::Value = response.json();
+    assert_eq!(fetched[field], serde_json::Value::Null);
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_get_sessions_empty(#[future] http_ctx: HttpTestContext) {
@@ -308,7 +434,7 @@ async fn test_update_session_all_fields(#[future] http_ctx: HttpTestContext) {
     response.assert_status_ok();
     let updated: SessionWithProfit = response.json();
     assert_eq!(updated.session.duration_minutes, 240);
-    assert_eq!(updated.profit, 200.0); // 500 - (200 + 100)
+    assert_eq!(updated.profit, BigDecimal::from(200)); // 500 - (200 + 100)
     assert_eq!(updated.session.notes, Some("Updated notes".to_string()));
 }
 
@@ -347,7 +473,7 @@ async fn test_update_session_partial(#[future] http_ctx: HttpTestContext) {
     let updated: SessionWithProfit = response.json();
     // Original values preserved
     assert_eq!(updated.session.duration_minutes, 120);
-    assert_eq!(updated.profit, 50.0);
+    assert_eq!(updated.profit, BigDecimal::from(50));
     // Only notes changed
     assert_eq!(
         updated.session.notes,
@@ -507,7 +633,9 @@ async fn test_export_sessions_csv_has_header(#[future] http_ctx: HttpTestContext
 
     response.assert_status_ok();
     let csv = response.text();
-    assert!(csv.contains("Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Notes"));
+    assert!(csv.contains(
+        "Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Game Type,Stakes,Location,Notes"
+    ));
 }
 
 #[rstest]
@@ -594,242 +722,2856 @@ async fn test_export_sessions_empty_returns_header_only(#[future] http_ctx: Http
     assert_eq!(lines.len(), 1); // Just the header
 }
 
-// =============================================================================
-// Phase 7: Full Workflow Tests
-// =============================================================================
-
 #[rstest]
 #[tokio::test]
-async fn test_complete_user_workflow(#[future] http_ctx: HttpTestContext) {
+async fn test_export_sessions_json_empty_returns_empty_array(#[future] http_ctx: HttpTestContext) {
     let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
 
-    // 1. Register
-    let register_resp = ctx
-        .server
-        .post("/api/auth/register")
-        .json(&json!({
-            "email": "poker@example.com",
-            "username": "pokerplayer",
-            "password": "securepwd123"
-        }))
-        .await;
-    register_resp.assert_status(axum::http::StatusCode::CREATED);
-    let auth: AuthResponse = register_resp.json();
-    let token = auth.token;
-
-    // 2. Get profile
-    let me_resp = ctx
+    let response = ctx
         .server
-        .get("/api/auth/me")
+        .get("/api/sessions/export")
+        .add_query_param("format", "json")
         .add_header("Authorization", format!("Bearer {}", token))
         .await;
-    me_resp.assert_status_ok();
 
-    // 3. Update cookie consent
-    ctx.server
-        .put("/api/auth/cookie-consent")
-        .add_header("Authorization", format!("Bearer {}", token))
-        .json(&json!({ "cookie_consent": true }))
-        .await
-        .assert_status_ok();
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "application/json");
+    let sessions: Vec<serde_json::Value> = response.json();
+    assert!(sessions.is_empty());
+}
 
-    // 4. Create multiple sessions
-    for i in 1..=3 {
-        ctx.server
-            .post("/api/sessions")
+#[rstest]
+#[tokio::test]
+async fn test_export_sessions_empty_after_time_range_filter(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    // default_session_json is dated 2024-01-15, well outside the last 7 days
+    create_session(&ctx, &token, default_session_json()).await;
+
+    for (format, expected) in [
+        (
+            "csv",
+            "Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Game Type,Stakes,Location,Notes\n",
+        ),
+        ("json", "[]"),
+    ] {
+        let response = ctx
+            .server
+            .get("/api/sessions/export")
+            .add_query_param("format", format)
+            .add_query_param("time_range", "7days")
             .add_header("Authorization", format!("Bearer {}", token))
-            .json(&json!({
-                "session_date": format!("2024-01-{:02}", i),
-                "duration_minutes": 60 * i,
-                "buy_in_amount": 100.0,
-                "cash_out_amount": 100.0 + (i as f64 * 25.0)
-            }))
-            .await
-            .assert_status(StatusCode::CREATED);
+            .await;
+
+        response.assert_status_ok();
+        assert_eq!(response.text(), expected, "format {}", format);
     }
+}
 
-    // 5. Get all sessions
-    let sessions_resp = ctx
-        .server
-        .get("/api/sessions")
-        .add_header("Authorization", format!("Bearer {}", token))
-        .await;
-    let sessions: Vec<SessionWithProfit> = sessions_resp.json();
-    assert_eq!(sessions.len(), 3);
+#[rstest]
+#[tokio::test]
+async fn test_export_sessions_column_subset(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, default_session_json()).await;
 
-    // 6. Export CSV
-    let export_resp = ctx
+    let response = ctx
         .server
         .get("/api/sessions/export")
+        .add_query_param("columns", "date,profit")
         .add_header("Authorization", format!("Bearer {}", token))
         .await;
-    export_resp.assert_status_ok();
-    let csv = export_resp.text();
-    assert!(csv.contains("2024-01-01"));
-    assert!(csv.contains("2024-01-03"));
 
-    // 7. Change password
-    ctx.server
-        .post("/api/auth/change-password")
-        .add_header("Authorization", format!("Bearer {}", token))
-        .json(&json!({
-            "old_password": "securepwd123",
-            "new_password": "newsecurepwd456"
-        }))
-        .await
-        .assert_status_ok();
+    response.assert_status_ok();
+    let csv = response.text();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines, vec!["Date,Profit/Loss", "2024-01-15,50.00"]);
+}
 
-    // 8. Login with new password
-    let login_resp = ctx
+#[rstest]
+#[tokio::test]
+async fn test_export_sessions_unknown_column_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
         .server
-        .post("/api/auth/login")
-        .json(&json!({
-            "email": "poker@example.com",
-            "password": "newsecurepwd456"
-        }))
+        .get("/api/sessions/export")
+        .add_query_param("columns", "date,rake")
+        .add_header("Authorization", format!("Bearer {}", token))
         .await;
-    login_resp.assert_status_ok();
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "Unknown export column: rake");
 }
 
 #[rstest]
 #[tokio::test]
-async fn test_multi_user_isolation_workflow(#[future] http_ctx: HttpTestContext) {
+async fn test_export_sessions_columns_with_json_returns_400(#[future] http_ctx: HttpTestContext) {
     let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
 
-    // User 1 registers and creates sessions
-    let token1 = register_and_get_token(&ctx, "user1@example.com").await;
-    for i in 1..=2 {
-        ctx.server
-            .post("/api/sessions")
-            .add_header("Authorization", format!("Bearer {}", token1))
-            .json(&json!({
-                "session_date": format!("2024-01-{:02}", i),
-                "duration_minutes": 60,
-                "buy_in_amount": 100.0,
-                "cash_out_amount": 150.0
-            }))
-            .await
-            .assert_status(StatusCode::CREATED);
-    }
-
-    // User 2 registers and creates sessions
-    let token2 = register_and_get_token(&ctx, "user2@example.com").await;
-    for i in 3..=5 {
-        ctx.server
-            .post("/api/sessions")
-            .add_header("Authorization", format!("Bearer {}", token2))
-            .json(&json!({
-                "session_date": format!("2024-01-{:02}", i),
-                "duration_minutes": 90,
-                "buy_in_amount": 200.0,
-                "cash_out_amount": 180.0
-            }))
-            .await
-            .assert_status(StatusCode::CREATED);
-    }
-
-    // User 1 sees only their 2 sessions
-    let user1_sessions: Vec<SessionWithProfit> = ctx
+    let response = ctx
         .server
-        .get("/api/sessions")
-        .add_header("Authorization", format!("Bearer {}", token1))
-        .await
-        .json();
-    assert_eq!(user1_sessions.len(), 2);
-    assert!(user1_sessions.iter().all(|s| s.profit == 50.0));
+        .get("/api/sessions/export")
+        .add_query_param("format", "json")
+        .add_query_param("columns", "date")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
 
-    // User 2 sees only their 3 sessions
-    let user2_sessions: Vec<SessionWithProfit> = ctx
-        .server
-        .get("/api/sessions")
-        .add_header("Authorization", format!("Bearer {}", token2))
-        .await
-        .json();
-    assert_eq!(user2_sessions.len(), 3);
-    assert!(user2_sessions.iter().all(|s| s.profit == -20.0));
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
 
-    // User 1's export has 2 data rows
-    let export1 = ctx
+#[rstest]
+#[tokio::test]
+async fn test_export_sessions_anonymize_drops_notes(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["notes"] = json!("Villain in seat 3 tilts after losing big pots");
+    create_session(&ctx, &token, body).await;
+
+    let response = ctx
         .server
         .get("/api/sessions/export")
-        .add_header("Authorization", format!("Bearer {}", token1))
-        .await
-        .text();
-    assert_eq!(export1.lines().count(), 3); // header + 2 rows
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    let csv = response.text();
+    assert!(csv.starts_with(
+        "Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Game Type,Stakes,Location,Notes\n"
+    ));
+    assert!(csv.contains("Villain in seat 3"));
 
-    // User 2's export has 3 data rows
-    let export2 = ctx
+    let response = ctx
         .server
         .get("/api/sessions/export")
-        .add_header("Authorization", format!("Bearer {}", token2))
-        .await
-        .text();
-    assert_eq!(export2.lines().count(), 4); // header + 3 rows
+        .add_query_param("anonymize", "true")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    let csv = response.text();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "Date,Duration (hours),Buy-in,Rebuy,Cash Out,Profit/Loss,Game Type,Stakes",
+            "2024-01-15,2.0,100.00,0.00,150.00,50.00,cash,"
+        ]
+    );
 }
 
 #[rstest]
 #[tokio::test]
-async fn test_session_crud_lifecycle(#[future] http_ctx: HttpTestContext) {
+async fn test_export_sessions_anonymize_json_clears_text(#[future] http_ctx: HttpTestContext) {
     let ctx = http_ctx.await;
     let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["notes"] = json!("Private journal entry");
+    body["category"] = json!("Bellagio");
+    create_session(&ctx, &token, body).await;
 
-    // Create
-    let create_resp = ctx
+    let response = ctx
         .server
-        .post("/api/sessions")
+        .get("/api/sessions/export")
+        .add_query_param("format", "json")
+        .add_query_param("anonymize", "true")
         .add_header("Authorization", format!("Bearer {}", token))
-        .json(&json!({
-            "session_date": "2024-06-15",
-            "duration_minutes": 180,
-            "buy_in_amount": 500.0,
-            "cash_out_amount": 750.0,
-            "notes": "Initial notes"
-        }))
         .await;
-    create_resp.assert_status(StatusCode::CREATED);
-    let session: SessionWithProfit = create_resp.json();
-    let session_id = session.session.id;
-    assert_eq!(session.profit, 250.0);
 
-    // Read
-    let read_resp = ctx
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert_eq!(sessions.len(), 1);
+    assert!(sessions[0].session.notes.is_none());
+    assert!(sessions[0].session.category.is_none());
+    assert_eq!(sessions[0].profit, BigDecimal::from(50));
+}
+
+#[tokio::test]
+async fn test_export_sessions_over_max_rows_continues_by_date_cursor() {
+    let mut config = test_config();
+    config.max_export_rows = Some(2);
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    for day in 1..=5 {
+        create_session(
+            &ctx,
+            &token,
+            session_json_on(&format!("2024-01-{:02}", day)),
+        )
+        .await;
+    }
+
+    let mut exported_dates = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut pages = 0;
+    loop {
+        let mut request = ctx
+            .server
+            .get("/api/sessions/export")
+            .add_query_param("format", "json")
+            .add_header("Authorization", format!("Bearer {}", token));
+        if let Some(cursor) = &cursor {
+            request = request.add_query_param("cursor", cursor);
+        }
+        let response = request.await;
+        response.assert_status_ok();
+        pages += 1;
+
+        let sessions: Vec<SessionWithProfit> = response.json();
+        assert!(sessions.len() <= 2);
+        exported_dates.extend(sessions.iter().map(|s| s.session.session_date.to_string()));
+
+        cursor = response
+            .maybe_header("x-export-next-cursor")
+            .map(|value| value.to_str().unwrap().to_string());
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(pages, 3);
+    assert_eq!(
+        exported_dates,
+        vec![
+            "2024-01-01",
+            "2024-01-02",
+            "2024-01-03",
+            "2024-01-04",
+            "2024-01-05"
+        ]
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_export_sessions_invalid_cursor_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
         .server
-        .get(&format!("/api/sessions/{}", session_id))
+        .get("/api/sessions/export")
+        .add_query_param("cursor", "last-tuesday")
         .add_header("Authorization", format!("Bearer {}", token))
         .await;
-    read_resp.assert_status_ok();
-    let read_session: SessionWithProfit = read_resp.json();
-    assert_eq!(read_session.session.id, session_id);
 
-    // Update
-    let update_resp = ctx
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_export_sessions_json_format(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, default_session_json()).await;
+
+    let response = ctx
         .server
-        .put(&format!("/api/sessions/{}", session_id))
+        .get("/api/sessions/export")
+        .add_query_param("format", "json")
         .add_header("Authorization", format!("Bearer {}", token))
-        .json(&json!({
-            "cash_out_amount": 1000.0,
-            "notes": "Updated: big win!"
-        }))
         .await;
-    update_resp.assert_status_ok();
-    let updated_session: SessionWithProfit = update_resp.json();
-    assert_eq!(updated_session.profit, 500.0);
+
+    response.assert_status_ok();
     assert_eq!(
-        updated_session.session.notes,
-        Some("Updated: big win!".to_string())
+        response.headers().get("content-type").unwrap(),
+        "application/json"
     );
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].profit, BigDecimal::from(50));
+}
 
-    // Delete
+#[tokio::test]
+async fn test_export_sessions_disabled_format_returns_400() {
+    let mut config = test_config();
+    config.enabled_export_formats = vec![ExportFormat::Csv];
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("format", "json")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "Export format json is disabled");
+
+    let unknown = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("format", "xlsx")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    unknown.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = unknown.json();
+    assert_eq!(body["error"], "Unknown export format: xlsx");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_export_sessions_range_returns_partial_content(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, default_session_json()).await;
+
+    let full = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    full.assert_status_ok();
+    assert_eq!(full.headers().get("accept-ranges").unwrap(), "bytes");
+    let csv = full.as_bytes().to_vec();
+
+    let partial = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .add_header("Range", "bytes=5-24")
+        .await;
+
+    partial.assert_status(StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        partial.headers().get("content-range").unwrap(),
+        &format!("bytes 5-24/{}", csv.len())
+    );
+    assert_eq!(partial.as_bytes().as_ref(), &csv[5..=24]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_export_sessions_range_past_end_returns_416(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .add_header("Range", "bytes=100000-")
+        .await;
+
+    response.assert_status(StatusCode::RANGE_NOT_SATISFIABLE);
+}
+
+// =============================================================================
+// Phase 7: Full Workflow Tests
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_complete_user_workflow(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+
+    // 1. Register
+    let register_resp = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "poker@example.com",
+            "username": "pokerplayer",
+            "password": "securepwd123"
+        }))
+        .await;
+    register_resp.assert_status(axum::http::StatusCode::CREATED);
+    let auth: AuthResponse = register_resp.json();
+    let token = auth.token;
+
+    // 2. Get profile
+    let me_resp = ctx
+        .server
+        .get("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    me_resp.assert_status_ok();
+
+    // 3. Update cookie consent
     ctx.server
-        .delete(&format!("/api/sessions/{}", session_id))
+        .put("/api/auth/cookie-consent")
         .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "cookie_consent": true }))
         .await
         .assert_status_ok();
 
-    // Verify deleted
+    // 4. Create multiple sessions
+    for i in 1..=3 {
+        ctx.server
+            .post("/api/sessions")
+            .add_header("Authorization", format!("Bearer {}", token))
+            .json(&json!({
+                "session_date": format!("2024-01-{:02}", i),
+                "duration_minutes": 60 * i,
+                "buy_in_amount": 100.0,
+                "cash_out_amount": 100.0 + (i as f64 * 25.0)
+            }))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    // 5. Get all sessions
+    let sessions_resp = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    let sessions: Vec<SessionWithProfit> = sessions_resp.json();
+    assert_eq!(sessions.len(), 3);
+
+    // 6. Export CSV
+    let export_resp = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    export_resp.assert_status_ok();
+    let csv = export_resp.text();
+    assert!(csv.contains("2024-01-01"));
+    assert!(csv.contains("2024-01-03"));
+
+    // 7. Change password
     ctx.server
-        .get(&format!("/api/sessions/{}", session_id))
+        .post("/api/auth/change-password")
         .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "old_password": "securepwd123",
+            "new_password": "newsecurepwd456"
+        }))
         .await
-        .assert_status_not_found();
+        .assert_status_ok();
+
+    // 8. Login with new password
+    let login_resp = ctx
+        .server
+        .post("/api/auth/login")
+        .json(&json!({
+            "email": "poker@example.com",
+            "password": "newsecurepwd456"
+        }))
+        .await;
+    login_resp.assert_status_ok();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_multi_user_isolation_workflow(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+
+    // User 1 registers and creates sessions
+    let token1 = register_and_get_token(&ctx, "user1@example.com").await;
+    for i in 1..=2 {
+        ctx.server
+            .post("/api/sessions")
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .json(&json!({
+                "session_date": format!("2024-01-{:02}", i),
+                "duration_minutes": 60,
+                "buy_in_amount": 100.0,
+                "cash_out_amount": 150.0
+            }))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    // User 2 registers and creates sessions
+    let token2 = register_and_get_token(&ctx, "user2@example.com").await;
+    for i in 3..=5 {
+        ctx.server
+            .post("/api/sessions")
+            .add_header("Authorization", format!("Bearer {}", token2))
+            .json(&json!({
+                "session_date": format!("2024-01-{:02}", i),
+                "duration_minutes": 90,
+                "buy_in_amount": 200.0,
+                "cash_out_amount": 180.0
+            }))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    // User 1 sees only their 2 sessions
+    let user1_sessions: Vec<SessionWithProfit> = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .await
+        .json();
+    assert_eq!(user1_sessions.len(), 2);
+    let won = BigDecimal::from(50);
+    assert!(user1_sessions.iter().all(|s| s.profit == won));
+
+    // User 2 sees only their 3 sessions
+    let user2_sessions: Vec<SessionWithProfit> = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token2))
+        .await
+        .json();
+    assert_eq!(user2_sessions.len(), 3);
+    let lost = BigDecimal::from(-20);
+    assert!(user2_sessions.iter().all(|s| s.profit == lost));
+
+    // User 1's export has 2 data rows
+    let export1 = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .await
+        .text();
+    assert_eq!(export1.lines().count(), 3); // header + 2 rows
+
+    // User 2's export has 3 data rows
+    let export2 = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_header("Authorization", format!("Bearer {}", token2))
+        .await
+        .text();
+    assert_eq!(export2.lines().count(), 4); // header + 3 rows
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_session_crud_lifecycle(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    // Create
+    let create_resp = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "session_date": "2024-06-15",
+            "duration_minutes": 180,
+            "buy_in_amount": 500.0,
+            "cash_out_amount": 750.0,
+            "notes": "Initial notes"
+        }))
+        .await;
+    create_resp.assert_status(StatusCode::CREATED);
+    let session: SessionWithProfit = create_resp.json();
+    let session_id = session.session.id;
+    assert_eq!(session.profit, BigDecimal::from(250));
+
+    // Read
+    let read_resp = ctx
+        .server
+        .get(&format!("/api/sessions/{}", session_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    read_resp.assert_status_ok();
+    let read_session: SessionWithProfit = read_resp.json();
+    assert_eq!(read_session.session.id, session_id);
+
+    // Update
+    let update_resp = ctx
+        .server
+        .put(&format!("/api/sessions/{}", session_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "cash_out_amount": 1000.0,
+            "notes": "Updated: big win!"
+        }))
+        .await;
+    update_resp.assert_status_ok();
+    let updated_session: SessionWithProfit = update_resp.json();
+    assert_eq!(updated_session.profit, BigDecimal::from(500));
+    assert_eq!(
+        updated_session.session.notes,
+        Some("Updated: big win!".to_string())
+    );
+
+    // Delete
+    ctx.server
+        .delete(&format!("/api/sessions/{}", session_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status_ok();
+
+    // Verify deleted
+    ctx.server
+        .get(&format!("/api/sessions/{}", session_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status_not_found();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_rebuy_without_buy_in_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "session_date": "2024-01-15",
+            "duration_minutes": 120,
+            "buy_in_amount": 0.0,
+            "rebuy_amount": 100.0,
+            "cash_out_amount": 150.0
+        }))
+        .await;
+
+    response.assert_status_bad_request();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "A rebuy requires a non-zero buy-in");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_with_cents_is_exact(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let session = create_session(
+        &ctx,
+        &token,
+        json!({
+            "session_date": "2024-01-15",
+            "duration_minutes": 120,
+            "buy_in_cents": 10033,
+            "rebuy_cents": 1,
+            "cash_out_cents": 20000
+        }),
+    )
+    .await;
+
+    assert_eq!(session.session.buy_in_amount.to_string(), "100.33");
+    assert_eq!(session.session.rebuy_amount.to_string(), "0.01");
+    assert_eq!(session.session.cash_out_amount.to_string(), "200.00");
+    assert_eq!(session.session.profit().to_string(), "99.66");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_amount_and_cents_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let mut body = default_session_json();
+    body["buy_in_cents"] = json!(10000);
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status_bad_request();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_missing_buy_in_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "session_date": "2024-01-15",
+            "duration_minutes": 120,
+            "cash_out_amount": 150.0
+        }))
+        .await;
+
+    response.assert_status_bad_request();
+}
+
+// =============================================================================
+// Session Ordering
+// =============================================================================
+
+async fn create_sessions_on_dates(ctx: &HttpTestContext, token: &str) {
+    for date in ["2024-01-02", "2024-01-03", "2024-01-01"] {
+        create_session(ctx, token, session_json_on(date)).await;
+    }
+}
+
+fn session_dates(sessions: &[SessionWithProfit]) -> Vec<String> {
+    sessions
+        .iter()
+        .map(|s| s.session.session_date.to_string())
+        .collect()
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_get_sessions_default_order_is_descending(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_sessions_on_dates(&ctx, &token).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert_eq!(
+        session_dates(&sessions),
+        vec!["2024-01-03", "2024-01-02", "2024-01-01"]
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_get_sessions_explicit_order(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_sessions_on_dates(&ctx, &token).await;
+
+    let asc: Vec<SessionWithProfit> = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("order", "asc")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json();
+    assert_eq!(
+        session_dates(&asc),
+        vec!["2024-01-01", "2024-01-02", "2024-01-03"]
+    );
+
+    let desc: Vec<SessionWithProfit> = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("order", "desc")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json();
+    assert_eq!(
+        session_dates(&desc),
+        vec!["2024-01-03", "2024-01-02", "2024-01-01"]
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_get_sessions_invalid_order_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("order", "sideways")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_bad_request();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_recent_sessions_returns_n_newest(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    for day in 1..=7 {
+        create_session(
+            &ctx,
+            &token,
+            session_json_on(&format!("2024-03-{:02}", day)),
+        )
+        .await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/recent")
+        .add_query_param("n", 3)
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert_eq!(
+        session_dates(&sessions),
+        vec!["2024-03-07", "2024-03-06", "2024-03-05"]
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_recent_sessions_defaults_to_five(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    for day in 1..=7 {
+        create_session(
+            &ctx,
+            &token,
+            session_json_on(&format!("2024-03-{:02}", day)),
+        )
+        .await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/recent")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert_eq!(sessions.len(), 5);
+    assert_eq!(sessions[0].session.session_date.to_string(), "2024-03-07");
+}
+
+#[rstest]
+#[case("0")]
+#[case("51")]
+#[case("-1")]
+#[tokio::test]
+async fn test_recent_sessions_invalid_n_returns_400(
+    #[future] http_ctx: HttpTestContext,
+    #[case] n: &str,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/recent")
+        .add_query_param("n", n)
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_bad_request();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_export_sessions_order(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_sessions_on_dates(&ctx, &token).await;
+
+    let export_dates = |csv: String| -> Vec<String> {
+        csv.lines()
+            .skip(1)
+            .map(|line| line.split(',').next().unwrap().to_string())
+            .collect()
+    };
+
+    // Exports default to ascending for spreadsheets
+    let default_csv = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .text();
+    assert_eq!(
+        export_dates(default_csv),
+        vec!["2024-01-01", "2024-01-02", "2024-01-03"]
+    );
+
+    let desc_csv = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("order", "desc")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .text();
+    assert_eq!(
+        export_dates(desc_csv),
+        vec!["2024-01-03", "2024-01-02", "2024-01-01"]
+    );
+}
+
+// =============================================================================
+// Session List Size Cap
+// =============================================================================
+
+async fn capped_ctx(max_list_page_size: u32) -> HttpTestContext {
+    let mut config = test_config();
+    config.max_list_page_size = max_list_page_size;
+    HttpTestContext::with_config(config).await
+}
+
+#[tokio::test]
+async fn test_get_sessions_never_exceeds_max_page_size() {
+    let ctx = capped_ctx(2).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_sessions_on_dates(&ctx, &token).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert_eq!(session_dates(&sessions), vec!["2024-01-03", "2024-01-02"]);
+}
+
+#[tokio::test]
+async fn test_get_sessions_limit_above_cap_returns_400() {
+    let ctx = capped_ctx(2).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_sessions_on_dates(&ctx, &token).await;
+
+    for limit in ["3", "1000", "0", "-1"] {
+        let response = ctx
+            .server
+            .get("/api/sessions")
+            .add_query_param("limit", limit)
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_get_sessions_limit_and_offset_page_through() {
+    let ctx = capped_ctx(2).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_sessions_on_dates(&ctx, &token).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("limit", "2")
+        .add_query_param("offset", "2")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert_eq!(session_dates(&sessions), vec!["2024-01-01"]);
+}
+
+// =============================================================================
+// Default Time Range
+// =============================================================================
+
+#[tokio::test]
+async fn test_default_time_range_applies_when_omitted() {
+    let mut config = test_config();
+    config.default_time_range = "30days".to_string();
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let today = chrono::Utc::now().date_naive().to_string();
+    create_session(&ctx, &token, session_json_on(&today)).await;
+    create_session(&ctx, &token, session_json_on("2024-01-15")).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    assert_eq!(response.json::<Vec<SessionWithProfit>>().len(), 1);
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("time_range", "all")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    assert_eq!(response.json::<Vec<SessionWithProfit>>().len(), 2);
+
+    // Header row plus one line per session
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    assert_eq!(response.text().lines().count(), 2);
+
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("time_range", "all")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    assert_eq!(response.text().lines().count(), 3);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_list_invalid_time_range_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("time_range", "2weeks")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Notes Storage Cap
+// =============================================================================
+
+fn session_with_notes(notes: &str) -> serde_json::Value {
+    let mut body = default_session_json();
+    body["notes"] = json!(notes);
+    body
+}
+
+#[tokio::test]
+async fn test_notes_cap_rejects_create_and_update_over_limit() {
+    let mut config = test_config();
+    config.max_total_notes_bytes = Some(20);
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    // Exactly at the cap is allowed
+    let first = create_session(&ctx, &token, session_with_notes("0123456789")).await;
+    create_session(&ctx, &token, session_with_notes("abcdefghij")).await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&session_with_notes("x"))
+        .await;
+    response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["current_bytes"], 20);
+    assert_eq!(body["max_bytes"], 20);
+
+    // Growing an existing note is refused too
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", first.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "notes": "0123456789!" }))
+        .await;
+    response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+
+    // Trimming frees room for a new session
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", first.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "notes": "01234" }))
+        .await;
+    response.assert_status_ok();
+    create_session(&ctx, &token, session_with_notes("x")).await;
+}
+
+#[tokio::test]
+async fn test_notes_cap_applies_to_append() {
+    let mut config = test_config();
+    config.max_total_notes_bytes = Some(10);
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let created = create_session(&ctx, &token, session_with_notes("01234")).await;
+
+    let response = ctx
+        .server
+        .post(&format!(
+            "/api/sessions/{}/notes/append",
+            created.session.id
+        ))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "text": "56789" }))
+        .await;
+
+    // The newline separator pushes the total to 11 bytes
+    response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["current_bytes"], 5);
+}
+
+// =============================================================================
+// Debug Timing Headers
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_debug_timing_headers_absent_by_default(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    assert!(!response.headers().contains_key("x-db-queries"));
+    assert!(!response.headers().contains_key("x-db-time-ms"));
+}
+
+#[tokio::test]
+async fn test_debug_timing_headers_when_enabled() {
+    let mut config = test_config();
+    config.debug_timing = true;
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let queries: u64 = response
+        .headers()
+        .get("x-db-queries")
+        .expect("missing X-DB-Queries")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(queries >= 1);
+
+    let time_ms: f64 = response
+        .headers()
+        .get("x-db-time-ms")
+        .expect("missing X-DB-Time-Ms")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(time_ms >= 0.0);
+}
+
+// =============================================================================
+// Large Loss Confirmation
+// =============================================================================
+
+async fn large_loss_ctx() -> HttpTestContext {
+    let mut config = test_config();
+    config.large_loss_confirm_threshold = Some(1000.0);
+    HttpTestContext::with_config(config).await
+}
+
+fn large_loss_session_json() -> serde_json::Value {
+    let mut body = default_session_json();
+    body["buy_in_amount"] = json!(2000.0);
+    body["cash_out_amount"] = json!(500.0);
+    body
+}
+
+#[tokio::test]
+async fn test_large_loss_without_confirmation_returns_400() {
+    let ctx = large_loss_ctx().await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&large_loss_session_json())
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert_eq!(
+        body["loss"].as_str().unwrap().parse::<f64>().unwrap(),
+        1500.0
+    );
+}
+
+#[tokio::test]
+async fn test_large_loss_with_confirmation_is_created() {
+    let ctx = large_loss_ctx().await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let mut body = large_loss_session_json();
+    body["confirm_large_loss"] = json!(true);
+    let session = create_session(&ctx, &token, body).await;
+
+    assert_eq!(session.profit, BigDecimal::from(-1500));
+}
+
+#[tokio::test]
+async fn test_update_to_large_loss_requires_confirmation() {
+    let ctx = large_loss_ctx().await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let session = create_session(&ctx, &token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", session.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "cash_out_amount": 0.0, "buy_in_amount": 5000.0 }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_large_loss_guard_follows_profit_convention() {
+    let ctx = large_loss_ctx().await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    ctx.server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "profit_convention": "exclude_rebuy" }))
+        .await
+        .assert_status_ok();
+
+    // A 1700 loss counting the rebuy, but only 200 under exclude_rebuy
+    let mut body = default_session_json();
+    body["buy_in_amount"] = json!(200.0);
+    body["rebuy_amount"] = json!(1500.0);
+    body["cash_out_amount"] = json!(0.0);
+
+    let response = ctx
+        .server
+        .post("/api/sessions/preview-profit")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+    response.assert_status_ok();
+    let preview: ProfitPreview = response.json();
+
+    let session = create_session(&ctx, &token, body).await;
+    assert_eq!(session.profit, BigDecimal::from(-200));
+    assert_eq!(preview.profit, session.profit);
+    assert_eq!(preview.roi, Some(-1.0));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_large_loss_allowed_when_guard_disabled(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let session = create_session(&ctx, &token, large_loss_session_json()).await;
+
+    assert_eq!(session.profit, BigDecimal::from(-1500));
+}
+
+// =============================================================================
+// Per-user Session Defaults
+// =============================================================================
+
+async fn set_default_buy_in(ctx: &HttpTestContext, token: &str, default_buy_in: f64) {
+    ctx.server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "default_buy_in": default_buy_in }))
+        .await
+        .assert_status_ok();
+}
+
+fn session_json_without_buy_in() -> serde_json::Value {
+    let mut body = default_session_json();
+    body.as_object_mut().unwrap().remove("buy_in_amount");
+    body
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_uses_default_buy_in_when_omitted(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    set_default_buy_in(&ctx, &token, 50.0).await;
+
+    let session = create_session(&ctx, &token, session_json_without_buy_in()).await;
+
+    assert_eq!(session.session.buy_in_amount, BigDecimal::from(50));
+    assert_eq!(session.profit, BigDecimal::from(100));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_explicit_buy_in_overrides_default(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    set_default_buy_in(&ctx, &token, 50.0).await;
+
+    let session = create_session(&ctx, &token, default_session_json()).await;
+
+    assert_eq!(session.session.buy_in_amount, BigDecimal::from(100));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_without_buy_in_or_default_returns_400(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&session_json_without_buy_in())
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_update_profile_rejects_negative_default_buy_in(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "default_buy_in": -10.0 }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_update_profile_leaves_omitted_fields_alone(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    set_default_buy_in(&ctx, &token, 50.0).await;
+
+    let response = ctx
+        .server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "profit_convention": "exclude_rebuy" }))
+        .await;
+    response.assert_status_ok();
+    let user: serde_json::Value = response.json();
+    assert_eq!(user["profit_convention"], "exclude_rebuy");
+    assert_eq!(user["default_buy_in"], "50.00");
+
+    let response = ctx
+        .server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "default_buy_in": null }))
+        .await;
+    response.assert_status_ok();
+    let user: serde_json::Value = response.json();
+    assert!(user["default_buy_in"].is_null());
+    assert_eq!(user["profit_convention"], "exclude_rebuy");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_uses_default_location_and_game_type(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let response = ctx
+        .server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "default_location": "Bellagio", "default_game_type": "tournament" }))
+        .await;
+    response.assert_status_ok();
+    let user: serde_json::Value = response.json();
+    assert_eq!(user["default_location"], "Bellagio");
+    assert_eq!(user["default_game_type"], "tournament");
+
+    let session = create_session(&ctx, &token, default_session_json()).await;
+    assert_eq!(session.session.location.as_deref(), Some("Bellagio"));
+    assert_eq!(session.session.game_type, "tournament");
+
+    // Explicit values win over the defaults
+    let mut body = default_session_json();
+    body["location"] = json!("Aria");
+    body["game_type"] = json!("cash");
+    let session = create_session(&ctx, &token, body).await;
+    assert_eq!(session.session.location.as_deref(), Some("Aria"));
+    assert_eq!(session.session.game_type, "cash");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_update_profile_rejects_unknown_default_game_type(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "default_game_type": "roulette" }))
+        .await;
+
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+// =============================================================================
+// Appending Notes
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_append_notes_twice_keeps_order(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let session = create_session(&ctx, &token, default_session_json()).await;
+
+    for text in ["Tight table early", "Moved to seat 3"] {
+        ctx.server
+            .post(&format!(
+                "/api/sessions/{}/notes/append",
+                session.session.id
+            ))
+            .add_header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ "text": text }))
+            .await
+            .assert_status_ok();
+    }
+
+    let updated: SessionWithProfit = ctx
+        .server
+        .get(&format!("/api/sessions/{}", session.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json();
+    assert_eq!(
+        updated.session.notes.as_deref(),
+        Some("Tight table early\nMoved to seat 3")
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_append_notes_with_timestamp(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["notes"] = json!("Existing");
+    let session = create_session(&ctx, &token, body).await;
+
+    let response = ctx
+        .server
+        .post(&format!(
+            "/api/sessions/{}/notes/append",
+            session.session.id
+        ))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "text": "Big pot", "timestamp": true }))
+        .await;
+
+    response.assert_status_ok();
+    let updated: SessionWithProfit = response.json();
+    let notes = updated.session.notes.unwrap();
+    assert!(notes.starts_with("Existing\n["));
+    assert!(notes.ends_with("UTC] Big pot"));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_append_notes_wrong_user_returns_404(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let owner_token = register_and_get_token(&ctx, "owner@example.com").await;
+    let other_token = register_and_get_token(&ctx, "other@example.com").await;
+    let session = create_session(&ctx, &owner_token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .post(&format!(
+            "/api/sessions/{}/notes/append",
+            session.session.id
+        ))
+        .add_header("Authorization", format!("Bearer {}", other_token))
+        .json(&json!({ "text": "Not mine" }))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_append_notes_empty_text_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let session = create_session(&ctx, &token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .post(&format!(
+            "/api/sessions/{}/notes/append",
+            session.session.id
+        ))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "text": "" }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Notes Templates
+// =============================================================================
+
+async fn create_template(ctx: &HttpTestContext, token: &str, body: &str) -> Template {
+    let response = ctx
+        .server
+        .post("/api/templates")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "name": "Standard", "body": body }))
+        .await;
+
+    response.assert_status(StatusCode::CREATED);
+    response.json()
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_and_list_templates(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let other_token = register_and_get_token(&ctx, "other@example.com").await;
+    let template = create_template(&ctx, &token, "Villain read:\nKey hand:").await;
+    create_template(&ctx, &other_token, "Not mine").await;
+
+    let response = ctx
+        .server
+        .get("/api/templates")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let templates: Vec<Template> = response.json();
+    assert_eq!(templates.len(), 1);
+    assert_eq!(templates[0].id, template.id);
+    assert_eq!(templates[0].body, "Villain read:\nKey hand:");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_template_empty_body_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .post("/api/templates")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "name": "Standard", "body": "" }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_uses_template_when_notes_omitted(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let template = create_template(&ctx, &token, "Villain read:\nKey hand:").await;
+
+    let mut body = default_session_json();
+    body["template_id"] = json!(template.id);
+    let session = create_session(&ctx, &token, body).await;
+
+    assert_eq!(
+        session.session.notes.as_deref(),
+        Some("Villain read:\nKey hand:")
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_explicit_notes_override_template(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let template = create_template(&ctx, &token, "Villain read:\nKey hand:").await;
+
+    let mut body = default_session_json();
+    body["template_id"] = json!(template.id);
+    body["notes"] = json!("Ran well");
+    let session = create_session(&ctx, &token, body).await;
+
+    assert_eq!(session.session.notes.as_deref(), Some("Ran well"));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_with_other_users_template_returns_400(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let other_token = register_and_get_token(&ctx, "other@example.com").await;
+    let template = create_template(&ctx, &other_token, "Not mine").await;
+
+    let mut body = default_session_json();
+    body["template_id"] = json!(template.id);
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Result Confirmation
+// =============================================================================
+
+fn unconfirmed_session_json() -> serde_json::Value {
+    let mut body = default_session_json();
+    body["cash_out_amount"] = json!(400.0);
+    body["confirmed"] = json!(false);
+    body
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_confirmed_by_default(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let session = create_session(&ctx, &token, default_session_json()).await;
+
+    assert!(session.session.confirmed);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_list_sessions_filters_by_confirmed(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, default_session_json()).await;
+    let quick = create_session(&ctx, &token, unconfirmed_session_json()).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("confirmed", "false")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].session.id, quick.session.id);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_list_sessions_empty_filter_returns_empty_list_by_default(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("confirmed", "false")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert!(sessions.is_empty());
+}
+
+#[tokio::test]
+async fn test_list_sessions_empty_filter_returns_404_when_configured() {
+    let mut config = test_config();
+    config.empty_filter_not_found = true;
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("confirmed", "false")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    // A matching filter and an unfiltered empty list are unaffected
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("confirmed", "true")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+
+    let other_token = register_and_get_token(&ctx, "other@example.com").await;
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", other_token))
+        .await;
+    response.assert_status_ok();
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert!(sessions.is_empty());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_confirmed_only_excludes_unconfirmed(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, default_session_json()).await;
+    create_session(&ctx, &token, unconfirmed_session_json()).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    let stats: serde_json::Value = response.json();
+    assert_eq!(stats["session_count"], 2);
+    assert_eq!(stats["total_profit"], "350.00");
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_query_param("confirmed_only", "true")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    let stats: serde_json::Value = response.json();
+    assert_eq!(stats["session_count"], 1);
+    assert_eq!(stats["total_profit"], "50.00");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_confirm_session(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let quick = create_session(&ctx, &token, unconfirmed_session_json()).await;
+    assert!(!quick.session.confirmed);
+
+    let response = ctx
+        .server
+        .post(&format!("/api/sessions/{}/confirm", quick.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let session: SessionWithProfit = response.json();
+    assert!(session.session.confirmed);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_confirm_session_wrong_user_returns_404(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let other_token = register_and_get_token(&ctx, "other@example.com").await;
+    let quick = create_session(&ctx, &token, unconfirmed_session_json()).await;
+
+    let response = ctx
+        .server
+        .post(&format!("/api/sessions/{}/confirm", quick.session.id))
+        .add_header("Authorization", format!("Bearer {}", other_token))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+// =============================================================================
+// Session Id Validation
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_malformed_session_id_returns_friendly_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let responses = [
+        ctx.server
+            .get("/api/sessions/not-a-uuid")
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await,
+        ctx.server
+            .delete("/api/sessions/not-a-uuid")
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await,
+        ctx.server
+            .post("/api/sessions/not-a-uuid/confirm")
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await,
+    ];
+
+    for response in responses {
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error"], "invalid session id");
+    }
+}
+
+// =============================================================================
+// Profit Preview
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_preview_profit_matches_created_session(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions/preview-profit")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&default_session_json())
+        .await;
+    response.assert_status_ok();
+    let preview: ProfitPreview = response.json();
+
+    let session = create_session(&ctx, &token, default_session_json()).await;
+    assert_eq!(preview.profit, session.profit);
+    assert_eq!(preview.roi, Some(0.5));
+    assert_eq!(preview.profit_per_hour, 25.0);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_preview_profit_uses_default_buy_in_like_create(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    set_default_buy_in(&ctx, &token, 50.0).await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions/preview-profit")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&session_json_without_buy_in())
+        .await;
+    response.assert_status_ok();
+    let preview: ProfitPreview = response.json();
+
+    let session = create_session(&ctx, &token, session_json_without_buy_in()).await;
+    assert_eq!(session.profit, BigDecimal::from(100));
+    assert_eq!(preview.profit, session.profit);
+    assert_eq!(preview.roi, Some(2.0));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_preview_profit_does_not_save(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    ctx.server
+        .post("/api/sessions/preview-profit")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&default_session_json())
+        .await
+        .assert_status_ok();
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    let sessions: Vec<SessionWithProfit> = response.json();
+    assert!(sessions.is_empty());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_preview_profit_validates_like_create(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let mut body = default_session_json();
+    body["session_date"] = json!("15/01/2024");
+    let response = ctx
+        .server
+        .post("/api/sessions/preview-profit")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+// Buy-in 100 kept from the existing session, over 120 minutes
+#[case::standard("standard", 150, 1.0, 75.0)]
+#[case::exclude_rebuy("exclude_rebuy", 200, 2.0, 100.0)]
+#[tokio::test]
+async fn test_preview_update_matches_applied_update(
+    #[future] http_ctx: HttpTestContext,
+    #[case] convention: &str,
+    #[case] profit: i64,
+    #[case] roi: f64,
+    #[case] profit_per_hour: f64,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    ctx.server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "profit_convention": convention }))
+        .await
+        .assert_status_ok();
+    let created = create_session(&ctx, &token, default_session_json()).await;
+    let patch = json!({ "rebuy_amount": 50.0, "cash_out_amount": 300.0 });
+
+    let response = ctx
+        .server
+        .post(&format!(
+            "/api/sessions/{}/preview-update",
+            created.session.id
+        ))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&patch)
+        .await;
+    response.assert_status_ok();
+    let preview: ProfitPreview = response.json();
+
+    // Nothing was written
+    let response = ctx
+        .server
+        .get(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    assert_eq!(response.json::<SessionWithProfit>().profit, created.profit);
+
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&patch)
+        .await;
+    response.assert_status_ok();
+    let updated: SessionWithProfit = response.json();
+
+    assert_eq!(preview.profit, updated.profit);
+    assert_eq!(preview.profit, BigDecimal::from(profit));
+    assert_eq!(preview.roi, Some(roi));
+    assert_eq!(preview.profit_per_hour, profit_per_hour);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_preview_update_of_other_users_session_returns_404(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let owner_token = register_and_get_token(&ctx, "owner@example.com").await;
+    let other_token = register_and_get_token(&ctx, "other@example.com").await;
+    let created = create_session(&ctx, &owner_token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .post(&format!(
+            "/api/sessions/{}/preview-update",
+            created.session.id
+        ))
+        .add_header("Authorization", format!("Bearer {}", other_token))
+        .json(&json!({ "cash_out_amount": 300.0 }))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+// =============================================================================
+// camelCase Responses
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_responses_use_snake_case_by_default(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    let sessions: serde_json::Value = response.json();
+    assert!(sessions[0].get("buy_in_amount").is_some());
+    assert!(sessions[0].get("buyInAmount").is_none());
+}
+
+#[tokio::test]
+async fn test_camel_case_json_renames_response_keys() {
+    let mut config = test_config();
+    config.camel_case_json = true;
+    let ctx = HttpTestContext::with_config(config).await;
+    // Not the register/create helpers: they parse snake_case responses
+    let response = ctx
+        .server
+        .post("/api/auth/register")
+        .json(&json!({
+            "email": "test@example.com",
+            "username": "test",
+            "password": "password123"
+        }))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert!(body["user"].get("cookieConsent").is_some());
+    let token = body["token"].as_str().unwrap().to_string();
+
+    ctx.server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&default_session_json())
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    let sessions: serde_json::Value = response.json();
+    assert!(sessions[0].get("buyInAmount").is_some());
+    assert!(sessions[0].get("buy_in_amount").is_none());
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    let stats: serde_json::Value = response.json();
+    assert_eq!(stats["sessionCount"], 1);
+}
+
+// =============================================================================
+// Delete by Date Range
+// =============================================================================
+
+async fn listed_session_dates(ctx: &HttpTestContext, token: &str) -> Vec<String> {
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("order", "asc")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    response
+        .json::<Vec<SessionWithProfit>>()
+        .iter()
+        .map(|s| s.session.session_date.to_string())
+        .collect()
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_delete_range_removes_only_in_range_sessions(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let other_token = register_and_get_token(&ctx, "other@example.com").await;
+    for date in [
+        "2024-01-09",
+        "2024-01-10",
+        "2024-01-15",
+        "2024-01-20",
+        "2024-01-21",
+    ] {
+        create_session(&ctx, &token, session_json_on(date)).await;
+    }
+    create_session(&ctx, &other_token, session_json_on("2024-01-15")).await;
+
+    let response = ctx
+        .server
+        .delete("/api/sessions")
+        .add_query_param("start_date", "2024-01-10")
+        .add_query_param("end_date", "2024-01-20")
+        .add_query_param("confirm", "delete")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["deleted"], 3);
+    assert_eq!(
+        listed_session_dates(&ctx, &token).await,
+        vec!["2024-01-09", "2024-01-21"]
+    );
+    // Other users' sessions in the range are untouched
+    assert_eq!(
+        listed_session_dates(&ctx, &other_token).await,
+        vec!["2024-01-15"]
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_delete_range_requires_confirm(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, session_json_on("2024-01-15")).await;
+
+    for confirm in [None, Some("yes")] {
+        let mut request = ctx
+            .server
+            .delete("/api/sessions")
+            .add_query_param("start_date", "2024-01-01")
+            .add_query_param("end_date", "2024-01-31")
+            .add_header("Authorization", format!("Bearer {}", token));
+        if let Some(confirm) = confirm {
+            request = request.add_query_param("confirm", confirm);
+        }
+
+        let response = request.await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert!(body["error"].as_str().unwrap().contains("confirm=delete"));
+    }
+
+    assert_eq!(listed_session_dates(&ctx, &token).await, vec!["2024-01-15"]);
+}
+
+#[rstest]
+#[case("2024-01-31", "2024-01-01")]
+#[case("2024-13-01", "2024-01-31")]
+#[case("2024-01-01", "jan 31")]
+#[tokio::test]
+async fn test_delete_range_rejects_invalid_dates(
+    #[future] http_ctx: HttpTestContext,
+    #[case] start_date: &str,
+    #[case] end_date: &str,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, session_json_on("2024-01-15")).await;
+
+    let response = ctx
+        .server
+        .delete("/api/sessions")
+        .add_query_param("start_date", start_date)
+        .add_query_param("end_date", end_date)
+        .add_query_param("confirm", "delete")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(listed_session_dates(&ctx, &token).await, vec!["2024-01-15"]);
+}
+
+// =============================================================================
+// Bulk Update
+// =============================================================================
+
+async fn session_category(ctx: &HttpTestContext, token: &str, id: uuid::Uuid) -> Option<String> {
+    let response = ctx
+        .server
+        .get(&format!("/api/sessions/{}", id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    response.json::<SessionWithProfit>().session.category
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_bulk_update_sets_category_on_listed_owned_sessions(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let other_token = register_and_get_token(&ctx, "other@example.com").await;
+
+    let mut body = default_session_json();
+    body["category"] = json!("Belagio");
+    let first = create_session(&ctx, &token, body.clone()).await.session.id;
+    let second = create_session(&ctx, &token, body.clone()).await.session.id;
+    let unlisted = create_session(&ctx, &token, body.clone()).await.session.id;
+    let not_owned = create_session(&ctx, &other_token, body).await.session.id;
+
+    let response = ctx
+        .server
+        .post("/api/sessions/bulk-update")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "ids": [first, second, not_owned],
+            "set": { "category": "Bellagio" }
+        }))
+        .await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["updated"], 2);
+
+    let bellagio = Some("bellagio".to_string());
+    let belagio = Some("belagio".to_string());
+    assert_eq!(session_category(&ctx, &token, first).await, bellagio);
+    assert_eq!(session_category(&ctx, &token, second).await, bellagio);
+    assert_eq!(session_category(&ctx, &token, unlisted).await, belagio);
+    assert_eq!(
+        session_category(&ctx, &other_token, not_owned).await,
+        belagio
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_bulk_update_sets_location_and_game_type(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let mut body = default_session_json();
+    body["location"] = json!("Belagio");
+    body["category"] = json!("live");
+    let first = create_session(&ctx, &token, body.clone()).await.session.id;
+    let second = create_session(&ctx, &token, body.clone()).await.session.id;
+    let unlisted = create_session(&ctx, &token, body).await.session.id;
+
+    let response = ctx
+        .server
+        .post("/api/sessions/bulk-update")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "ids": [first, second],
+            "set": { "location": "Bellagio", "game_type": "tournament" }
+        }))
+        .await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["updated"], 2);
+
+    for (id, location, game_type) in [
+        (first, "Bellagio", "tournament"),
+        (second, "Bellagio", "tournament"),
+        (unlisted, "Belagio", "cash"),
+    ] {
+        let session = ctx
+            .server
+            .get(&format!("/api/sessions/{}", id))
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await
+            .json::<SessionWithProfit>()
+            .session;
+        assert_eq!(session.location.as_deref(), Some(location));
+        assert_eq!(session.game_type, game_type);
+        // Fields not named in `set` are left alone
+        assert_eq!(session.category.as_deref(), Some("live"));
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_bulk_update_rejects_amounts(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let created = create_session(&ctx, &token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions/bulk-update")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "ids": [created.session.id],
+            "set": { "cash_out_amount": 0 }
+        }))
+        .await;
+
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    let response = ctx
+        .server
+        .get(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    assert_eq!(
+        response.json::<SessionWithProfit>().profit,
+        BigDecimal::from(50)
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_bulk_update_requires_ids_and_fields(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let created = create_session(&ctx, &token, default_session_json()).await;
+
+    for body in [
+        json!({ "ids": [], "set": { "category": "live" } }),
+        json!({ "ids": [created.session.id], "set": {} }),
+    ] {
+        let response = ctx
+            .server
+            .post("/api/sessions/bulk-update")
+            .add_header("Authorization", format!("Bearer {}", token))
+            .json(&body)
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_bulk_update_caps_ids_at_max_list_page_size() {
+    let ctx = capped_ctx(2).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        ids.push(
+            create_session(&ctx, &token, default_session_json())
+                .await
+                .session
+                .id,
+        );
+    }
+
+    let response = ctx
+        .server
+        .post("/api/sessions/bulk-update")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "ids": ids, "set": { "category": "live" } }))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "At most 2 sessions can be updated at once");
+    for id in &ids {
+        assert_eq!(session_category(&ctx, &token, *id).await, None);
+    }
+
+    let response = ctx
+        .server
+        .post("/api/sessions/bulk-update")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "ids": &ids[..2], "set": { "category": "live" } }))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["updated"], 2);
+}
+
+// =============================================================================
+// Custom Date Range
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_list_sessions_by_date_range(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    for date in ["2023-12-31", "2024-01-01", "2024-06-30", "2025-01-01"] {
+        create_session(&ctx, &token, session_json_on(date)).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("start_date", "2024-01-01")
+        .add_query_param("end_date", "2024-06-30")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let mut dates: Vec<String> = response
+        .json::<Vec<SessionWithProfit>>()
+        .into_iter()
+        .map(|s| s.session.session_date.to_string())
+        .collect();
+    dates.sort();
+    assert_eq!(dates, vec!["2024-01-01", "2024-06-30"]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_export_date_range_overrides_time_range(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    create_session(&ctx, &token, session_json_on("2024-01-15")).await;
+    create_session(&ctx, &token, session_json_on("2024-03-15")).await;
+
+    // A 7-day window would exclude both sessions
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("time_range", "7days")
+        .add_query_param("start_date", "2024-01-01")
+        .add_query_param("end_date", "2024-01-31")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    assert!(
+        response
+            .header("content-disposition")
+            .to_str()
+            .unwrap()
+            .contains("poker-sessions-2024-01-01_to_2024-01-31.csv")
+    );
+    let csv = response.text();
+    assert_eq!(csv.lines().count(), 2);
+    assert!(csv.contains("2024-01-15"));
+}
+
+#[rstest]
+#[case("/api/sessions", "2024-13-01", "2024-12-31")]
+#[case("/api/sessions", "2024-02-01", "2024-01-01")]
+#[case("/api/sessions/export", "2024-01-01", "not-a-date")]
+#[case("/api/sessions/export", "2024-02-01", "2024-01-01")]
+#[tokio::test]
+async fn test_invalid_date_range_returns_400(
+    #[future] http_ctx: HttpTestContext,
+    #[case] path: &str,
+    #[case] start_date: &str,
+    #[case] end_date: &str,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let response = ctx
+        .server
+        .get(path)
+        .add_query_param("start_date", start_date)
+        .add_query_param("end_date", end_date)
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Game Type
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_defaults_to_cash_game(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    let created = create_session(&ctx, &token, default_session_json()).await;
+
+    assert_eq!(created.session.game_type, "cash");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_update_session_game_type(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["game_type"] = json!("tournament");
+    let created = create_session(&ctx, &token, body).await;
+    assert_eq!(created.session.game_type, "tournament");
+
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"game_type": "cash"}))
+        .await;
+
+    response.assert_status_ok();
+    let updated: SessionWithProfit = response.json();
+    assert_eq!(updated.session.game_type, "cash");
+
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("columns", "date,game_type")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    let csv = response.text();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines, vec!["Date,Game Type", "2024-01-15,cash"]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_unknown_game_type_is_rejected(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["game_type"] = json!("sit_and_go");
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+// =============================================================================
+// Stakes
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_update_without_stakes_keeps_existing(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["stakes"] = json!("1/2");
+    let created = create_session(&ctx, &token, body).await;
+    assert_eq!(created.session.stakes.as_deref(), Some("1/2"));
+
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"duration_minutes": 90}))
+        .await;
+    response.assert_status_ok();
+    let updated: SessionWithProfit = response.json();
+    assert_eq!(updated.session.stakes.as_deref(), Some("1/2"));
+
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"stakes": "2/5"}))
+        .await;
+    response.assert_status_ok();
+    let updated: SessionWithProfit = response.json();
+    assert_eq!(updated.session.stakes.as_deref(), Some("2/5"));
+
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("columns", "date,stakes")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    let csv = response.text();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines, vec!["Date,Stakes", "2024-01-15,2/5"]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_stakes_too_long_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["stakes"] = json!("x".repeat(21));
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Location
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_list_sessions_filtered_by_location(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    for location in ["Bellagio", "Aria", "100% Home Game"] {
+        let mut body = default_session_json();
+        body["location"] = json!(location);
+        create_session(&ctx, &token, body).await;
+    }
+    create_session(&ctx, &token, default_session_json()).await;
+
+    for (filter, expected) in [("bella", vec!["Bellagio"]), ("%", vec!["100% Home Game"])] {
+        let response = ctx
+            .server
+            .get("/api/sessions")
+            .add_query_param("location", filter)
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await;
+
+        response.assert_status_ok();
+        let locations: Vec<String> = response
+            .json::<Vec<SessionWithProfit>>()
+            .into_iter()
+            .filter_map(|s| s.session.location)
+            .collect();
+        assert_eq!(locations, expected);
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_list_sessions_filtered_by_game_type_and_stakes(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    for (date, game_type, stakes) in [
+        ("2024-03-10", "cash", "2/5"),
+        ("2024-03-20", "cash", "2/5"),
+        ("2024-03-15", "cash", "1/2"),
+        ("2024-03-12", "tournament", "2/5"),
+        ("2024-04-02", "cash", "2/5"),
+    ] {
+        let mut body = session_json_on(date);
+        body["game_type"] = json!(game_type);
+        body["stakes"] = json!(stakes);
+        create_session(&ctx, &token, body).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_query_param("game_type", "cash")
+        .add_query_param("stakes", "2/5")
+        .add_query_param("start_date", "2024-03-01")
+        .add_query_param("end_date", "2024-03-31")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let sessions = response.json::<Vec<SessionWithProfit>>();
+    assert_eq!(
+        session_dates(&sessions),
+        vec!["2024-03-20".to_string(), "2024-03-10".to_string()]
+    );
+    assert!(
+        sessions
+            .iter()
+            .all(|s| s.session.game_type == "cash" && s.session.stakes.as_deref() == Some("2/5"))
+    );
+
+    ctx.server
+        .get("/api/sessions")
+        .add_query_param("game_type", "omaha")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status_bad_request();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_update_session_location(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let created = create_session(&ctx, &token, default_session_json()).await;
+    assert!(created.session.location.is_none());
+
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"location": "Aria"}))
+        .await;
+    response.assert_status_ok();
+    let updated: SessionWithProfit = response.json();
+    assert_eq!(updated.session.location.as_deref(), Some("Aria"));
+
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("columns", "date,location")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    let csv = response.text();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines, vec!["Date,Location", "2024-01-15,Aria"]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_location_too_long_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["location"] = json!("x".repeat(101));
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// GeoJSON Export
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_export_geojson_omits_sessions_without_coordinates(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["location"] = json!("Bellagio");
+    body["latitude"] = json!(36.1126);
+    body["longitude"] = json!(-115.1767);
+    let located = create_session(&ctx, &token, body).await;
+    let mut body = default_session_json();
+    body["latitude"] = json!(36.1126);
+    create_session(&ctx, &token, body).await;
+    create_session(&ctx, &token, default_session_json()).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("format", "geojson")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "application/geo+json");
+    let collection: serde_json::Value = response.json();
+    assert_eq!(collection["type"], "FeatureCollection");
+    let features = collection["features"].as_array().unwrap();
+    assert_eq!(features.len(), 1);
+    assert_eq!(features[0]["type"], "Feature");
+    assert_eq!(features[0]["geometry"]["type"], "Point");
+    assert_eq!(
+        features[0]["geometry"]["coordinates"],
+        json!([-115.1767, 36.1126])
+    );
+    let properties = &features[0]["properties"];
+    assert_eq!(properties["id"], json!(located.session.id));
+    assert_eq!(properties["date"], "2024-01-15");
+    assert_eq!(properties["profit"], json!(located.profit));
+    assert_eq!(properties["location"], "Bellagio");
+}
+
+#[rstest]
+#[case("latitude", 90.5)]
+#[case("latitude", -91.0)]
+#[case("longitude", 180.5)]
+#[case("longitude", -181.0)]
+#[tokio::test]
+async fn test_create_session_coordinate_out_of_range_returns_400(
+    #[future] http_ctx: HttpTestContext,
+    #[case] field: &str,
+    #[case] value: f64,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body[field] = json!(value);
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Session Result
+// =============================================================================
+
+#[tokio::test]
+async fn test_session_result_uses_break_even_epsilon() {
+    let mut config = test_config();
+    config.break_even_epsilon = 1.0;
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+
+    for (cash_out, expected) in [
+        (150.0, "win"),
+        (40.0, "loss"),
+        (100.5, "break_even"),
+        (99.0, "break_even"),
+    ] {
+        let mut body = default_session_json();
+        body["cash_out_amount"] = json!(cash_out);
+        let response = ctx
+            .server
+            .post("/api/sessions")
+            .add_header("Authorization", format!("Bearer {}", token))
+            .json(&body)
+            .await;
+
+        response.assert_status(StatusCode::CREATED);
+        let created: serde_json::Value = response.json();
+        assert_eq!(created["result"], expected, "cash out {}", cash_out);
+    }
+}
+
+// =============================================================================
+// Strict JSON
+// =============================================================================
+
+#[tokio::test]
+async fn test_strict_json_rejects_misspelled_field() {
+    let mut config = test_config();
+    config.strict_json = true;
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["byu_in_amount"] = body["buy_in_amount"].take();
+    body.as_object_mut().unwrap().remove("buy_in_amount");
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let error: serde_json::Value = response.json();
+    assert_eq!(error["error"], "Unknown fields: byu_in_amount");
+    assert_eq!(error["unknown_fields"], json!(["byu_in_amount"]));
+
+    // Known fields still pass
+    create_session(&ctx, &token, default_session_json()).await;
+}
+
+#[tokio::test]
+async fn test_strict_json_oversized_duration_returns_400() {
+    let mut config = test_config();
+    config.strict_json = true;
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["duration_minutes"] = json!(u64::MAX);
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let error: serde_json::Value = response.json();
+    assert_eq!(error["error"], "duration out of range");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_unknown_fields_ignored_by_default(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "test@example.com").await;
+    let mut body = default_session_json();
+    body["unexpected"] = json!(true);
+
+    create_session(&ctx, &token, body).await;
 }
@@ -0,0 +1,1326 @@
+mod common;
+mod http_common;
+
+use axum::http::StatusCode;
+use bigdecimal::BigDecimal;
+use common::test_config;
+use http_common::{
+    HttpTestContext, create_session, http_ctx, register_and_get_token, session_json_on,
+};
+use poker_tracker::models::{
+    BankrollPoint, BuyInBucket, CachedSessionStats, CategoryStats, CumulativeStats,
+    DurationCorrelation, GameTypeDashboard, HourBucket, KellyStake, RankingEntry, Rankings,
+    RiskOfRuin, RollingStats, SessionStats, WeekdayWeekendStats,
+};
+use rstest::rstest;
+use serde_json::json;
+
+const TOLERANCE: f64 = 1e-6;
+
+/// Create sessions with the given profits (buy-in of 100 each) on consecutive days
+async fn create_sessions_with_profits(ctx: &HttpTestContext, token: &str, profits: &[f64]) {
+    for (i, profit) in profits.iter().enumerate() {
+        let mut body = session_json_on(&format!("2024-01-{:02}", i + 1));
+        body["buy_in_amount"] = json!(100.0);
+        body["cash_out_amount"] = json!(100.0 + profit);
+        create_session(ctx, token, body).await;
+    }
+}
+
+// =============================================================================
+// Session Statistics
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_empty(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let stats: SessionStats = response.json();
+    assert_eq!(stats.session_count, 0);
+    assert!(stats.average_profit.is_none());
+    assert!(stats.result_variance.is_none());
+    assert!(stats.result_std_dev.is_none());
+    assert!(stats.overall_profit_per_hour.is_none());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_variance_null_for_single_session(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_sessions_with_profits(&ctx, &token, &[50.0]).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["session_count"], 1);
+    assert!(body["result_variance"].is_null());
+    assert!(body["result_std_dev"].is_null());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_variance_matches_hand_computation(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_sessions_with_profits(&ctx, &token, &[100.0, -50.0, 25.0, 75.0, -100.0]).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let stats: SessionStats = response.json();
+
+    // Mean is 10; squared deviations 8100 + 3600 + 225 + 4225 + 12100 = 28250
+    assert_eq!(stats.session_count, 5);
+    assert_eq!(stats.total_profit, BigDecimal::from(50));
+    assert_eq!(stats.average_profit, Some(BigDecimal::from(10)));
+    assert!((stats.result_variance.unwrap() - 28250.0 / 4.0).abs() < TOLERANCE);
+    // sqrt(7062.5) = 84.0387..., rounded to cents
+    assert_eq!(stats.result_std_dev, Some("84.04".parse().unwrap()));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_max_drawdown(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    // Cumulative: 100, 300 (peak, Jan 2), 150, 50 (trough, Jan 4), 250, 400, 300
+    create_sessions_with_profits(
+        &ctx,
+        &token,
+        &[100.0, 200.0, -150.0, -100.0, 200.0, 150.0, -100.0],
+    )
+    .await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let stats: SessionStats = response.json();
+    let drawdown = stats.max_drawdown.unwrap();
+    assert_eq!(drawdown.amount, BigDecimal::from(250));
+    assert_eq!(drawdown.peak_date.to_string(), "2024-01-02");
+    assert_eq!(drawdown.trough_date.to_string(), "2024-01-04");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_max_drawdown_null_for_single_session(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_sessions_with_profits(&ctx, &token, &[-50.0]).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert!(body["max_drawdown"].is_null());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_overall_profit_per_hour_is_pooled(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+
+    let mut short_win = session_json_on("2024-01-01");
+    short_win["duration_minutes"] = json!(60);
+    short_win["cash_out_amount"] = json!(200.0);
+    create_session(&ctx, &token, short_win).await;
+
+    let mut long_break_even = session_json_on("2024-01-02");
+    long_break_even["duration_minutes"] = json!(540);
+    long_break_even["cash_out_amount"] = json!(100.0);
+    create_session(&ctx, &token, long_break_even).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let stats: SessionStats = response.json();
+    // Pooled: 100 / 10h = 10/h; the mean of per-session rates would be 50/h
+    assert!((stats.total_hours - 10.0).abs() < TOLERANCE);
+    assert!((stats.overall_profit_per_hour.unwrap() - 10.0).abs() < TOLERANCE);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_respects_time_range(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_sessions_with_profits(&ctx, &token, &[100.0, -50.0]).await;
+
+    let today = chrono::Utc::now().date_naive().to_string();
+    create_session(&ctx, &token, session_json_on(&today)).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_query_param("time_range", "7days")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let stats: SessionStats = response.json();
+    assert_eq!(stats.session_count, 1);
+    assert!(stats.result_variance.is_none());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_invalid_time_range_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_query_param("time_range", "forever")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_requires_auth(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+
+    let response = ctx.server.get("/api/sessions/stats").await;
+
+    response.assert_status_unauthorized();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_cadence_per_week(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    // Jan 1 to Jan 29 spans exactly 4 weeks; each session is 2 hours
+    for date in ["2024-01-01", "2024-01-08", "2024-01-15", "2024-01-29"] {
+        create_session(&ctx, &token, session_json_on(date)).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let stats: SessionStats = response.json();
+    assert!((stats.avg_sessions_per_week.unwrap() - 1.0).abs() < TOLERANCE);
+    assert!((stats.avg_hours_per_week.unwrap() - 2.0).abs() < TOLERANCE);
+}
+
+// =============================================================================
+// Statistics by Category
+// =============================================================================
+
+async fn create_categorized_session(
+    ctx: &HttpTestContext,
+    token: &str,
+    category: Option<&str>,
+    profit: f64,
+) {
+    let mut body = session_json_on("2024-01-15");
+    body["buy_in_amount"] = json!(100.0);
+    body["cash_out_amount"] = json!(100.0 + profit);
+    if let Some(category) = category {
+        body["category"] = json!(category);
+    }
+    create_session(ctx, token, body).await;
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_by_category_groups_normalized_categories(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_categorized_session(&ctx, &token, Some("Online"), 100.0).await;
+    create_categorized_session(&ctx, &token, Some("  online "), -40.0).await;
+    create_categorized_session(&ctx, &token, Some("LIVE"), 25.0).await;
+    create_categorized_session(&ctx, &token, None, 10.0).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/by-category")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let groups: Vec<CategoryStats> = response.json();
+    let categories: Vec<Option<&str>> = groups.iter().map(|g| g.category.as_deref()).collect();
+    assert_eq!(categories, vec![Some("live"), Some("online"), None]);
+
+    assert_eq!(groups[0].stats.session_count, 1);
+    assert_eq!(groups[0].stats.total_profit, BigDecimal::from(25));
+    assert_eq!(groups[1].stats.session_count, 2);
+    assert_eq!(groups[1].stats.total_profit, BigDecimal::from(60));
+    assert_eq!(groups[2].stats.session_count, 1);
+    assert_eq!(groups[2].stats.total_profit, BigDecimal::from(10));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_by_category_empty(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/by-category")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let groups: Vec<CategoryStats> = response.json();
+    assert!(groups.is_empty());
+}
+
+// =============================================================================
+// Statistics by Hour of Day
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_by_hour_buckets_by_start_time(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    for (start_time, profit) in [
+        (Some("09:15"), 20.0),
+        (Some("21:00"), 100.0),
+        (Some("21:59:59"), -40.0),
+        (None, 500.0),
+    ] {
+        let mut body = session_json_on("2024-01-15");
+        body["buy_in_amount"] = json!(100.0);
+        body["cash_out_amount"] = json!(100.0 + profit);
+        if let Some(start_time) = start_time {
+            body["start_time"] = json!(start_time);
+        }
+        create_session(&ctx, &token, body).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/by-hour")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let buckets: Vec<HourBucket> = response.json();
+    assert_eq!(buckets.len(), 24);
+    assert_eq!(buckets[9].session_count, 1);
+    assert_eq!(buckets[9].total_profit, BigDecimal::from(20));
+    assert_eq!(buckets[21].session_count, 2);
+    assert_eq!(buckets[21].total_profit, BigDecimal::from(60));
+    // The session without a start time is not counted anywhere
+    let counted: u64 = buckets.iter().map(|b| b.session_count).sum();
+    assert_eq!(counted, 3);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_invalid_start_time_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    let mut body = session_json_on("2024-01-15");
+    body["start_time"] = json!("9pm");
+
+    let response = ctx
+        .server
+        .post("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Game Type Dashboard
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_dashboard_splits_cash_hourly_from_tournament_roi(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    // Cash: +50 over 2 hours
+    create_session(&ctx, &token, session_json_on("2024-01-01")).await;
+    // Tournaments: 200 invested, 300 back
+    for cash_out in [0.0, 300.0] {
+        let mut body = session_json_on("2024-01-02");
+        body["game_type"] = json!("tournament");
+        body["duration_minutes"] = json!(300);
+        body["cash_out_amount"] = json!(cash_out);
+        create_session(&ctx, &token, body).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/dashboard")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let dashboard: GameTypeDashboard = response.json();
+    assert_eq!(dashboard.cash.session_count, 1);
+    assert_eq!(dashboard.cash.total_profit, BigDecimal::from(50));
+    assert_eq!(dashboard.cash.profit_per_hour, Some(BigDecimal::from(25)));
+    assert_eq!(dashboard.tournament.tournament_count, 2);
+    assert_eq!(dashboard.tournament.total_invested, BigDecimal::from(200));
+    assert_eq!(dashboard.tournament.total_profit, BigDecimal::from(100));
+    assert_eq!(dashboard.tournament.roi, Some("0.5".parse().unwrap()));
+    assert_eq!(dashboard.tournament.itm_count, 1);
+    assert_eq!(dashboard.tournament.itm_rate, Some("0.5".parse().unwrap()));
+}
+
+// =============================================================================
+// Rolling Windows
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_rolling_stats_session_100_days_ago(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    let today = chrono::Utc::now().date_naive();
+    for (days_ago, profit) in [(5, 20.0), (100, 50.0)] {
+        let date = today - chrono::Duration::days(days_ago);
+        let mut body = session_json_on(&date.to_string());
+        body["buy_in_amount"] = json!(100.0);
+        body["cash_out_amount"] = json!(100.0 + profit);
+        create_session(&ctx, &token, body).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/rolling")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let rolling: RollingStats = response.json();
+    assert_eq!(rolling.last_30.session_count, 1);
+    assert_eq!(rolling.last_90.session_count, 1);
+    assert_eq!(rolling.last_90.total_profit, BigDecimal::from(20));
+    assert_eq!(rolling.last_365.session_count, 2);
+    assert_eq!(rolling.last_365.total_profit, BigDecimal::from(70));
+    assert_eq!(rolling.all_time.session_count, 2);
+    assert!((rolling.all_time.total_hours - 4.0).abs() < TOLERANCE);
+}
+
+// =============================================================================
+// Weekday vs Weekend
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_weekday_vs_weekend_split(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    // Friday, Saturday, Sunday, Monday; each session is 2 hours long
+    for (date, profit) in [
+        ("2024-01-12", 40.0),
+        ("2024-01-13", 100.0),
+        ("2024-01-14", -40.0),
+        ("2024-01-15", -10.0),
+    ] {
+        let mut body = session_json_on(date);
+        body["buy_in_amount"] = json!(100.0);
+        body["cash_out_amount"] = json!(100.0 + profit);
+        create_session(&ctx, &token, body).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/weekday-vs-weekend")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let stats: WeekdayWeekendStats = response.json();
+    assert_eq!(stats.weekday.session_count, 2);
+    assert_eq!(stats.weekday.total_profit, BigDecimal::from(30));
+    assert!((stats.weekday.total_hours - 4.0).abs() < TOLERANCE);
+    assert!((stats.weekday.profit_per_hour.unwrap() - 7.5).abs() < TOLERANCE);
+    assert_eq!(stats.weekend.session_count, 2);
+    assert_eq!(stats.weekend.total_profit, BigDecimal::from(60));
+    assert!((stats.weekend.profit_per_hour.unwrap() - 15.0).abs() < TOLERANCE);
+}
+
+// =============================================================================
+// Statistics by Buy-in Bucket
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_buy_in_buckets_group_by_range(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    for (buy_in, cash_out) in [(50.0, 80.0), (100.0, 50.0), (150.0, 250.0), (500.0, 400.0)] {
+        let mut body = session_json_on("2024-01-15");
+        body["buy_in_amount"] = json!(buy_in);
+        body["cash_out_amount"] = json!(cash_out);
+        create_session(&ctx, &token, body).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/by-buyin-bucket")
+        .add_query_param("size", "100")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let buckets: Vec<BuyInBucket> = response.json();
+    let ranges: Vec<(BigDecimal, BigDecimal, u64)> = buckets
+        .iter()
+        .map(|b| (b.bucket_min.clone(), b.bucket_max.clone(), b.session_count))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![
+            (BigDecimal::from(0), BigDecimal::from(100), 1),
+            (BigDecimal::from(100), BigDecimal::from(200), 2),
+            (BigDecimal::from(500), BigDecimal::from(600), 1),
+        ]
+    );
+    assert_eq!(buckets[0].total_profit, BigDecimal::from(30));
+    assert_eq!(buckets[1].total_profit, BigDecimal::from(50));
+    assert_eq!(buckets[2].total_profit, BigDecimal::from(-100));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_buy_in_buckets_invalid_size_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+
+    for size in ["0", "-50"] {
+        let response = ctx
+            .server
+            .get("/api/sessions/stats/by-buyin-bucket")
+            .add_query_param("size", size)
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+}
+
+// =============================================================================
+// Rankings
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_rankings_order_by_profit_per_hour_above_min_hours(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    // Each session is 2 hours long
+    create_categorized_session(&ctx, &token, Some("live"), 40.0).await;
+    create_categorized_session(&ctx, &token, Some("live"), 0.0).await;
+    create_categorized_session(&ctx, &token, Some("online"), 100.0).await;
+    create_categorized_session(&ctx, &token, Some("online"), 60.0).await;
+    create_categorized_session(&ctx, &token, Some("home"), 500.0).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/rankings")
+        .add_query_param("min_hours", "4")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let rankings: Rankings = response.json();
+    let names: Vec<Option<&str>> = rankings
+        .categories
+        .iter()
+        .map(|entry| entry.name.as_deref())
+        .collect();
+    assert_eq!(names, vec![Some("online"), Some("live")]);
+    assert_eq!(rankings.categories[0].session_count, 2);
+    assert!((rankings.categories[0].total_hours - 4.0).abs() < TOLERANCE);
+    assert!((rankings.categories[0].profit_per_hour - 40.0).abs() < TOLERANCE);
+    assert!((rankings.categories[1].profit_per_hour - 10.0).abs() < TOLERANCE);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_rankings_default_min_hours_excludes_small_samples(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_categorized_session(&ctx, &token, Some("live"), 40.0).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/rankings")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let rankings: Rankings = response.json();
+    assert!(rankings.categories.is_empty());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_rankings_by_location_game_type_and_stakes(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    // Each session is 2 hours long
+    for (location, stakes, game_type, profit) in [
+        ("Aria", "2/5", "cash", 100.0),
+        ("Aria", "2/5", "cash", 60.0),
+        ("Bellagio", "1/2", "cash", 20.0),
+        ("Bellagio", "1/2", "cash", 0.0),
+        ("Wynn", "2/5", "tournament", 500.0),
+    ] {
+        let mut body = session_json_on("2024-01-15");
+        body["buy_in_amount"] = json!(100.0);
+        body["cash_out_amount"] = json!(100.0 + profit);
+        body["location"] = json!(location);
+        body["stakes"] = json!(stakes);
+        body["game_type"] = json!(game_type);
+        create_session(&ctx, &token, body).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/rankings")
+        .add_query_param("min_hours", "4")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let rankings: Rankings = response.json();
+    let names = |entries: &[RankingEntry]| -> Vec<Option<String>> {
+        entries.iter().map(|entry| entry.name.clone()).collect()
+    };
+    // Wynn and tournaments have only 2 hours, below the threshold
+    assert_eq!(
+        names(&rankings.locations),
+        vec![Some("Aria".to_string()), Some("Bellagio".to_string())]
+    );
+    assert_eq!(rankings.locations[0].session_count, 2);
+    assert!((rankings.locations[0].profit_per_hour - 40.0).abs() < TOLERANCE);
+    assert_eq!(names(&rankings.game_types), vec![Some("cash".to_string())]);
+    assert_eq!(rankings.game_types[0].session_count, 4);
+    assert_eq!(
+        names(&rankings.stakes),
+        vec![Some("2/5".to_string()), Some("1/2".to_string())]
+    );
+    assert_eq!(rankings.stakes[0].session_count, 3);
+    assert!((rankings.stakes[0].profit_per_hour - 110.0).abs() < TOLERANCE);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_rankings_negative_min_hours_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/rankings")
+        .add_query_param("min_hours", "-1")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Risk of Ruin
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_risk_of_ruin_known_mean_and_variance(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "risk@example.com").await;
+    // Mean 10, every deviation is +/-100: sample variance 10 * 10000 / 9
+    let profits = [110.0, -90.0].repeat(5);
+    create_sessions_with_profits(&ctx, &token, &profits).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/risk")
+        .add_query_param("bankroll", "1000")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let risk: RiskOfRuin = response.json();
+    let variance = 100000.0 / 9.0;
+    assert_eq!(risk.session_count, 10);
+    assert_eq!(risk.bankroll, BigDecimal::from(1000));
+    assert_eq!(risk.mean_profit, BigDecimal::from(10));
+    // sqrt(11111.11...) = 105.4092..., rounded to cents
+    assert_eq!(risk.std_dev, "105.41".parse().unwrap());
+    assert!((risk.risk_of_ruin - (-2.0 * 1000.0 * 10.0 / variance).exp()).abs() < TOLERANCE);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_risk_of_ruin_requires_minimum_sessions(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "risk@example.com").await;
+    create_sessions_with_profits(&ctx, &token, &[110.0, -90.0, 50.0]).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/risk")
+        .add_query_param("bankroll", "1000")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+#[case("0")]
+#[case("-100")]
+#[case("lots")]
+#[tokio::test]
+async fn test_risk_of_ruin_invalid_bankroll_returns_400(
+    #[future] http_ctx: HttpTestContext,
+    #[case] bankroll: &str,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "risk@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/risk")
+        .add_query_param("bankroll", bankroll)
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Kelly Stake
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_kelly_known_mean_and_variance(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "kelly@example.com").await;
+    // Buy-in 100 each; mean 25, deviations +/-75: sample variance 10 * 5625 / 9 = 6250,
+    // so the Kelly fraction is 100 * 25 / 6250 = 0.4
+    let profits = [100.0, -50.0].repeat(5);
+    create_sessions_with_profits(&ctx, &token, &profits).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/kelly")
+        .add_query_param("bankroll", "1000")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let kelly: KellyStake = response.json();
+    assert_eq!(kelly.session_count, 10);
+    assert_eq!(kelly.mean_profit, BigDecimal::from(25));
+    assert_eq!(kelly.average_buy_in, BigDecimal::from(100));
+    assert!((kelly.kelly_fraction - 0.4).abs() < TOLERANCE);
+    assert!((kelly.half_kelly_fraction - 0.2).abs() < TOLERANCE);
+    assert_eq!(kelly.suggested_buy_in, BigDecimal::from(400));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_kelly_requires_minimum_sessions(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "kelly@example.com").await;
+    create_sessions_with_profits(&ctx, &token, &[100.0, -50.0, 25.0]).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/kelly")
+        .add_query_param("bankroll", "1000")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_kelly_requires_bankroll(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "kelly@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/kelly")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Bankroll Series
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_bankroll_series_aggregates_days_and_fills_gaps(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    for (date, profit) in [
+        ("2024-01-01", 50.0),
+        ("2024-01-01", -20.0),
+        ("2024-01-03", 100.0),
+    ] {
+        let mut body = session_json_on(date);
+        body["buy_in_amount"] = json!(100.0);
+        body["cash_out_amount"] = json!(100.0 + profit);
+        create_session(&ctx, &token, body).await;
+    }
+
+    let series_points = |series: Vec<BankrollPoint>| -> Vec<(String, BigDecimal)> {
+        series
+            .into_iter()
+            .map(|point| (point.date.to_string(), point.balance))
+            .collect()
+    };
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/bankroll-series")
+        .add_query_param("starting_bankroll", "1000")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        series_points(response.json()),
+        vec![
+            ("2024-01-01".to_string(), BigDecimal::from(1030)),
+            ("2024-01-03".to_string(), BigDecimal::from(1130)),
+        ]
+    );
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/bankroll-series")
+        .add_query_param("starting_bankroll", "1000")
+        .add_query_param("fill_gaps", "true")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        series_points(response.json()),
+        vec![
+            ("2024-01-01".to_string(), BigDecimal::from(1030)),
+            ("2024-01-02".to_string(), BigDecimal::from(1030)),
+            ("2024-01-03".to_string(), BigDecimal::from(1130)),
+        ]
+    );
+}
+
+// =============================================================================
+// Cumulative Profit
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_cumulative_excludes_sessions_after_as_of(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "cumulative@example.com").await;
+
+    // Each default session is 2 hours with a profit of 50
+    create_session(&ctx, &token, session_json_on("2024-06-15")).await;
+    create_session(&ctx, &token, session_json_on("2024-06-30")).await;
+    let mut later = session_json_on("2024-07-01");
+    later["cash_out_amount"] = json!(1000.0);
+    create_session(&ctx, &token, later).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/cumulative")
+        .add_query_param("as_of", "2024-06-30")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let cumulative: CumulativeStats = response.json();
+    assert_eq!(cumulative.as_of.to_string(), "2024-06-30");
+    assert_eq!(cumulative.session_count, 2);
+    assert_eq!(cumulative.total_profit, BigDecimal::from(100));
+    assert_eq!(cumulative.total_hours, BigDecimal::from(4));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_cumulative_confirmed_only_excludes_unconfirmed(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "cumulative@example.com").await;
+
+    create_session(&ctx, &token, session_json_on("2024-06-15")).await;
+    let mut unconfirmed = session_json_on("2024-06-20");
+    unconfirmed["cash_out_amount"] = json!(1000.0);
+    unconfirmed["confirmed"] = json!(false);
+    create_session(&ctx, &token, unconfirmed).await;
+
+    for (confirmed_only, session_count, total_profit) in [("false", 2, 950), ("true", 1, 50)] {
+        let response = ctx
+            .server
+            .get("/api/sessions/stats/cumulative")
+            .add_query_param("as_of", "2024-06-30")
+            .add_query_param("confirmed_only", confirmed_only)
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await;
+
+        response.assert_status_ok();
+        let cumulative: CumulativeStats = response.json();
+        assert_eq!(cumulative.session_count, session_count);
+        assert_eq!(cumulative.total_profit, BigDecimal::from(total_profit));
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_cumulative_invalid_date_returns_400(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "cumulative@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/cumulative")
+        .add_query_param("as_of", "2024-13-45")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Duration Correlation
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_duration_correlation_perfectly_correlated(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "correlation@example.com").await;
+
+    // Profit grows linearly with duration: 25 per hour
+    for (i, hours) in [1, 2, 3, 4].iter().enumerate() {
+        let mut body = session_json_on(&format!("2024-02-{:02}", i + 1));
+        body["duration_minutes"] = json!(hours * 60);
+        body["buy_in_amount"] = json!(100.0);
+        body["cash_out_amount"] = json!(100.0 + 25.0 * *hours as f64);
+        create_session(&ctx, &token, body).await;
+    }
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/duration-correlation")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let correlation: DurationCorrelation = response.json();
+    assert_eq!(correlation.sample_size, 4);
+    assert!((correlation.correlation.unwrap() - 1.0).abs() < TOLERANCE);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_duration_correlation_null_below_three_sessions(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "correlation@example.com").await;
+    create_sessions_with_profits(&ctx, &token, &[10.0, 20.0]).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats/duration-correlation")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["sample_size"], 2);
+    assert!(body["correlation"].is_null());
+}
+
+// =============================================================================
+// Profit Convention
+// =============================================================================
+
+/// One session with buy-in 100, rebuy 50 and cash-out 200
+async fn create_session_with_rebuy(ctx: &HttpTestContext, token: &str) {
+    let mut body = session_json_on("2024-01-15");
+    body["buy_in_amount"] = json!(100.0);
+    body["rebuy_amount"] = json!(50.0);
+    body["cash_out_amount"] = json!(200.0);
+    create_session(ctx, token, body).await;
+}
+
+async fn total_profit(ctx: &HttpTestContext, token: &str) -> BigDecimal {
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    response.json::<SessionStats>().total_profit
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_profit_convention_defaults_to_standard(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_session_with_rebuy(&ctx, &token).await;
+
+    assert_eq!(total_profit(&ctx, &token).await, BigDecimal::from(50));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_profit_convention_exclude_rebuy_applies_to_stats_and_export(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_session_with_rebuy(&ctx, &token).await;
+
+    let response = ctx
+        .server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "profit_convention": "exclude_rebuy" }))
+        .await;
+    response.assert_status_ok();
+    let user: serde_json::Value = response.json();
+    assert_eq!(user["profit_convention"], "exclude_rebuy");
+
+    assert_eq!(total_profit(&ctx, &token).await, BigDecimal::from(100));
+
+    let response = ctx
+        .server
+        .get("/api/sessions/export")
+        .add_query_param("columns", "rebuy,profit")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.text(), "Rebuy,Profit/Loss\n50.00,100.00\n");
+
+    // An explicit null restores the default
+    ctx.server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "profit_convention": null }))
+        .await
+        .assert_status_ok();
+    assert_eq!(total_profit(&ctx, &token).await, BigDecimal::from(50));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_profit_convention_exclude_rebuy_applies_to_session_responses(
+    #[future] http_ctx: HttpTestContext,
+) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    ctx.server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "profit_convention": "exclude_rebuy" }))
+        .await
+        .assert_status_ok();
+
+    let mut body = session_json_on("2024-01-15");
+    body["rebuy_amount"] = json!(50.0);
+    body["cash_out_amount"] = json!(200.0);
+    let created = create_session(&ctx, &token, body).await;
+    assert_eq!(created.profit, BigDecimal::from(100));
+
+    for path in [
+        format!("/api/sessions/{}", created.session.id),
+        "/api/sessions".to_string(),
+        "/api/sessions/recent".to_string(),
+    ] {
+        let response = ctx
+            .server
+            .get(&path)
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        let session = if body.is_array() { &body[0] } else { &body };
+        assert_eq!(session["profit"], "100.00", "{}", path);
+    }
+
+    let response = ctx
+        .server
+        .put(&format!("/api/sessions/{}", created.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "cash_out_amount": 250.0 }))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["profit"], "150.00");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_profit_convention_unknown_name_rejected(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+
+    let response = ctx
+        .server
+        .put("/api/auth/me")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "profit_convention": "gross" }))
+        .await;
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+// =============================================================================
+// Cache Headers
+// =============================================================================
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_not_cacheable_by_default(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    response.assert_status_ok();
+    assert!(response.maybe_header("cache-control").is_none());
+}
+
+#[tokio::test]
+async fn test_stats_cache_headers_when_configured() {
+    let mut config = test_config();
+    config.stats_cache_max_age_secs = 30;
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+
+    for path in ["/api/sessions/stats", "/api/sessions/stats/by-hour"] {
+        let response = ctx
+            .server
+            .get(path)
+            .add_header("Authorization", format!("Bearer {}", token))
+            .add_header("Origin", "https://app.example.com")
+            .await;
+
+        response.assert_status_ok();
+        assert_eq!(response.header("cache-control"), "private, max-age=30");
+        // The CORS layer's Vary is kept alongside the credential headers
+        let vary: Vec<String> = response
+            .iter_headers_by_name("vary")
+            .flat_map(|value| value.to_str().unwrap().split(','))
+            .map(|name| name.trim().to_ascii_lowercase())
+            .collect();
+        for name in ["origin", "authorization", "x-api-key"] {
+            assert!(vary.iter().any(|v| v == name), "missing Vary: {}", name);
+        }
+    }
+
+    // Only stats are cacheable, and only when read
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    assert!(response.maybe_header("cache-control").is_none());
+
+    let response = ctx
+        .server
+        .post("/api/sessions/stats/refresh")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    assert!(response.maybe_header("cache-control").is_none());
+}
+
+// =============================================================================
+// Stats Cache
+// =============================================================================
+
+/// Fetch all-time stats, asserting success
+async fn get_all_time_stats(ctx: &HttpTestContext, token: &str) -> CachedSessionStats {
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    response.json()
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_stats_computed_live_before_first_refresh(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_sessions_with_profits(&ctx, &token, &[50.0]).await;
+
+    let stats = get_all_time_stats(&ctx, &token).await;
+
+    assert_eq!(stats.stats.session_count, 1);
+    assert!(!stats.stale);
+    assert!(stats.refreshed_at.is_none());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_session_write_marks_stats_cache_stale(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_sessions_with_profits(&ctx, &token, &[50.0]).await;
+
+    let response = ctx
+        .server
+        .post("/api/sessions/stats/refresh")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    let refreshed: CachedSessionStats = response.json();
+    assert_eq!(refreshed.stats.session_count, 1);
+    assert!(!refreshed.stale);
+    assert!(refreshed.refreshed_at.is_some());
+
+    let cached = get_all_time_stats(&ctx, &token).await;
+    assert!(!cached.stale);
+    assert!(cached.refreshed_at.is_some());
+
+    // The cache keeps serving the old numbers, flagged as stale
+    create_session(&ctx, &token, session_json_on("2024-02-01")).await;
+    let cached = get_all_time_stats(&ctx, &token).await;
+    assert!(cached.stale);
+    assert_eq!(cached.stats.session_count, 1);
+
+    let response = ctx
+        .server
+        .post("/api/sessions/stats/refresh")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    let cached = get_all_time_stats(&ctx, &token).await;
+    assert!(!cached.stale);
+    assert_eq!(cached.stats.session_count, 2);
+
+    // Filtered stats never come from the cache
+    create_session(&ctx, &token, session_json_on("2024-02-02")).await;
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_query_param("confirmed_only", "true")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    let filtered: CachedSessionStats = response.json();
+    assert_eq!(filtered.stats.session_count, 3);
+    assert!(!filtered.stale);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_deleting_session_marks_stats_cache_stale(#[future] http_ctx: HttpTestContext) {
+    let ctx = http_ctx.await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    let first = create_session(&ctx, &token, session_json_on("2024-01-01")).await;
+    create_session(&ctx, &token, session_json_on("2024-01-02")).await;
+    ctx.server
+        .post("/api/sessions/stats/refresh")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .assert_status_ok();
+
+    // Not the most recently updated session, so only the count changes
+    ctx.server
+        .delete(&format!("/api/sessions/{}", first.session.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+
+    assert!(get_all_time_stats(&ctx, &token).await.stale);
+}
+
+// =============================================================================
+// Minimum Duration
+// =============================================================================
+
+#[tokio::test]
+async fn test_short_sessions_excluded_from_stats_only() {
+    let mut config = test_config();
+    config.min_duration_for_stats_minutes = 15;
+    let ctx = HttpTestContext::with_config(config).await;
+    let token = register_and_get_token(&ctx, "stats@example.com").await;
+    create_session(&ctx, &token, session_json_on("2024-01-01")).await;
+    let mut short = session_json_on("2024-01-02");
+    short["duration_minutes"] = json!(2);
+    short["cash_out_amount"] = json!(400.0);
+    create_session(&ctx, &token, short).await;
+
+    let response = ctx
+        .server
+        .get("/api/sessions/stats")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    let stats: SessionStats = response.json();
+    assert_eq!(stats.session_count, 1);
+    assert!((stats.total_hours - 2.0).abs() < TOLERANCE);
+
+    // Still listed
+    let response = ctx
+        .server
+        .get("/api/sessions")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    let sessions: Vec<serde_json::Value> = response.json();
+    assert_eq!(sessions.len(), 2);
+}
@@ -3,14 +3,14 @@ mod common;
 use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
 use common::{
     DirectConnectionTestDb, create_test_user_raw, default_session_request, get_session_by_id,
-    get_sessions_for_user,
+    get_sessions_for_user, test_config,
 };
 use diesel::{prelude::*, sql_types::Integer};
 use poker_tracker::handlers::poker_session::{
     self, CreateSessionError, DeleteSessionError, GetSessionError, UpdateSessionError,
 };
 use poker_tracker::models::{
-    CreatePokerSessionRequest, UpdatePokerSessionRequest, calculate_profit,
+    CreatePokerSessionRequest, UpdatePokerSessionRequest, calculate_profit_decimal,
 };
 use poker_tracker::utils::DbProvider;
 use rstest::rstest;
@@ -52,14 +52,15 @@ async fn test_create_session(#[future] test_db: DirectConnectionTestDb) {
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: Some(50.0),
-        cash_out_amount: 200.0,
+        cash_out_amount: Some(200.0),
         notes: Some("Test session".to_string()),
+        ..Default::default()
     };
 
     // Call the handler using the TestDb as the connection provider
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
@@ -79,13 +80,14 @@ async fn test_create_session_minimal(#[future] test_db: DirectConnectionTestDb)
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 60,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: None,
-        cash_out_amount: 150.0,
+        cash_out_amount: Some(150.0),
         notes: None,
+        ..Default::default()
     };
 
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
@@ -104,13 +106,14 @@ async fn test_create_session_with_rebuy(#[future] test_db: DirectConnectionTestD
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 180,
-        buy_in_amount: 200.0,
+        buy_in_amount: Some(200.0),
         rebuy_amount: Some(100.0),
-        cash_out_amount: 500.0,
+        cash_out_amount: Some(500.0),
         notes: None,
+        ..Default::default()
     };
 
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
@@ -126,13 +129,14 @@ async fn test_create_session_with_notes(#[future] test_db: DirectConnectionTestD
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: None,
-        cash_out_amount: 150.0,
+        cash_out_amount: Some(150.0),
         notes: Some("Great session at the casino!".to_string()),
+        ..Default::default()
     };
 
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
@@ -152,13 +156,14 @@ async fn test_create_session_invalid_date_format(#[future] test_db: DirectConnec
     let session_req = CreatePokerSessionRequest {
         session_date: "01/15/2024".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: None,
-        cash_out_amount: 150.0,
+        cash_out_amount: Some(150.0),
         notes: None,
+        ..Default::default()
     };
 
-    let result = poker_session::do_create_session(&db, user.id, session_req).await;
+    let result = poker_session::do_create_session(&db, &test_config(), user.id, session_req).await;
 
     assert!(matches!(
         result,
@@ -172,9 +177,10 @@ async fn test_create_session_generates_uuid(#[future] test_db: DirectConnectionT
     let db = test_db.await;
     let user = create_test_user_raw(&db, "test@test.com", "testuser");
 
-    let session = poker_session::do_create_session(&db, user.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let session =
+        poker_session::do_create_session(&db, &test_config(), user.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // Session should have a valid, non-nil UUID
     assert!(!session.id.is_nil());
@@ -186,9 +192,10 @@ async fn test_create_session_persists_to_database(#[future] test_db: DirectConne
     let db = test_db.await;
     let user = create_test_user_raw(&db, "test@test.com", "testuser");
 
-    let session = poker_session::do_create_session(&db, user.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let session =
+        poker_session::do_create_session(&db, &test_config(), user.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // Verify we can retrieve the session from the database
     let retrieved = get_session_by_id(&db, session.id);
@@ -220,12 +227,13 @@ async fn test_get_sessions_multiple(#[future] test_db: DirectConnectionTestDb) {
         let session_req = CreatePokerSessionRequest {
             session_date: format!("2024-01-{:02}", i),
             duration_minutes: 60 * i,
-            buy_in_amount: 100.0,
+            buy_in_amount: Some(100.0),
             rebuy_amount: None,
-            cash_out_amount: 150.0,
+            cash_out_amount: Some(150.0),
             notes: Some(format!("Session {}", i)),
+            ..Default::default()
         };
-        poker_session::do_create_session(&db, user.id, session_req)
+        poker_session::do_create_session(&db, &test_config(), user.id, session_req)
             .await
             .expect("Failed to create session");
     }
@@ -252,12 +260,13 @@ async fn test_get_sessions_user_isolation(#[future] test_db: DirectConnectionTes
     let session_req_a = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: None,
-        cash_out_amount: 200.0,
+        cash_out_amount: Some(200.0),
         notes: Some("User A session".to_string()),
+        ..Default::default()
     };
-    poker_session::do_create_session(&db, user_a.id, session_req_a)
+    poker_session::do_create_session(&db, &test_config(), user_a.id, session_req_a)
         .await
         .expect("Failed to create session");
 
@@ -265,12 +274,13 @@ async fn test_get_sessions_user_isolation(#[future] test_db: DirectConnectionTes
     let session_req_b = CreatePokerSessionRequest {
         session_date: "2024-01-16".to_string(),
         duration_minutes: 180,
-        buy_in_amount: 200.0,
+        buy_in_amount: Some(200.0),
         rebuy_amount: Some(50.0),
-        cash_out_amount: 300.0,
+        cash_out_amount: Some(300.0),
         notes: Some("User B session".to_string()),
+        ..Default::default()
     };
-    poker_session::do_create_session(&db, user_b.id, session_req_b)
+    poker_session::do_create_session(&db, &test_config(), user_b.id, session_req_b)
         .await
         .expect("Failed to create session");
 
@@ -294,9 +304,10 @@ async fn test_create_session_assigns_correct_user(#[future] test_db: DirectConne
     let user_b = create_test_user_raw(&db, "userb@test.com", "userb");
 
     // Create session for user A
-    let session = poker_session::do_create_session(&db, user_a.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let session =
+        poker_session::do_create_session(&db, &test_config(), user_a.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // Session should belong to user A, not user B
     assert_eq!(session.user_id, user_a.id);
@@ -313,23 +324,24 @@ async fn test_profit_calculation_positive(#[future] test_db: DirectConnectionTes
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: None,
-        cash_out_amount: 200.0,
+        cash_out_amount: Some(200.0),
         notes: None,
+        ..Default::default()
     };
 
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
-    let profit = calculate_profit(
+    let profit = calculate_profit_decimal(
         &session.buy_in_amount,
         &session.rebuy_amount,
         &session.cash_out_amount,
     );
 
-    assert!((profit - 100.0).abs() < 0.01);
+    assert_eq!(profit, BigDecimal::from(100));
 }
 
 #[rstest]
@@ -342,23 +354,24 @@ async fn test_profit_calculation_negative(#[future] test_db: DirectConnectionTes
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 200.0,
+        buy_in_amount: Some(200.0),
         rebuy_amount: Some(100.0),
-        cash_out_amount: 150.0,
+        cash_out_amount: Some(150.0),
         notes: None,
+        ..Default::default()
     };
 
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
-    let profit = calculate_profit(
+    let profit = calculate_profit_decimal(
         &session.buy_in_amount,
         &session.rebuy_amount,
         &session.cash_out_amount,
     );
 
-    assert!((profit - (-150.0)).abs() < 0.01);
+    assert_eq!(profit, BigDecimal::from(-150));
 }
 
 #[rstest]
@@ -371,23 +384,24 @@ async fn test_profit_calculation_break_even(#[future] test_db: DirectConnectionT
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: None,
-        cash_out_amount: 100.0,
+        cash_out_amount: Some(100.0),
         notes: None,
+        ..Default::default()
     };
 
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
-    let profit = calculate_profit(
+    let profit = calculate_profit_decimal(
         &session.buy_in_amount,
         &session.rebuy_amount,
         &session.cash_out_amount,
     );
 
-    assert!((profit - 0.0).abs() < 0.01);
+    assert_eq!(profit, BigDecimal::from(0));
 }
 
 #[rstest]
@@ -400,23 +414,24 @@ async fn test_profit_calculation_with_rebuy(#[future] test_db: DirectConnectionT
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: Some(50.0),
-        cash_out_amount: 250.0,
+        cash_out_amount: Some(250.0),
         notes: None,
+        ..Default::default()
     };
 
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
-    let profit = calculate_profit(
+    let profit = calculate_profit_decimal(
         &session.buy_in_amount,
         &session.rebuy_amount,
         &session.cash_out_amount,
     );
 
-    assert!((profit - 100.0).abs() < 0.01);
+    assert_eq!(profit, BigDecimal::from(100));
 }
 
 #[rstest]
@@ -429,23 +444,24 @@ async fn test_profit_calculation_decimal_precision(#[future] test_db: DirectConn
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 99.99,
+        buy_in_amount: Some(99.99),
         rebuy_amount: Some(50.01),
-        cash_out_amount: 175.50,
+        cash_out_amount: Some(175.50),
         notes: None,
+        ..Default::default()
     };
 
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
-    let profit = calculate_profit(
+    let profit = calculate_profit_decimal(
         &session.buy_in_amount,
         &session.rebuy_amount,
         &session.cash_out_amount,
     );
 
-    assert!((profit - 25.50).abs() < 0.01);
+    assert_eq!(profit, "25.50".parse::<BigDecimal>().unwrap());
 }
 
 #[rstest]
@@ -457,13 +473,14 @@ async fn test_amounts_stored_correctly(#[future] test_db: DirectConnectionTestDb
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 123.45,
+        buy_in_amount: Some(123.45),
         rebuy_amount: Some(67.89),
-        cash_out_amount: 234.56,
+        cash_out_amount: Some(234.56),
         notes: None,
+        ..Default::default()
     };
 
-    let session = poker_session::do_create_session(&db, user.id, session_req)
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
@@ -484,9 +501,10 @@ async fn test_get_session_success(#[future] test_db: DirectConnectionTestDb) {
     let user = create_test_user_raw(&db, "test@test.com", "testuser");
 
     // Create a session
-    let created = poker_session::do_create_session(&db, user.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let created =
+        poker_session::do_create_session(&db, &test_config(), user.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // Get the session
     let retrieved =
@@ -518,9 +536,10 @@ async fn test_get_session_wrong_user(#[future] test_db: DirectConnectionTestDb)
     let user_b = create_test_user_raw(&db, "userb@test.com", "userb");
 
     // Create a session for user A
-    let session = poker_session::do_create_session(&db, user_a.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let session =
+        poker_session::do_create_session(&db, &test_config(), user_a.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // User B tries to get user A's session - should fail with NotFound
     let result = poker_session::do_get_session(&db, session.id, user_b.id);
@@ -535,9 +554,10 @@ async fn test_update_session_all_fields(#[future] test_db: DirectConnectionTestD
     let user = create_test_user_raw(&db, "test@test.com", "testuser");
 
     // Create a session
-    let created = poker_session::do_create_session(&db, user.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let created =
+        poker_session::do_create_session(&db, &test_config(), user.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // Update all fields
     let update_req = UpdatePokerSessionRequest {
@@ -547,10 +567,12 @@ async fn test_update_session_all_fields(#[future] test_db: DirectConnectionTestD
         rebuy_amount: Some(200.0),
         cash_out_amount: Some(1000.0),
         notes: Some("Updated notes".to_string()),
+        ..Default::default()
     };
 
-    let updated = poker_session::do_update_session(&db, created.id, user.id, update_req)
-        .expect("Failed to update session");
+    let updated =
+        poker_session::do_update_session(&db, &test_config(), created.id, user.id, update_req)
+            .expect("Failed to update session");
 
     assert_eq!(updated.id, created.id);
     assert_eq!(updated.duration_minutes, 240);
@@ -568,12 +590,13 @@ async fn test_update_session_partial(#[future] test_db: DirectConnectionTestDb)
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: Some(50.0),
-        cash_out_amount: 200.0,
+        cash_out_amount: Some(200.0),
         notes: Some("Original notes".to_string()),
+        ..Default::default()
     };
-    let created = poker_session::do_create_session(&db, user.id, session_req)
+    let created = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
@@ -585,10 +608,12 @@ async fn test_update_session_partial(#[future] test_db: DirectConnectionTestDb)
         rebuy_amount: None,
         cash_out_amount: None,
         notes: None, // Keep original notes
+        ..Default::default()
     };
 
-    let updated = poker_session::do_update_session(&db, created.id, user.id, update_req)
-        .expect("Failed to update session");
+    let updated =
+        poker_session::do_update_session(&db, &test_config(), created.id, user.id, update_req)
+            .expect("Failed to update session");
 
     // Duration should be updated
     assert_eq!(updated.duration_minutes, 180);
@@ -611,9 +636,11 @@ async fn test_update_session_not_found(#[future] test_db: DirectConnectionTestDb
         rebuy_amount: None,
         cash_out_amount: None,
         notes: None,
+        ..Default::default()
     };
 
-    let result = poker_session::do_update_session(&db, fake_session_id, user.id, update_req);
+    let result =
+        poker_session::do_update_session(&db, &test_config(), fake_session_id, user.id, update_req);
 
     assert!(matches!(result, Err(UpdateSessionError::NotFound)));
 }
@@ -627,9 +654,10 @@ async fn test_update_session_wrong_user(#[future] test_db: DirectConnectionTestD
     let user_b = create_test_user_raw(&db, "userb@test.com", "userb");
 
     // Create a session for user A
-    let session = poker_session::do_create_session(&db, user_a.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let session =
+        poker_session::do_create_session(&db, &test_config(), user_a.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // User B tries to update user A's session
     let update_req = UpdatePokerSessionRequest {
@@ -639,9 +667,11 @@ async fn test_update_session_wrong_user(#[future] test_db: DirectConnectionTestD
         rebuy_amount: None,
         cash_out_amount: None,
         notes: None,
+        ..Default::default()
     };
 
-    let result = poker_session::do_update_session(&db, session.id, user_b.id, update_req);
+    let result =
+        poker_session::do_update_session(&db, &test_config(), session.id, user_b.id, update_req);
 
     assert!(matches!(result, Err(UpdateSessionError::NotFound)));
 
@@ -657,9 +687,10 @@ async fn test_update_session_invalid_date(#[future] test_db: DirectConnectionTes
     let db = test_db.await;
     let user = create_test_user_raw(&db, "test@test.com", "testuser");
 
-    let session = poker_session::do_create_session(&db, user.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let session =
+        poker_session::do_create_session(&db, &test_config(), user.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // Try to update with invalid date format
     let update_req = UpdatePokerSessionRequest {
@@ -669,9 +700,11 @@ async fn test_update_session_invalid_date(#[future] test_db: DirectConnectionTes
         rebuy_amount: None,
         cash_out_amount: None,
         notes: None,
+        ..Default::default()
     };
 
-    let result = poker_session::do_update_session(&db, session.id, user.id, update_req);
+    let result =
+        poker_session::do_update_session(&db, &test_config(), session.id, user.id, update_req);
 
     assert!(matches!(result, Err(UpdateSessionError::InvalidDateFormat)));
 }
@@ -683,9 +716,10 @@ async fn test_delete_session_success(#[future] test_db: DirectConnectionTestDb)
     let user = create_test_user_raw(&db, "test@test.com", "testuser");
 
     // Create a session
-    let session = poker_session::do_create_session(&db, user.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let session =
+        poker_session::do_create_session(&db, &test_config(), user.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // Delete the session
     poker_session::do_delete_session(&db, session.id, user.id).expect("Failed to delete session");
@@ -716,9 +750,10 @@ async fn test_delete_session_wrong_user(#[future] test_db: DirectConnectionTestD
     let user_b = create_test_user_raw(&db, "userb@test.com", "userb");
 
     // Create a session for user A
-    let session = poker_session::do_create_session(&db, user_a.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let session =
+        poker_session::do_create_session(&db, &test_config(), user_a.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     // User B tries to delete user A's session
     let result = poker_session::do_delete_session(&db, session.id, user_b.id);
@@ -737,9 +772,10 @@ async fn test_delete_session_idempotent(#[future] test_db: DirectConnectionTestD
     let user = create_test_user_raw(&db, "test@test.com", "testuser");
 
     // Create and delete a session
-    let session = poker_session::do_create_session(&db, user.id, default_session_request())
-        .await
-        .expect("Failed to create session");
+    let session =
+        poker_session::do_create_session(&db, &test_config(), user.id, default_session_request())
+            .await
+            .expect("Failed to create session");
 
     poker_session::do_delete_session(&db, session.id, user.id)
         .expect("First delete should succeed");
@@ -768,13 +804,14 @@ async fn test_create_session_invalid_date_various_formats(
     let session_req = CreatePokerSessionRequest {
         session_date: invalid_date.to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: None,
-        cash_out_amount: 150.0,
+        cash_out_amount: Some(150.0),
         notes: None,
+        ..Default::default()
     };
 
-    let result = poker_session::do_create_session(&db, user.id, session_req).await;
+    let result = poker_session::do_create_session(&db, &test_config(), user.id, session_req).await;
     assert!(
         matches!(result, Err(CreateSessionError::InvalidDateFormat(_))),
         "Expected InvalidDateFormat for date: {}",
@@ -792,13 +829,14 @@ async fn test_create_session_valid_date_formats(#[future] test_db: DirectConnect
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: None,
-        cash_out_amount: 150.0,
+        cash_out_amount: Some(150.0),
         notes: None,
+        ..Default::default()
     };
 
-    let result = poker_session::do_create_session(&db, user.id, session_req).await;
+    let result = poker_session::do_create_session(&db, &test_config(), user.id, session_req).await;
     assert!(result.is_ok());
 }
 
@@ -821,13 +859,15 @@ async fn test_create_session_boundary_dates(#[future] test_db: DirectConnectionT
         let session_req = CreatePokerSessionRequest {
             session_date: date.to_string(),
             duration_minutes: 60,
-            buy_in_amount: 100.0,
+            buy_in_amount: Some(100.0),
             rebuy_amount: None,
-            cash_out_amount: 100.0,
+            cash_out_amount: Some(100.0),
             notes: None,
+            ..Default::default()
         };
 
-        let result = poker_session::do_create_session(&db, user.id, session_req).await;
+        let result =
+            poker_session::do_create_session(&db, &test_config(), user.id, session_req).await;
         assert!(result.is_ok(), "Date {} should be valid", date);
     }
 }
@@ -842,12 +882,13 @@ async fn test_update_preserves_unmodified_fields(#[future] test_db: DirectConnec
     let session_req = CreatePokerSessionRequest {
         session_date: "2024-01-15".to_string(),
         duration_minutes: 120,
-        buy_in_amount: 100.0,
+        buy_in_amount: Some(100.0),
         rebuy_amount: Some(50.0),
-        cash_out_amount: 200.0,
+        cash_out_amount: Some(200.0),
         notes: Some("Original notes".to_string()),
+        ..Default::default()
     };
-    let created = poker_session::do_create_session(&db, user.id, session_req)
+    let created = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
         .await
         .expect("Failed to create session");
 
@@ -859,10 +900,12 @@ async fn test_update_preserves_unmodified_fields(#[future] test_db: DirectConnec
         rebuy_amount: None,
         cash_out_amount: None,
         notes: None,
+        ..Default::default()
     };
 
-    let updated = poker_session::do_update_session(&db, created.id, user.id, update_req)
-        .expect("Failed to update session");
+    let updated =
+        poker_session::do_update_session(&db, &test_config(), created.id, user.id, update_req)
+            .expect("Failed to update session");
 
     // All original values should be preserved
     assert_eq!(updated.duration_minutes, 120);
@@ -874,3 +917,209 @@ async fn test_update_preserves_unmodified_fields(#[future] test_db: DirectConnec
     );
     assert_eq!(updated.notes, Some("Original notes".to_string()));
 }
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_rebuy_without_buy_in_rejected(
+    #[future] test_db: DirectConnectionTestDb,
+) {
+    let db = test_db.await;
+    let user = create_test_user_raw(&db, "test@test.com", "testuser");
+
+    let session_req = CreatePokerSessionRequest {
+        session_date: "2024-01-15".to_string(),
+        duration_minutes: 120,
+        buy_in_amount: Some(0.0),
+        rebuy_amount: Some(100.0),
+        cash_out_amount: Some(200.0),
+        notes: None,
+        ..Default::default()
+    };
+
+    let result = poker_session::do_create_session(&db, &test_config(), user.id, session_req).await;
+
+    assert!(matches!(result, Err(CreateSessionError::RebuyWithoutBuyIn)));
+    assert!(get_sessions_for_user(&db, user.id).is_empty());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_rebuy_without_buy_in_allowed_when_disabled(
+    #[future] test_db: DirectConnectionTestDb,
+) {
+    let db = test_db.await;
+    let user = create_test_user_raw(&db, "test@test.com", "testuser");
+
+    let mut config = test_config();
+    config.require_buy_in_for_rebuy = false;
+
+    let session_req = CreatePokerSessionRequest {
+        session_date: "2024-01-15".to_string(),
+        duration_minutes: 120,
+        buy_in_amount: Some(0.0),
+        rebuy_amount: Some(100.0),
+        cash_out_amount: Some(200.0),
+        notes: None,
+        ..Default::default()
+    };
+
+    let result = poker_session::do_create_session(&db, &config, user.id, session_req).await;
+
+    assert!(result.is_ok());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_rebuy_with_buy_in_accepted(#[future] test_db: DirectConnectionTestDb) {
+    let db = test_db.await;
+    let user = create_test_user_raw(&db, "test@test.com", "testuser");
+
+    let session_req = CreatePokerSessionRequest {
+        session_date: "2024-01-15".to_string(),
+        duration_minutes: 120,
+        buy_in_amount: Some(100.0),
+        rebuy_amount: Some(100.0),
+        cash_out_amount: Some(200.0),
+        notes: None,
+        ..Default::default()
+    };
+
+    let result = poker_session::do_create_session(&db, &test_config(), user.id, session_req).await;
+
+    assert!(result.is_ok());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_update_session_rebuy_without_buy_in_rejected(
+    #[future] test_db: DirectConnectionTestDb,
+) {
+    let db = test_db.await;
+    let user = create_test_user_raw(&db, "test@test.com", "testuser");
+
+    let created =
+        poker_session::do_create_session(&db, &test_config(), user.id, default_session_request())
+            .await
+            .expect("Failed to create session");
+
+    // Zeroing the buy-in while the existing rebuy stays in place is invalid
+    let update_req = UpdatePokerSessionRequest {
+        session_date: None,
+        duration_minutes: None,
+        buy_in_amount: Some(0.0),
+        rebuy_amount: None,
+        cash_out_amount: None,
+        notes: None,
+        ..Default::default()
+    };
+
+    let result =
+        poker_session::do_update_session(&db, &test_config(), created.id, user.id, update_req);
+
+    assert!(matches!(result, Err(UpdateSessionError::RebuyWithoutBuyIn)));
+    let unchanged = get_session_by_id(&db, created.id).expect("Session should still exist");
+    assert_eq!(unchanged.buy_in_amount, created.buy_in_amount);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_update_session_rebuy_without_buy_in_allowed_when_disabled(
+    #[future] test_db: DirectConnectionTestDb,
+) {
+    let db = test_db.await;
+    let user = create_test_user_raw(&db, "test@test.com", "testuser");
+
+    let mut config = test_config();
+    config.require_buy_in_for_rebuy = false;
+
+    let created =
+        poker_session::do_create_session(&db, &config, user.id, default_session_request())
+            .await
+            .expect("Failed to create session");
+
+    let update_req = UpdatePokerSessionRequest {
+        buy_in_amount: Some(0.0),
+        ..Default::default()
+    };
+
+    let updated = poker_session::do_update_session(&db, &config, created.id, user.id, update_req)
+        .expect("Update should succeed");
+
+    assert_eq!(updated.buy_in_amount, BigDecimal::from(0));
+    assert_eq!(updated.rebuy_amount, created.rebuy_amount);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_create_session_with_cents_stores_exact_decimal(
+    #[future] test_db: DirectConnectionTestDb,
+) {
+    let db = test_db.await;
+    let user = create_test_user_raw(&db, "cents@example.com", "cents");
+
+    let session_req = CreatePokerSessionRequest {
+        session_date: "2024-01-15".to_string(),
+        duration_minutes: 120,
+        buy_in_cents: Some(10033),
+        cash_out_cents: Some(15000),
+        ..Default::default()
+    };
+
+    let session = poker_session::do_create_session(&db, &test_config(), user.id, session_req)
+        .await
+        .expect("Failed to create session");
+
+    let stored = get_sessions_for_user(&db, user.id);
+    assert_eq!(stored.len(), 1);
+    assert_eq!(
+        stored[0].buy_in_amount,
+        "100.33".parse::<BigDecimal>().unwrap()
+    );
+    assert_eq!(session.rebuy_amount, BigDecimal::from(0));
+    assert_eq!(session.profit(), "49.67".parse::<BigDecimal>().unwrap());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_notes_quota_holds_under_concurrent_creates(
+    #[future] test_db: DirectConnectionTestDb,
+) {
+    let db = test_db.await;
+    let user = create_test_user_raw(&db, "quota@example.com", "quota");
+
+    let mut config = test_config();
+    config.max_total_notes_bytes = Some(20);
+
+    // Room for two of these; the rest must be refused even when they race
+    let attempts = 8;
+    let barrier = std::sync::Barrier::new(attempts);
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..attempts)
+            .map(|_| {
+                scope.spawn(|| {
+                    let session_req = CreatePokerSessionRequest {
+                        notes: Some("0123456789".to_string()),
+                        ..default_session_request()
+                    };
+                    barrier.wait();
+                    futures::executor::block_on(poker_session::do_create_session(
+                        &db,
+                        &config,
+                        user.id,
+                        session_req,
+                    ))
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 2);
+    assert!(
+        results
+            .iter()
+            .filter_map(|r| r.as_ref().err())
+            .all(|e| matches!(e, CreateSessionError::NotesQuotaExceeded(_)))
+    );
+    assert_eq!(get_sessions_for_user(&db, user.id).len(), 2);
+}
@@ -59,7 +59,7 @@ export interface PokerSession {
   notes: string | null;
   created_at: string;
   updated_at: string;
-  profit?: number;
+  profit?: string;
 }
 
 export interface AuthResponse {
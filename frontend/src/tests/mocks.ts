@@ -24,7 +24,7 @@ export const mockSessions: PokerSession[] = [
     notes: 'Good session',
     created_at: '2024-01-15T00:00:00Z',
     updated_at: '2024-01-15T00:00:00Z',
-    profit: 50,
+    profit: '50.00',
   },
   {
     id: 'session-2',
@@ -37,7 +37,7 @@ export const mockSessions: PokerSession[] = [
     notes: 'Tough table',
     created_at: '2024-01-20T00:00:00Z',
     updated_at: '2024-01-20T00:00:00Z',
-    profit: -70,
+    profit: '-70.00',
   },
   {
     id: 'session-3',
@@ -50,7 +50,7 @@ export const mockSessions: PokerSession[] = [
     notes: null,
     created_at: '2024-01-25T00:00:00Z',
     updated_at: '2024-01-25T00:00:00Z',
-    profit: 120,
+    profit: '120.00',
   },
 ];
 